@@ -0,0 +1,44 @@
+-- Add the instrument_class column to ReferenceData. Existing records are classified from their CFI
+-- code and attributes, following the same rules as `ReferenceData::classify`.
+
+ALTER TABLE ReferenceData ADD COLUMN instrument_class CHAR(4) NOT NULL DEFAULT 'OTHR';
+
+UPDATE ReferenceData SET instrument_class = CASE
+    WHEN substr(cfi, 1, 1) IN ('O', 'H', 'F', 'S', 'J', 'K') AND (
+        is_commodities_derivative
+        OR substr(cfi, 1, 2) IN ('FC', 'ST', 'HT', 'JT', 'KT')
+        OR (substr(cfi, 1, 2) IN ('OC', 'OP') AND substr(cfi, 4, 1) = 'T')
+        OR EXISTS (
+            SELECT 1 FROM DerivativeAttributes
+            JOIN AssetClassSpecificAttributes
+                ON AssetClassSpecificAttributes.id = DerivativeAttributes.asset_class_specific_attributes_id
+            WHERE DerivativeAttributes.id = ReferenceData.derivative_attributes_id
+                AND AssetClassSpecificAttributes.commodity_attributes_id IS NOT NULL
+        )
+    ) THEN 'CMDT'
+    WHEN substr(cfi, 1, 2) IN ('ED', 'DD', 'RD') THEN 'DPRC'
+    WHEN substr(cfi, 1, 2) IN ('EY', 'DS', 'DE') THEN 'STRC'
+    WHEN substr(cfi, 1, 1) = 'E' THEN 'EQTY'
+    WHEN substr(cfi, 1, 2) = 'CE' THEN 'ETFS'
+    WHEN substr(cfi, 1, 1) = 'C' THEN 'FUND'
+    WHEN substr(cfi, 1, 1) = 'D' THEN 'BOND'
+    WHEN substr(cfi, 1, 1) = 'R' THEN 'ENTL'
+    WHEN substr(cfi, 1, 1) = 'F' THEN 'FUTR'
+    WHEN substr(cfi, 1, 1) IN ('O', 'H') THEN 'OPTN'
+    WHEN substr(cfi, 1, 1) = 'S' THEN 'SWAP'
+    WHEN substr(cfi, 1, 2) = 'JF' THEN 'FXFW'
+    WHEN substr(cfi, 1, 1) = 'J' THEN 'FWRD'
+    WHEN debt_attributes_id IS NOT NULL THEN 'BOND'
+    WHEN substr(cfi, 1, 1) = 'K' AND EXISTS (
+        SELECT 1 FROM DerivativeAttributes
+        JOIN AssetClassSpecificAttributes
+            ON AssetClassSpecificAttributes.id = DerivativeAttributes.asset_class_specific_attributes_id
+        JOIN FxDerivativeAttributes
+            ON FxDerivativeAttributes.id = AssetClassSpecificAttributes.fx_attributes_id
+        WHERE DerivativeAttributes.id = ReferenceData.derivative_attributes_id
+            AND FxDerivativeAttributes.notional_currency_2 IS NOT NULL
+    ) THEN 'FXFW'
+    ELSE 'OTHR'
+END;
+
+CREATE INDEX IF NOT EXISTS idx_refdata_class ON ReferenceData(instrument_class);
@@ -0,0 +1,21 @@
+-- Add the commodity sub-product codes that were previously missing from the SubProduct table.
+
+INSERT OR IGNORE INTO SubProduct (code, description) VALUES
+    -- Agricultural
+    ('GROS', 'GrainsAndOilSeeds'),
+    ('SOFT', 'Softs'),
+    ('POTA', 'Potato'),
+    ('OOLI', 'OliveOil'),
+    ('DIRY', 'Dairy'),
+    ('FRST', 'Forestry'),
+    ('SEAF', 'Seafood'),
+    ('LSTK', 'Livestock'),
+    ('GRIN', 'Grain'),
+    -- Environmental
+    ('EMIS', 'Emissions'),
+    ('WTHR', 'Weather'),
+    ('CRBR', 'CarbonRelated'),
+    -- Freight
+    ('WETF', 'Wet'),
+    ('DRYF', 'Dry'),
+    ('CSHP', 'ContainerShips');
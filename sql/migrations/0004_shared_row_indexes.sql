@@ -0,0 +1,10 @@
+-- Index the values of the Term, FloatingRate and FirdsIndex tables, whose rows are now shared
+-- between records, and the columns that reference them. Existing duplicate rows can be merged with
+-- `dedupe_shared_rows`.
+
+CREATE INDEX IF NOT EXISTS idx_term_value ON Term(number, unit);
+CREATE INDEX IF NOT EXISTS idx_floating_rate_value ON FloatingRate(name, term_id);
+CREATE INDEX IF NOT EXISTS idx_index_value ON FirdsIndex(isin, name_id);
+CREATE INDEX IF NOT EXISTS idx_ir_floating_rate ON InterestRate(floating_rate_id);
+CREATE INDEX IF NOT EXISTS idx_irda_reference_rate ON InterestRateDerivativeAttributes(reference_rate_id);
+CREATE INDEX IF NOT EXISTS idx_underlying_index ON UnderlyingSingle(index_id);
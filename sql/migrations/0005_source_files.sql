@@ -0,0 +1,30 @@
+-- Record the FIRDS file and type of record from which each ReferenceData row was loaded. Rows
+-- loaded before this migration have no source file.
+
+CREATE TABLE IF NOT EXISTS RecordType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO RecordType (code, label) VALUES
+    ('FULL', 'Full'),
+    ('NEWR', 'New'),
+    ('MODF', 'Modified'),
+    ('TERM', 'Terminated');
+
+CREATE TABLE IF NOT EXISTS SourceFile (
+    id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    file_type TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    file_id TEXT,
+    download_link TEXT,
+    publication_timestamp TIMESTAMP,
+    checksum TEXT,
+    UNIQUE (source, file_name)
+);
+
+ALTER TABLE ReferenceData ADD COLUMN source_file_id INTEGER REFERENCES SourceFile(id);
+ALTER TABLE ReferenceData ADD COLUMN record_type CHAR(4) REFERENCES RecordType(code);
+
+CREATE INDEX IF NOT EXISTS idx_refdata_source_file ON ReferenceData(source_file_id);
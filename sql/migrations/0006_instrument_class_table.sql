@@ -0,0 +1,38 @@
+-- Add the InstrumentClass code table, and triggers which check that ReferenceData.instrument_class
+-- is one of its codes. Existing rows are not checked, as their classes were assigned by
+-- `ReferenceData::classify`, and ReferenceData does not need to be rebuilt.
+
+CREATE TABLE IF NOT EXISTS InstrumentClass (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO InstrumentClass (code, label) VALUES
+    ('EQTY', 'Equity'),
+    ('DPRC', 'DepositoryReceipt'),
+    ('ETFS', 'Etf'),
+    ('FUND', 'Fund'),
+    ('BOND', 'Bond'),
+    ('STRC', 'StructuredProduct'),
+    ('ENTL', 'Entitlement'),
+    ('FUTR', 'Future'),
+    ('OPTN', 'Option'),
+    ('SWAP', 'Swap'),
+    ('FXFW', 'FxForward'),
+    ('FWRD', 'Forward'),
+    ('CMDT', 'CommodityDerivative'),
+    ('OTHR', 'Other');
+
+CREATE TRIGGER IF NOT EXISTS refdata_instrument_class_insert
+BEFORE INSERT ON ReferenceData
+WHEN NEW.instrument_class NOT IN (SELECT code FROM InstrumentClass)
+BEGIN
+    SELECT RAISE(ABORT, 'Unknown instrument_class');
+END;
+
+CREATE TRIGGER IF NOT EXISTS refdata_instrument_class_update
+BEFORE UPDATE OF instrument_class ON ReferenceData
+WHEN NEW.instrument_class NOT IN (SELECT code FROM InstrumentClass)
+BEGIN
+    SELECT RAISE(ABORT, 'Unknown instrument_class');
+END;
@@ -1,3 +1,7 @@
+-- The latest version of the SQLite schema, used to create new databases. When changing this file, add
+-- a migration to sql/migrations that makes the same change to existing databases and increment
+-- SCHEMA_VERSION in src/sql/migrate.rs.
+
 -- PRODUCT ENUMS
 
 CREATE TABLE IF NOT EXISTS BaseProduct (
@@ -25,6 +29,16 @@ CREATE TABLE IF NOT EXISTS SubProduct (
     description TEXT
 );
 INSERT OR IGNORE INTO SubProduct (code, description) VALUES
+    -- Agricultural
+    ('GROS', 'GrainsAndOilSeeds'),
+    ('SOFT', 'Softs'),
+    ('POTA', 'Potato'),
+    ('OOLI', 'OliveOil'),
+    ('DIRY', 'Dairy'),
+    ('FRST', 'Forestry'),
+    ('SEAF', 'Seafood'),
+    ('LSTK', 'Livestock'),
+    ('GRIN', 'Grain'),
     -- Energy
     ('ELEC', 'Electricity'),
     ('NGAS', 'NaturalGas'),
@@ -34,6 +48,10 @@ INSERT OR IGNORE INTO SubProduct (code, description) VALUES
     ('RNNG', 'RenewableEnergy'),
     ('LGHT', 'LightEnds'),
     ('DIST', 'Distillates'),
+    -- Environmental
+    ('EMIS', 'Emissions'),
+    ('WTHR', 'Weather'),
+    ('CRBR', 'CarbonRelated'),
     -- Fertilizer
     ('AMMO', 'Ammonia'),
     ('DAPH', 'Dap'),
@@ -41,6 +59,10 @@ INSERT OR IGNORE INTO SubProduct (code, description) VALUES
     ('SLPH', 'Sulphur'),
     ('UREA', 'Urea'),
     ('UAAN', 'Uan'),
+    -- Freight
+    ('WETF', 'Wet'),
+    ('DRYF', 'Dry'),
+    ('CSHP', 'ContainerShips'),
     -- Industrial
     ('CSTR', 'Construction'),
     ('MFTG', 'Manufacturing'),
@@ -298,6 +320,55 @@ INSERT OR IGNORE INTO StrikePriceType (code, label) VALUES
     ('BASIS_POINTS', 'BasisPoints'),
     ('NO_PRICE', 'NoPrice');
 
+-- The broad classes of instrument assigned by `ReferenceData::classify`.
+CREATE TABLE IF NOT EXISTS InstrumentClass (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO InstrumentClass (code, label) VALUES
+    ('EQTY', 'Equity'),
+    ('DPRC', 'DepositoryReceipt'),
+    ('ETFS', 'Etf'),
+    ('FUND', 'Fund'),
+    ('BOND', 'Bond'),
+    ('STRC', 'StructuredProduct'),
+    ('ENTL', 'Entitlement'),
+    ('FUTR', 'Future'),
+    ('OPTN', 'Option'),
+    ('SWAP', 'Swap'),
+    ('FXFW', 'FxForward'),
+    ('FWRD', 'Forward'),
+    ('CMDT', 'CommodityDerivative'),
+    ('OTHR', 'Other');
+
+-- PROVENANCE
+
+-- The type of record in a FIRDS file from which a ReferenceData row was created.
+CREATE TABLE IF NOT EXISTS RecordType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO RecordType (code, label) VALUES
+    ('FULL', 'Full'),
+    ('NEWR', 'New'),
+    ('MODF', 'Modified'),
+    ('TERM', 'Terminated');
+
+-- A published FIRDS file from which records were loaded.
+CREATE TABLE IF NOT EXISTS SourceFile (
+    id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    file_type TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    file_id TEXT,
+    download_link TEXT,
+    publication_timestamp TIMESTAMP,
+    checksum TEXT,
+    UNIQUE (source, file_name)
+);
+
 -- MODEL
 
 CREATE TABLE IF NOT EXISTS Term (
@@ -496,17 +567,52 @@ CREATE TABLE IF NOT EXISTS ReferenceData (
     technical_attributes_id INTEGER,
     debt_attributes_id INTEGER,
     derivative_attributes_id INTEGER,
+    -- Derived from the other fields using `ReferenceData::classify`, for convenient filtering.
+    instrument_class CHAR(4) NOT NULL,
     -- Below fields are not present in FIRDS data but are necessary for tracking modifications.
     latest_record BOOLEAN NOT NULL,
     valid_from DATE NOT NULL,
     valid_to DATE,
+    -- The file and type of record from which the row was loaded, if known.
+    source_file_id INTEGER,
+    record_type CHAR(4),
     FOREIGN KEY (trading_venue_attrs_id) REFERENCES TradingVenueAttributes(id),
     FOREIGN KEY (technical_attributes_id) REFERENCES TechnicalAttributes(id),
     FOREIGN KEY (debt_attributes_id) REFERENCES DebtAttributes(id),
-    FOREIGN KEY (derivative_attributes_id) REFERENCES DerivativeAttributes(id)
-);
+    FOREIGN KEY (derivative_attributes_id) REFERENCES DerivativeAttributes(id),
+    FOREIGN KEY (source_file_id) REFERENCES SourceFile(id),
+    FOREIGN KEY (record_type) REFERENCES RecordType(code)
+);
+
+-- ReferenceData.instrument_class must be one of the codes in InstrumentClass. This is enforced by
+-- triggers rather than a foreign key, as SQLite can only add a foreign key to an existing table by
+-- rebuilding it.
+CREATE TRIGGER IF NOT EXISTS refdata_instrument_class_insert
+BEFORE INSERT ON ReferenceData
+WHEN NEW.instrument_class NOT IN (SELECT code FROM InstrumentClass)
+BEGIN
+    SELECT RAISE(ABORT, 'Unknown instrument_class');
+END;
+
+CREATE TRIGGER IF NOT EXISTS refdata_instrument_class_update
+BEFORE UPDATE OF instrument_class ON ReferenceData
+WHEN NEW.instrument_class NOT IN (SELECT code FROM InstrumentClass)
+BEGIN
+    SELECT RAISE(ABORT, 'Unknown instrument_class');
+END;
 
 CREATE INDEX IF NOT EXISTS idx_refdata_isin ON ReferenceData(isin);
 CREATE INDEX IF NOT EXISTS idx_refdata_tv_id ON ReferenceData(trading_venue_attrs_id);
 CREATE INDEX IF NOT EXISTS idx_refdata_valid_to ON ReferenceData(valid_to);
-CREATE INDEX IF NOT EXISTS idx_tv_mic ON TradingVenueAttributes(trading_venue);
\ No newline at end of file
+CREATE INDEX IF NOT EXISTS idx_refdata_class ON ReferenceData(instrument_class);
+CREATE INDEX IF NOT EXISTS idx_refdata_source_file ON ReferenceData(source_file_id);
+CREATE INDEX IF NOT EXISTS idx_tv_mic ON TradingVenueAttributes(trading_venue);
+-- Term, FloatingRate and FirdsIndex rows are shared between all the records that reference the
+-- same rate. These indexes are used to look up existing rows when inserting, and to find the
+-- instruments that reference a given rate.
+CREATE INDEX IF NOT EXISTS idx_term_value ON Term(number, unit);
+CREATE INDEX IF NOT EXISTS idx_floating_rate_value ON FloatingRate(name, term_id);
+CREATE INDEX IF NOT EXISTS idx_index_value ON FirdsIndex(isin, name_id);
+CREATE INDEX IF NOT EXISTS idx_ir_floating_rate ON InterestRate(floating_rate_id);
+CREATE INDEX IF NOT EXISTS idx_irda_reference_rate ON InterestRateDerivativeAttributes(reference_rate_id);
+CREATE INDEX IF NOT EXISTS idx_underlying_index ON UnderlyingSingle(index_id);
\ No newline at end of file
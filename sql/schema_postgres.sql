@@ -0,0 +1,685 @@
+-- PostgreSQL version of schema.sql. The two files should be kept in sync: the tables, columns and
+-- reference rows are the same, but IDs are identity columns, dates and times use native types and
+-- the shared Term, FloatingRate and FirdsIndex rows are kept unique by constraints (requires
+-- PostgreSQL 15 or later).
+
+-- PRODUCT ENUMS
+
+CREATE TABLE IF NOT EXISTS BaseProduct (
+    code CHAR(4) PRIMARY KEY,
+    description TEXT
+);
+INSERT INTO BaseProduct (code, description) VALUES
+    ('AGRI', 'Agricultural'),
+    ('NRGY', 'Energy'),
+    ('ENVR', 'Environmental'),
+    ('FRGT', 'Freight'),
+    ('FRTL', 'Fertilizer'),
+    ('INDP', 'IndustrialProducts'),
+    ('METL', 'Metals'),
+    ('MCEX', 'MultiCommodityExotic'),
+    ('PAPR', 'Paper'),
+    ('POLY', 'Polypropylene'),
+    ('INFL', 'Inflation'),
+    ('OEST', 'OfficialEconomicStatistics'),
+    ('OTHC', 'OtherC10'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS SubProduct (
+    code CHAR(4) PRIMARY KEY,
+    description TEXT
+);
+INSERT INTO SubProduct (code, description) VALUES
+    -- Agricultural
+    ('GROS', 'GrainsAndOilSeeds'),
+    ('SOFT', 'Softs'),
+    ('POTA', 'Potato'),
+    ('OOLI', 'OliveOil'),
+    ('DIRY', 'Dairy'),
+    ('FRST', 'Forestry'),
+    ('SEAF', 'Seafood'),
+    ('LSTK', 'Livestock'),
+    ('GRIN', 'Grain'),
+    -- Energy
+    ('ELEC', 'Electricity'),
+    ('NGAS', 'NaturalGas'),
+    ('OILP', 'Oil'),
+    ('COAL', 'Coal'),
+    ('INRG', 'InterEnergy'),
+    ('RNNG', 'RenewableEnergy'),
+    ('LGHT', 'LightEnds'),
+    ('DIST', 'Distillates'),
+    -- Environmental
+    ('EMIS', 'Emissions'),
+    ('WTHR', 'Weather'),
+    ('CRBR', 'CarbonRelated'),
+    -- Fertilizer
+    ('AMMO', 'Ammonia'),
+    ('DAPH', 'Dap'),
+    ('PTSH', 'Potash'),
+    ('SLPH', 'Sulphur'),
+    ('UREA', 'Urea'),
+    ('UAAN', 'Uan'),
+    -- Freight
+    ('WETF', 'Wet'),
+    ('DRYF', 'Dry'),
+    ('CSHP', 'ContainerShips'),
+    -- Industrial
+    ('CSTR', 'Construction'),
+    ('MFTG', 'Manufacturing'),
+    -- Metal
+    ('NPRM', 'NonPrecious'),
+    ('PRME', 'Precious'),
+    -- Paper
+    ('CBRD', 'Containerboard'),
+    ('NSPT', 'Newsprint'),
+    ('PULP', 'Pulp'),
+    ('RCVP', 'RecoveredPaper'),
+    -- Polypropylene
+    ('PLST', 'Plastic'),
+    -- Other C10
+    ('DLVR', 'Deliverable'),
+    ('NDLV', 'NonDeliverable')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS FurtherSubProduct (
+    code CHAR(4) PRIMARY KEY,
+    description TEXT
+);
+INSERT INTO FurtherSubProduct (code, description) VALUES
+    -- Grain and oil seeds
+    ('FWHT', 'FeedWheat'),
+    ('SOYB', 'Soybeans'),
+    ('CORN', 'Corn'),
+    ('RPSD', 'Rapeseed'),
+    ('RICE', 'Rice'),
+    -- Softs
+    ('CCOA', 'Cocoa'),
+    ('ROBU', 'RobustaCoffee'),
+    ('WHSG', 'WhiteSugar'),
+    ('BRWN', 'RawSugar'),
+    -- Olive oil
+    ('LAMP', 'Lampante'),
+    -- Grain
+    ('MWHT', 'MillingWheat'),
+    -- Electricity
+    ('BSLD', 'BaseLoad'),
+    ('FITR', 'FinancialTransmissionRights'),
+    ('PKLD', 'PeakLoad'),
+    ('OFFP', 'OffPeak'),
+    -- Natural gas
+    ('GASP', 'Gaspool'),
+    ('LNGG', 'Lng'),
+    ('NBPG', 'Nbp'),
+    ('NCGG', 'Ncg'),
+    ('TTFG', 'Ttf'),
+    -- Oil
+    ('BAKK', 'Bakken'),
+    ('BDSL', 'Biodiesel'),
+    ('BRNT', 'Brent'),
+    ('BRNX', 'BrentNx'),
+    ('CNDA', 'Canadian'),
+    ('COND', 'Condensate'),
+    ('DSEL', 'Diesel'),
+    ('DUBA', 'Dubai'),
+    ('ESPO', 'Espo'),
+    ('ETHA', 'Ethanol'),
+    ('FUEL', 'Fuel'),
+    ('FOIL', 'FuelOil'),
+    ('GOIL', 'Gasoil'),
+    ('GSLN', 'Gasoline'),
+    ('HEAT', 'HeatingOil'),
+    ('JTFL', 'JetFuel'),
+    ('KERO', 'Kerosene'),
+    ('LLSO', 'LightLouisianaSweet'),
+    ('MARS', 'Mars'),
+    ('NAPH', 'Naphtha'),
+    ('NGLO', 'Ngl'),
+    ('TAPI', 'Tapis'),
+    ('URAL', 'Urals'),
+    ('WTIO', 'Wti'),
+    -- Emissions
+    ('CERE', 'Cer'),
+    ('ERUE', 'Eru'),
+    ('EUAE', 'Euae'),
+    ('EUAA', 'Euaa'),
+    -- Wet freight
+    ('TNKR', 'Tankers'),
+    -- Dry Freight
+    ('DBCR', 'DryBulkCarriers'),
+    -- Non-precious metals
+    ('ALUM', 'Aluminium'),
+    ('ALUA', 'AluminiumAlloy'),
+    ('CBLT', 'Cobalt'),
+    ('COPR', 'Copper'),
+    ('IRON', 'IronOre'),
+    ('LEAD', 'Lead'),
+    ('MOLY', 'Molybdenum'),
+    ('NASC', 'Nasaac'),
+    ('NICK', 'Nickel'),
+    ('STEL', 'Steel'),
+    ('TINN', 'Tin'),
+    ('ZINC', 'Zinc'),
+    -- Precious metals
+    ('GOLD', 'Gold'),
+    ('SLVR', 'Silver'),
+    ('PTNM', 'Platinum'),
+    ('PLDM', 'Palladium'),
+
+    -- Used in various sub-products
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+-- ENUMS
+
+CREATE TABLE IF NOT EXISTS TermUnit (
+    code CHAR(8) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO TermUnit (code, label) VALUES
+    ('DAYS', 'Days'),
+    ('WEEK', 'Week'),
+    ('MNTH', 'Month'),
+    ('YEAR', 'Year')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS IndexCode (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO IndexCode (code, label) VALUES
+    ('EONA', 'Eonia'),
+    ('EONS', 'EoniaSwap'),
+    ('EURO', 'Euribor'),
+    ('EUCH', 'EuroSwiss'),
+    ('GCFR', 'GcfRepo'),
+    ('ISDA', 'Isdafix'),
+    ('LIBI', 'Libid'),
+    ('LIBO', 'Libor'),
+    ('MAAA', 'MuniAaa'),
+    ('PFAN', 'Pfandbriefe'),
+    ('TIBO', 'Tibor'),
+    ('STBO', 'Stibor'),
+    ('BBSW', 'Bbsw'),
+    ('JIBA', 'Jibar'),
+    ('BUBO', 'Bubor'),
+    ('CDOR', 'Cdor'),
+    ('CIBO', 'Cibor'),
+    ('MOSP', 'Mosprim'),
+    ('NIBO', 'Nibor'),
+    ('PRBO', 'Pribor'),
+    ('TLBO', 'Telbor'),
+    ('WIBO', 'Wibor'),
+    ('TREA', 'Treasury'),
+    ('SWAP', 'Swap'),
+    ('FUSW', 'FutureSwap')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS IndexName (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    index_code CHAR(4) REFERENCES IndexCode(code),
+    text TEXT,
+    -- Enforce that exactly one of (index_code, text) is non-null
+    CHECK (
+       (index_code IS NOT NULL AND text IS NULL)
+           OR
+       (index_code IS NULL AND text IS NOT NULL)
+    )
+);
+
+CREATE TABLE IF NOT EXISTS DebtSeniority (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO DebtSeniority (code, label) VALUES
+    ('SNDB', 'Senior'),
+    ('MZZD', 'Mezzanine'),
+    ('SBOD', 'Subordinated'),
+    ('JUND', 'Junior')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS OptionType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO OptionType (code, label) VALUES
+    ('PUTO', 'Put'),
+    ('CALL', 'Call'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS OptionExerciseStyle (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO OptionExerciseStyle (code, label) VALUES
+    ('EURO', 'European'),
+    ('AMER', 'American'),
+    ('ASIA', 'Asian'),
+    ('BERM', 'Bermudan'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS DeliveryType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO DeliveryType (code, label) VALUES
+    ('PHYS', 'Physical'),
+    ('CASH', 'Cash'),
+    ('OPTL', 'Optional')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS TransactionType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO TransactionType (code, label) VALUES
+    ('FUTR', 'Futures'),
+    ('OPTN', 'Options'),
+    ('TAPO', 'Tapos'),
+    ('SWAP', 'Swaps'),
+    ('MINI', 'Minis'),
+    ('OTCT', 'OverTheCounter'),
+    ('ORIT', 'Outright'),
+    ('CRCK', 'Crack'),
+    ('DIFF', 'Differential'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS FinalPriceType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO FinalPriceType (code, label) VALUES
+    ('ARGM', 'ArgusMcCloskey'),
+    ('BLTC', 'Baltic'),
+    ('EXOF', 'Exchange'),
+    ('GBCL', 'GlobalCoal'),
+    ('IHSM', 'IHSMarkit'),
+    ('PLAT', 'Platts'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS FxType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO FxType (code, label) VALUES
+    ('FXCR', 'CrossRates'),
+    ('FXEM', 'EmergingMarkets'),
+    ('FXMJ', 'Majors')
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS StrikePriceType (
+    code TEXT PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO StrikePriceType (code, label) VALUES
+    ('MONETARY_VALUE', 'MonetaryValue'),
+    ('PERCENTAGE', 'Percentage'),
+    ('YIELD', 'Yield'),
+    ('BASIS_POINTS', 'BasisPoints'),
+    ('NO_PRICE', 'NoPrice')
+ON CONFLICT DO NOTHING;
+
+-- The broad classes of instrument assigned by `ReferenceData::classify`.
+CREATE TABLE IF NOT EXISTS InstrumentClass (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO InstrumentClass (code, label) VALUES
+    ('EQTY', 'Equity'),
+    ('DPRC', 'DepositoryReceipt'),
+    ('ETFS', 'Etf'),
+    ('FUND', 'Fund'),
+    ('BOND', 'Bond'),
+    ('STRC', 'StructuredProduct'),
+    ('ENTL', 'Entitlement'),
+    ('FUTR', 'Future'),
+    ('OPTN', 'Option'),
+    ('SWAP', 'Swap'),
+    ('FXFW', 'FxForward'),
+    ('FWRD', 'Forward'),
+    ('CMDT', 'CommodityDerivative'),
+    ('OTHR', 'Other')
+ON CONFLICT DO NOTHING;
+
+-- MODEL
+
+CREATE TABLE IF NOT EXISTS Term (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    number INTEGER NOT NULL,
+    unit CHAR(4) NOT NULL, -- maps to TermUnit enum
+    FOREIGN KEY (unit) REFERENCES TermUnit(code),
+    UNIQUE (number, unit)
+);
+
+CREATE TABLE IF NOT EXISTS StrikePrice (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    price_type TEXT NOT NULL,
+    price DOUBLE PRECISION,
+    pending BOOLEAN NOT NULL,
+    currency TEXT,
+    FOREIGN KEY (price_type) REFERENCES StrikePriceType(code)
+);
+
+CREATE TABLE IF NOT EXISTS FloatingRate (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name TEXT,
+    term_id BIGINT,
+    FOREIGN KEY (term_id) REFERENCES Term(id),
+    UNIQUE NULLS NOT DISTINCT (name, term_id)
+);
+
+-- Table for Index struct. Called FirdsIndex to avoid conflict with INDEX keyword in SQL.
+CREATE TABLE IF NOT EXISTS FirdsIndex (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    isin VARCHAR(12),
+    name_id BIGINT NOT NULL,
+    FOREIGN KEY (name_id) REFERENCES FloatingRate(id),
+    UNIQUE NULLS NOT DISTINCT (isin, name_id)
+);
+
+CREATE TABLE IF NOT EXISTS TradingVenueAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    trading_venue CHAR(4) NOT NULL,
+    requested_admission BOOLEAN NOT NULL,
+    approval_date TIMESTAMPTZ,
+    request_date TIMESTAMPTZ,
+    admission_or_first_trade_date TIMESTAMPTZ,
+    termination_date TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS InterestRate (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    fixed DOUBLE PRECISION,
+    floating_rate_id BIGINT,
+    spread INTEGER,
+    FOREIGN KEY (floating_rate_id) REFERENCES FloatingRate(id),
+    CHECK (
+        (fixed IS NOT NULL AND floating_rate_id IS NULL AND spread IS NULL)
+            OR
+        (fixed IS NULL AND floating_rate_id IS NOT NULL)
+    )
+);
+
+CREATE TABLE IF NOT EXISTS PublicationPeriod (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    from_date DATE NOT NULL,
+    to_date DATE
+);
+
+CREATE TABLE IF NOT EXISTS TechnicalAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    relevant_competent_authority TEXT,
+    publication_period_id BIGINT,
+    relevant_trading_venue CHAR(4),
+    FOREIGN KEY (publication_period_id) REFERENCES PublicationPeriod(id)
+);
+
+CREATE TABLE IF NOT EXISTS DebtAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    total_issued_amount DOUBLE PRECISION NOT NULL,
+    maturity_date DATE,
+    nominal_currency TEXT NOT NULL,
+    nominal_value_per_unit DOUBLE PRECISION NOT NULL,
+    interest_rate_id BIGINT NOT NULL,
+    seniority CHAR(4),
+    FOREIGN KEY (interest_rate_id) REFERENCES InterestRate(id),
+    FOREIGN KEY (seniority) REFERENCES DebtSeniority(code)
+);
+
+CREATE TABLE IF NOT EXISTS CommodityDerivativeAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    product CHAR(4) NOT NULL,
+    subproduct CHAR(4),
+    -- Note: Products and subproducts are flattened as otherwise it would add a fair bit of complexity to the schema.
+    further_subproduct CHAR(4),
+    transaction_type CHAR(4),
+    final_price_type CHAR(4),
+    FOREIGN KEY (transaction_type) REFERENCES TransactionType(code),
+    FOREIGN KEY (final_price_type) REFERENCES FinalPriceType(code)
+);
+
+CREATE TABLE IF NOT EXISTS InterestRateDerivativeAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    reference_rate_id BIGINT NOT NULL,
+    interest_rate_1_id BIGINT,
+    notional_currency_2 TEXT,
+    interest_rate_2_id BIGINT,
+    FOREIGN KEY (reference_rate_id) REFERENCES FloatingRate(id),
+    FOREIGN KEY (interest_rate_1_id) REFERENCES InterestRate(id),
+    FOREIGN KEY (interest_rate_2_id) REFERENCES InterestRate(id)
+);
+
+CREATE TABLE IF NOT EXISTS FxDerivativeAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    notional_currency_2 TEXT,
+    fx_type CHAR(4), 
+    FOREIGN KEY (fx_type) REFERENCES FxType(code)
+);
+
+CREATE TABLE IF NOT EXISTS UnderlyingBasket (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY
+    -- Underlying ISINs and issuer_lei stored in separate tables
+);
+
+CREATE TABLE IF NOT EXISTS UnderlyingBasketIsin (
+    basket_id BIGINT NOT NULL,
+    isin CHAR(12) NOT NULL,
+    FOREIGN KEY (basket_id) REFERENCES UnderlyingBasket(id)
+);
+
+CREATE TABLE IF NOT EXISTS UnderlyingBasketIssuerLei (
+    basket_id BIGINT NOT NULL,
+    issuer_lei CHAR(20) NOT NULL,
+    FOREIGN KEY (basket_id) REFERENCES UnderlyingBasket(id)
+);
+
+CREATE TABLE IF NOT EXISTS UnderlyingSingle (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    isin CHAR(12),
+    index_id BIGINT,
+    lei CHAR(20),
+    FOREIGN KEY (index_id) REFERENCES FirdsIndex(id),
+    CHECK (
+        (isin IS NOT NULL AND index_id IS NULL AND lei IS NULL)
+            OR
+        (isin IS NULL AND index_id IS NOT NULL AND lei IS NULL)
+            OR
+        (isin IS NULL AND index_id IS NULL AND lei IS NOT NULL)
+    )
+);
+
+CREATE TABLE IF NOT EXISTS DerivativeUnderlying (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    single_id BIGINT,
+    basket_id BIGINT,
+    FOREIGN KEY (single_id) REFERENCES UnderlyingSingle(id),
+    FOREIGN KEY (basket_id) REFERENCES UnderlyingBasket(id),
+    CHECK (
+        (single_id IS NOT NULL AND basket_id IS NULL)
+            OR
+        (single_id IS NULL AND basket_id IS NOT NULL)
+    )
+);
+
+CREATE TABLE IF NOT EXISTS AssetClassSpecificAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    commodity_attributes_id BIGINT,
+    ir_attributes_id BIGINT,
+    fx_attributes_id BIGINT,
+    FOREIGN KEY (commodity_attributes_id) REFERENCES CommodityDerivativeAttributes(id),
+    FOREIGN KEY (ir_attributes_id) REFERENCES InterestRateDerivativeAttributes(id),
+    FOREIGN KEY (fx_attributes_id) REFERENCES FxDerivativeAttributes(id)
+);
+
+CREATE TABLE IF NOT EXISTS DerivativeAttributes (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    expiry_date DATE,
+    price_multiplier DOUBLE PRECISION,
+    underlying_id BIGINT,
+    option_type CHAR(4),
+    strike_price_id BIGINT,
+    option_exercise_style CHAR(4),
+    delivery_type CHAR(4),
+    asset_class_specific_attributes_id BIGINT,
+    FOREIGN KEY (underlying_id) REFERENCES DerivativeUnderlying(id),
+    FOREIGN KEY (option_type) REFERENCES OptionType(code),
+    FOREIGN KEY (strike_price_id) REFERENCES StrikePrice(id),
+    FOREIGN KEY (option_exercise_style) REFERENCES OptionExerciseStyle(code),
+    FOREIGN KEY (delivery_type) REFERENCES DeliveryType(code),
+    FOREIGN KEY (asset_class_specific_attributes_id) REFERENCES AssetClassSpecificAttributes(id)
+);
+
+-- The type of record in a FIRDS file from which a ReferenceData row was created.
+CREATE TABLE IF NOT EXISTS RecordType (
+    code CHAR(4) PRIMARY KEY,
+    label TEXT NOT NULL
+);
+
+INSERT INTO RecordType (code, label) VALUES
+    ('FULL', 'Full'),
+    ('NEWR', 'New'),
+    ('MODF', 'Modified'),
+    ('TERM', 'Terminated')
+ON CONFLICT DO NOTHING;
+
+-- A published FIRDS file from which records were loaded.
+CREATE TABLE IF NOT EXISTS SourceFile (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source TEXT NOT NULL,
+    file_type TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    file_id TEXT,
+    download_link TEXT,
+    publication_timestamp TIMESTAMPTZ,
+    checksum TEXT,
+    UNIQUE (source, file_name)
+);
+
+CREATE TABLE IF NOT EXISTS ReferenceData (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    isin CHAR(12) NOT NULL,
+    full_name TEXT NOT NULL,
+    cfi CHAR(6) NOT NULL,
+    is_commodities_derivative BOOLEAN NOT NULL,
+    issuer_lei CHAR(20) NOT NULL,
+    fisn VARCHAR(35) NOT NULL,
+    trading_venue_attrs_id BIGINT NOT NULL,
+    notional_currency TEXT NOT NULL,
+    technical_attributes_id BIGINT,
+    debt_attributes_id BIGINT,
+    derivative_attributes_id BIGINT,
+    -- Derived from the other fields using `ReferenceData::classify`, for convenient filtering.
+    instrument_class CHAR(4) NOT NULL,
+    -- Below fields are not present in FIRDS data but are necessary for tracking modifications.
+    latest_record BOOLEAN NOT NULL,
+    valid_from DATE NOT NULL,
+    valid_to DATE,
+    -- The file and type of record from which the row was loaded, if known.
+    source_file_id BIGINT,
+    record_type CHAR(4),
+    FOREIGN KEY (trading_venue_attrs_id) REFERENCES TradingVenueAttributes(id),
+    FOREIGN KEY (technical_attributes_id) REFERENCES TechnicalAttributes(id),
+    FOREIGN KEY (debt_attributes_id) REFERENCES DebtAttributes(id),
+    FOREIGN KEY (derivative_attributes_id) REFERENCES DerivativeAttributes(id),
+    FOREIGN KEY (instrument_class) REFERENCES InstrumentClass(code),
+    FOREIGN KEY (source_file_id) REFERENCES SourceFile(id),
+    FOREIGN KEY (record_type) REFERENCES RecordType(code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_refdata_isin ON ReferenceData(isin);
+CREATE INDEX IF NOT EXISTS idx_refdata_tv_id ON ReferenceData(trading_venue_attrs_id);
+CREATE INDEX IF NOT EXISTS idx_refdata_valid_to ON ReferenceData(valid_to);
+CREATE INDEX IF NOT EXISTS idx_refdata_class ON ReferenceData(instrument_class);
+CREATE INDEX IF NOT EXISTS idx_tv_mic ON TradingVenueAttributes(trading_venue);
+-- Term, FloatingRate and FirdsIndex rows are shared between all the records that reference the
+-- same rate (enforced by their unique constraints). These indexes are used to find the instruments
+-- that reference a given rate.
+CREATE INDEX IF NOT EXISTS idx_ir_floating_rate ON InterestRate(floating_rate_id);
+CREATE INDEX IF NOT EXISTS idx_irda_reference_rate ON InterestRateDerivativeAttributes(reference_rate_id);
+CREATE INDEX IF NOT EXISTS idx_underlying_index ON UnderlyingSingle(index_id);
+
+-- UPGRADES
+
+-- The PostgreSQL schema is not versioned. Instead, the statements below bring a database created
+-- by an earlier version of this file up to date, and do nothing for a database created by this
+-- version (in which the CREATE TABLE statements above have already done the same).
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'referencedata_instrument_class_fkey') THEN
+        ALTER TABLE ReferenceData
+            ADD CONSTRAINT referencedata_instrument_class_fkey
+            FOREIGN KEY (instrument_class) REFERENCES InstrumentClass(code);
+    END IF;
+END $$;
+
+-- Term, FloatingRate and FirdsIndex rows were created for every record, without unique
+-- constraints, by earlier versions. Merge the duplicates (pointing references at the row with the
+-- lowest ID, as dedupe_shared_rows does for SQLite) before adding the constraints. Each table is
+-- deduplicated after the tables it references, as merging those can create new duplicates.
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conrelid = 'term'::regclass AND contype = 'u') THEN
+        CREATE TEMP TABLE DuplicateRow AS
+        SELECT id, MIN(id) OVER (PARTITION BY number, unit) AS canonical_id FROM Term;
+        DELETE FROM DuplicateRow WHERE id = canonical_id;
+        UPDATE FloatingRate SET term_id = DuplicateRow.canonical_id
+        FROM DuplicateRow WHERE FloatingRate.term_id = DuplicateRow.id;
+        DELETE FROM Term WHERE id IN (SELECT id FROM DuplicateRow);
+        DROP TABLE DuplicateRow;
+        ALTER TABLE Term ADD UNIQUE (number, unit);
+    END IF;
+END $$;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conrelid = 'floatingrate'::regclass AND contype = 'u') THEN
+        CREATE TEMP TABLE DuplicateRow AS
+        SELECT id, MIN(id) OVER (PARTITION BY name, term_id) AS canonical_id FROM FloatingRate;
+        DELETE FROM DuplicateRow WHERE id = canonical_id;
+        UPDATE FirdsIndex SET name_id = DuplicateRow.canonical_id
+        FROM DuplicateRow WHERE FirdsIndex.name_id = DuplicateRow.id;
+        UPDATE InterestRate SET floating_rate_id = DuplicateRow.canonical_id
+        FROM DuplicateRow WHERE InterestRate.floating_rate_id = DuplicateRow.id;
+        UPDATE InterestRateDerivativeAttributes SET reference_rate_id = DuplicateRow.canonical_id
+        FROM DuplicateRow WHERE InterestRateDerivativeAttributes.reference_rate_id = DuplicateRow.id;
+        DELETE FROM FloatingRate WHERE id IN (SELECT id FROM DuplicateRow);
+        DROP TABLE DuplicateRow;
+        ALTER TABLE FloatingRate ADD UNIQUE NULLS NOT DISTINCT (name, term_id);
+    END IF;
+END $$;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conrelid = 'firdsindex'::regclass AND contype = 'u') THEN
+        CREATE TEMP TABLE DuplicateRow AS
+        SELECT id, MIN(id) OVER (PARTITION BY isin, name_id) AS canonical_id FROM FirdsIndex;
+        DELETE FROM DuplicateRow WHERE id = canonical_id;
+        UPDATE UnderlyingSingle SET index_id = DuplicateRow.canonical_id
+        FROM DuplicateRow WHERE UnderlyingSingle.index_id = DuplicateRow.id;
+        DELETE FROM FirdsIndex WHERE id IN (SELECT id FROM DuplicateRow);
+        DROP TABLE DuplicateRow;
+        ALTER TABLE FirdsIndex ADD UNIQUE NULLS NOT DISTINCT (isin, name_id);
+    END IF;
+END $$;
+
+-- The file and type of record from which each row was loaded.
+ALTER TABLE ReferenceData ADD COLUMN IF NOT EXISTS source_file_id BIGINT REFERENCES SourceFile(id);
+ALTER TABLE ReferenceData ADD COLUMN IF NOT EXISTS record_type CHAR(4) REFERENCES RecordType(code);
+CREATE INDEX IF NOT EXISTS idx_refdata_source_file ON ReferenceData(source_file_id);
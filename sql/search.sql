@@ -0,0 +1,32 @@
+-- Optional full-text search index over the names, FISNs and issuer LEIs of the records in
+-- ReferenceData. Created by `enable_search` and dropped by `disable_search`; it is not part of the
+-- versioned schema. The index is an FTS5 external content table, so the text is not stored twice,
+-- and triggers keep it in sync with ReferenceData however rows are inserted, updated or deleted.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS InstrumentSearch USING fts5(
+    full_name,
+    fisn,
+    issuer_lei,
+    content = 'ReferenceData',
+    content_rowid = 'id'
+);
+
+CREATE TRIGGER IF NOT EXISTS InstrumentSearch_insert AFTER INSERT ON ReferenceData BEGIN
+    INSERT INTO InstrumentSearch (rowid, full_name, fisn, issuer_lei)
+    VALUES (new.id, new.full_name, new.fisn, new.issuer_lei);
+END;
+
+CREATE TRIGGER IF NOT EXISTS InstrumentSearch_delete AFTER DELETE ON ReferenceData BEGIN
+    INSERT INTO InstrumentSearch (InstrumentSearch, rowid, full_name, fisn, issuer_lei)
+    VALUES ('delete', old.id, old.full_name, old.fisn, old.issuer_lei);
+END;
+
+CREATE TRIGGER IF NOT EXISTS InstrumentSearch_update AFTER UPDATE OF full_name, fisn, issuer_lei ON ReferenceData BEGIN
+    INSERT INTO InstrumentSearch (InstrumentSearch, rowid, full_name, fisn, issuer_lei)
+    VALUES ('delete', old.id, old.full_name, old.fisn, old.issuer_lei);
+    INSERT INTO InstrumentSearch (rowid, full_name, fisn, issuer_lei)
+    VALUES (new.id, new.full_name, new.fisn, new.issuer_lei);
+END;
+
+-- Index any records that already exist.
+INSERT INTO InstrumentSearch (InstrumentSearch) VALUES ('rebuild');
@@ -0,0 +1,272 @@
+//! A builder for constructing [`ReferenceData`] from sources other than the FIRDS XML files (or in
+//! tests), without having to fill in every nested struct.
+
+use crate::cfi::Cfi;
+use crate::currency::Currency;
+use crate::error::BuildError;
+use crate::fisn::Fisn;
+use crate::identifiers::{Isin, Lei, Mic};
+use crate::model::{DebtAttributes, DerivativeAttributes, ReferenceData, TechnicalAttributes, TradingVenueAttributes};
+use crate::validate::{Severity, Validator};
+use chrono::{DateTime, Utc};
+
+/// Builder for [`ReferenceData`]. Create one using [`ReferenceData::builder`].
+///
+/// The ISIN, full name, CFI code, issuer LEI, trading venue and notional currency must be set. If
+/// no FISN is set, an empty one is used.
+///
+/// ```
+/// use firds::{Isin, Lei, Mic, ReferenceData};
+///
+/// let ref_data = ReferenceData::builder()
+///     .isin(Isin::new("US0378331005").unwrap())
+///     .full_name("Apple Inc.")
+///     .cfi("ESVUFR".parse().unwrap())
+///     .issuer_lei(Lei::new("HWUPKR0MPOU8FGXBT394").unwrap())
+///     .trading_venue(Mic::new("XETR").unwrap())
+///     .notional_currency("EUR".parse().unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(ref_data.venue().as_str(), "XETR");
+/// ```
+#[derive(Debug, Default)]
+pub struct ReferenceDataBuilder {
+    isin: Option<Isin>,
+    full_name: Option<String>,
+    cfi: Option<Cfi>,
+    is_commodities_derivative: bool,
+    issuer_lei: Option<Lei>,
+    fisn: Option<Fisn>,
+    trading_venue_attrs: Option<TradingVenueAttributes>,
+    admission_date: Option<DateTime<Utc>>,
+    notional_currency: Option<Currency>,
+    technical_attributes: Option<TechnicalAttributes>,
+    debt_attributes: Option<DebtAttributes>,
+    derivative_attributes: Option<DerivativeAttributes>,
+}
+
+impl ReferenceDataBuilder {
+    pub fn isin(mut self, isin: Isin) -> Self {
+        self.isin = Some(isin);
+        self
+    }
+
+    pub fn full_name(mut self, full_name: impl Into<String>) -> Self {
+        self.full_name = Some(full_name.into());
+        self
+    }
+
+    pub fn cfi(mut self, cfi: Cfi) -> Self {
+        self.cfi = Some(cfi);
+        self
+    }
+
+    pub fn commodities_derivative(mut self, is_commodities_derivative: bool) -> Self {
+        self.is_commodities_derivative = is_commodities_derivative;
+        self
+    }
+
+    pub fn issuer_lei(mut self, issuer_lei: Lei) -> Self {
+        self.issuer_lei = Some(issuer_lei);
+        self
+    }
+
+    pub fn fisn(mut self, fisn: Fisn) -> Self {
+        self.fisn = Some(fisn);
+        self
+    }
+
+    /// Set the trading venue. If trading venue attributes have already been set, only the venue is
+    /// changed; otherwise, the other trading venue attributes are left empty.
+    pub fn trading_venue(mut self, mic: Mic) -> Self {
+        match &mut self.trading_venue_attrs {
+            Some(attrs) => attrs.trading_venue = mic,
+            None => self.trading_venue_attrs = Some(TradingVenueAttributes::new(mic))
+        }
+        self
+    }
+
+    /// Set the date of admission to trading or first trade. This can be set before or after the
+    /// trading venue, and takes precedence over the date in any attributes set with
+    /// [`trading_venue_attrs`](Self::trading_venue_attrs).
+    pub fn admission_date(mut self, date: DateTime<Utc>) -> Self {
+        self.admission_date = Some(date);
+        self
+    }
+
+    pub fn trading_venue_attrs(mut self, attrs: TradingVenueAttributes) -> Self {
+        self.trading_venue_attrs = Some(attrs);
+        self
+    }
+
+    pub fn notional_currency(mut self, currency: Currency) -> Self {
+        self.notional_currency = Some(currency);
+        self
+    }
+
+    pub fn technical_attributes(mut self, attrs: TechnicalAttributes) -> Self {
+        self.technical_attributes = Some(attrs);
+        self
+    }
+
+    pub fn debt_attributes(mut self, attrs: DebtAttributes) -> Self {
+        self.debt_attributes = Some(attrs);
+        self
+    }
+
+    pub fn derivative_attributes(mut self, attrs: DerivativeAttributes) -> Self {
+        self.derivative_attributes = Some(attrs);
+        self
+    }
+
+    /// Build the [`ReferenceData`], returning an error if any mandatory field is missing, the ISIN
+    /// or issuer LEI is invalid, or the record fails any of the default validation rules (see
+    /// [`Validator::default`]) with [`Severity::Error`].
+    pub fn build(self) -> Result<ReferenceData, BuildError> {
+        let ref_data = self.build_unchecked()?;
+        ref_data.isin.validate()
+            .map_err(|e| BuildError::InvalidIdentifier(ref_data.isin.to_string(), e))?;
+        ref_data.issuer_lei.validate()
+            .map_err(|e| BuildError::InvalidIdentifier(ref_data.issuer_lei.to_string(), e))?;
+        let errors: Vec<_> = Validator::default().validate(&ref_data).into_iter()
+            .filter(|f| f.severity == Severity::Error)
+            .collect();
+        if errors.is_empty() {
+            Ok(ref_data)
+        } else {
+            Err(BuildError::Invalid(errors))
+        }
+    }
+
+    /// Build the [`ReferenceData`], only checking that mandatory fields are present.
+    pub fn build_unchecked(self) -> Result<ReferenceData, BuildError> {
+        let mut ref_data = ReferenceData {
+            isin: self.isin.ok_or(BuildError::MissingField("isin"))?,
+            full_name: self.full_name.ok_or(BuildError::MissingField("full_name"))?,
+            cfi: self.cfi.ok_or(BuildError::MissingField("cfi"))?,
+            is_commodities_derivative: self.is_commodities_derivative,
+            issuer_lei: self.issuer_lei.ok_or(BuildError::MissingField("issuer_lei"))?,
+            fisn: self.fisn.unwrap_or_else(|| Fisn::new("")),
+            trading_venue_attrs: self.trading_venue_attrs.ok_or(BuildError::MissingField("trading_venue"))?,
+            notional_currency: self.notional_currency.ok_or(BuildError::MissingField("notional_currency"))?,
+            technical_attributes: self.technical_attributes,
+            debt_attributes: self.debt_attributes,
+            derivative_attributes: self.derivative_attributes,
+        };
+        if let Some(date) = self.admission_date {
+            ref_data.trading_venue_attrs.admission_or_first_trade_date = Some(date);
+        }
+        Ok(ref_data)
+    }
+}
+
+impl ReferenceData {
+    /// Return a new [`ReferenceDataBuilder`].
+    pub fn builder() -> ReferenceDataBuilder {
+        ReferenceDataBuilder::default()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::error::{BuildError, IdentifierError};
+    use crate::{DebtAttributes, DerivativeAttributes, DerivativeUnderlying, Index, IndexCode, IndexName, InterestRate, FloatingRate, Isin, Lei, Mic, ReferenceData, ReferenceDataBuilder, TradingVenueAttributes, UnderlyingBasket};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    /// Return a builder with all mandatory fields set, for a test instrument with the given CFI code.
+    pub(crate) fn builder(cfi: &str) -> ReferenceDataBuilder {
+        ReferenceData::builder()
+            .isin(Isin::new_unchecked("DE0001102390"))
+            .full_name("Test instrument")
+            .cfi(cfi.parse().unwrap())
+            .issuer_lei(Lei::new_unchecked("5493001KJTIIGC8Y1R12"))
+            .trading_venue(Mic::new_unchecked("XETR"))
+            .notional_currency("EUR".parse().unwrap())
+    }
+
+    #[test]
+    fn test_builder() {
+        assert!(matches!(
+            ReferenceData::builder().isin(Isin::new_unchecked("DE0001102390")).build(),
+            Err(BuildError::MissingField("full_name"))
+        ));
+        assert!(matches!(
+            builder("ESVUFR").isin(Isin::new_unchecked("DE0001102391")).build(),
+            Err(BuildError::InvalidIdentifier(_, IdentifierError::BadCheckDigit))
+        ));
+        match builder("DBFTFB").build() {
+            Err(BuildError::Invalid(findings)) => assert_eq!(findings[0].rule, "debt-attributes-present"),
+            other => panic!("unexpected result: {other:?}")
+        }
+        assert!(builder("DBFTFB").build_unchecked().is_ok());
+
+        let maturity = NaiveDate::from_ymd_opt(2030, 2, 15);
+        let bond = builder("DBFTFB")
+            .debt_attributes(DebtAttributes {
+                total_issued_amount: 1_000_000.0,
+                maturity_date: maturity,
+                nominal_currency: "EUR".parse().unwrap(),
+                nominal_value_per_unit: 1000.0,
+                interest_rate: InterestRate::Floating(
+                    FloatingRate { name: Some(IndexName::Code(IndexCode::Euribor)), term: None },
+                    Some(50)
+                ),
+                seniority: None,
+            })
+            .build()
+            .unwrap();
+        assert_eq!(bond.maturity(), maturity);
+        assert_eq!(bond.expiry(), None);
+        assert_eq!(bond.venue().as_str(), "XETR");
+        assert!(matches!(bond.reference_rate(), Some(FloatingRate { name: Some(IndexName::Code(IndexCode::Euribor)), .. })));
+        assert!(bond.underlying_isins().is_empty());
+    }
+
+    #[test]
+    fn test_admission_date() {
+        let date = Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap();
+        // The admission date is kept whether it is set before or after the trading venue.
+        let data = ReferenceData::builder()
+            .admission_date(date)
+            .isin(Isin::new_unchecked("DE0001102390"))
+            .full_name("Test instrument")
+            .cfi("ESVUFR".parse().unwrap())
+            .issuer_lei(Lei::new_unchecked("5493001KJTIIGC8Y1R12"))
+            .trading_venue(Mic::new_unchecked("XETR"))
+            .notional_currency("EUR".parse().unwrap())
+            .build_unchecked()
+            .unwrap();
+        assert_eq!(data.trading_venue_attrs.admission_or_first_trade_date, Some(date));
+        let data = builder("ESVUFR")
+            .admission_date(date)
+            .trading_venue_attrs(TradingVenueAttributes::new(Mic::new_unchecked("XLON")))
+            .build_unchecked()
+            .unwrap();
+        assert_eq!(data.trading_venue_attrs.trading_venue.as_str(), "XLON");
+        assert_eq!(data.trading_venue_attrs.admission_or_first_trade_date, Some(date));
+    }
+
+    #[test]
+    fn test_underlying_accessors() {
+        let mut future = builder("FFICSX").build_unchecked().unwrap();
+        future.derivative_attributes = Some(DerivativeAttributes {
+            underlying: Some(DerivativeUnderlying::Basket(UnderlyingBasket {
+                isin: vec![Isin::new_unchecked("US0378331005"), Isin::new_unchecked("GB0002634946")],
+                issuer_lei: vec![Lei::new_unchecked("5493001KJTIIGC8Y1R12")],
+            })),
+            ..Default::default()
+        });
+        assert_eq!(future.underlying_isins().len(), 2);
+        assert_eq!(future.underlying_leis().len(), 1);
+
+        future.derivative_attributes = Some(DerivativeAttributes {
+            underlying: Some(DerivativeUnderlying::Single(crate::UnderlyingSingle::Index(Index {
+                isin: Some(Isin::new_unchecked("DE0008469008")),
+                name: FloatingRate { name: None, term: None },
+            }))),
+            ..Default::default()
+        });
+        assert_eq!(future.underlying_isins(), vec![&Isin::new_unchecked("DE0008469008")]);
+        assert!(future.reference_rate().is_some());
+    }
+}
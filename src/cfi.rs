@@ -0,0 +1,911 @@
+//! Decoding of Classification of Financial Instruments (CFI) codes, as defined in ISO 10962:2019.
+//!
+//! A CFI code consists of six letters. The first identifies the category of the instrument (eg,
+//! `E` for equities), the second identifies the group within that category (eg, `ES` for common or
+//! ordinary shares) and the remaining four letters are attributes whose meaning depends on the
+//! group. An `X` in an attribute position means that the attribute is not applicable or unknown.
+//!
+//! [`Cfi`] only checks the format of the code when it is constructed, and keeps the raw code, so
+//! that records using a category or group which this crate does not know (eg, from a newer
+//! version of the standard) can still be read. Unknown categories and groups are represented by
+//! [`CfiCategory::Unknown`] and [`CfiGroup::Unknown`], and can be rejected using
+//! [`Cfi::validate`]. The attributes are decoded on demand (see [`Cfi::attributes`]).
+
+use crate::error::CfiError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+/// The category of a financial instrument (the first letter of its CFI code).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CfiCategory {
+    /// Equities (`E`).
+    Equity,
+    /// Collective investment vehicles (`C`).
+    CollectiveInvestmentVehicle,
+    /// Debt instruments (`D`).
+    Debt,
+    /// Entitlements (rights) (`R`).
+    Entitlement,
+    /// Listed options (`O`).
+    ListedOption,
+    /// Futures (`F`).
+    Future,
+    /// Swaps (`S`).
+    Swap,
+    /// Non-listed and complex listed options (`H`).
+    NonListedOption,
+    /// Spots (`I`).
+    Spot,
+    /// Forwards (`J`).
+    Forward,
+    /// Strategies (`K`).
+    Strategy,
+    /// Financing (`L`).
+    Financing,
+    /// Referential instruments (`T`).
+    ReferentialInstrument,
+    /// Miscellaneous (`M`).
+    Other,
+    /// A category not defined by ISO 10962:2019. Contains the letter.
+    Unknown(char),
+}
+
+impl CfiCategory {
+    /// Return the category corresponding to the given letter, if any. Unknown letters return
+    /// `None` rather than [`CfiCategory::Unknown`].
+    pub fn from_code(code: char) -> Option<Self> {
+        Some(match code {
+            'E' => Self::Equity,
+            'C' => Self::CollectiveInvestmentVehicle,
+            'D' => Self::Debt,
+            'R' => Self::Entitlement,
+            'O' => Self::ListedOption,
+            'F' => Self::Future,
+            'S' => Self::Swap,
+            'H' => Self::NonListedOption,
+            'I' => Self::Spot,
+            'J' => Self::Forward,
+            'K' => Self::Strategy,
+            'L' => Self::Financing,
+            'T' => Self::ReferentialInstrument,
+            'M' => Self::Other,
+            _ => return None
+        })
+    }
+
+    /// Return the letter representing the category.
+    pub fn code(&self) -> char {
+        match self {
+            Self::Equity => 'E',
+            Self::CollectiveInvestmentVehicle => 'C',
+            Self::Debt => 'D',
+            Self::Entitlement => 'R',
+            Self::ListedOption => 'O',
+            Self::Future => 'F',
+            Self::Swap => 'S',
+            Self::NonListedOption => 'H',
+            Self::Spot => 'I',
+            Self::Forward => 'J',
+            Self::Strategy => 'K',
+            Self::Financing => 'L',
+            Self::ReferentialInstrument => 'T',
+            Self::Other => 'M',
+            Self::Unknown(c) => *c,
+        }
+    }
+
+    /// Return a human-readable description of the category.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Equity => "Equities",
+            Self::CollectiveInvestmentVehicle => "Collective investment vehicles",
+            Self::Debt => "Debt instruments",
+            Self::Entitlement => "Entitlements (rights)",
+            Self::ListedOption => "Listed options",
+            Self::Future => "Futures",
+            Self::Swap => "Swaps",
+            Self::NonListedOption => "Non-listed and complex listed options",
+            Self::Spot => "Spots",
+            Self::Forward => "Forwards",
+            Self::Strategy => "Strategies",
+            Self::Financing => "Financing",
+            Self::ReferentialInstrument => "Referential instruments",
+            Self::Other => "Miscellaneous",
+            Self::Unknown(_) => "Unknown category",
+        }
+    }
+}
+
+/// Defines [`CfiGroup`] along with its methods, from a table of (variant, category, group letter,
+/// description) rows.
+macro_rules! cfi_groups {
+    ($($variant:ident => ($category:ident, $code:literal, $desc:literal)),* $(,)?) => {
+        /// The group of a financial instrument within its category (the second letter of its CFI
+        /// code).
+        ///
+        /// The same group letter has different meanings in different categories, so each variant
+        /// represents a particular combination of category and group letter.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum CfiGroup {
+            $(
+                #[doc = $desc]
+                $variant,
+            )*
+            /// A group not defined by ISO 10962:2019 within its category. Contains the category
+            /// and the group letter.
+            Unknown(CfiCategory, char),
+        }
+
+        impl CfiGroup {
+            /// Return the group corresponding to the given category and group letter, if any.
+            /// Unknown combinations return `None` rather than [`CfiGroup::Unknown`].
+            pub fn from_codes(category: CfiCategory, code: char) -> Option<Self> {
+                match (category, code) {
+                    $((CfiCategory::$category, $code) => Some(Self::$variant),)*
+                    _ => None
+                }
+            }
+
+            /// Return the category to which the group belongs.
+            pub fn category(&self) -> CfiCategory {
+                match self {
+                    $(Self::$variant => CfiCategory::$category,)*
+                    Self::Unknown(category, _) => *category,
+                }
+            }
+
+            /// Return the letter representing the group within its category.
+            pub fn code(&self) -> char {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Unknown(_, code) => *code,
+                }
+            }
+
+            /// Return a human-readable description of the group.
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $desc,)*
+                    Self::Unknown(..) => "Unknown group",
+                }
+            }
+        }
+    };
+}
+
+cfi_groups! {
+    // Equities
+    CommonShares => (Equity, 'S', "Common/ordinary shares"),
+    PreferredShares => (Equity, 'P', "Preferred/preference shares"),
+    CommonConvertibleShares => (Equity, 'C', "Common/ordinary convertible shares"),
+    PreferredConvertibleShares => (Equity, 'F', "Preferred/preference convertible shares"),
+    LimitedPartnershipUnits => (Equity, 'L', "Limited partnership units"),
+    EquityDepositoryReceipts => (Equity, 'D', "Depository receipts on equities"),
+    StructuredParticipationProducts => (Equity, 'Y', "Structured instruments (participation)"),
+    OtherEquities => (Equity, 'M', "Others (miscellaneous equities)"),
+    // Collective investment vehicles
+    StandardInvestmentFunds => (CollectiveInvestmentVehicle, 'I', "Standard (vanilla) investment funds/mutual funds"),
+    HedgeFunds => (CollectiveInvestmentVehicle, 'H', "Hedge funds"),
+    RealEstateInvestmentTrusts => (CollectiveInvestmentVehicle, 'B', "Real estate investment trusts (REITs)"),
+    ExchangeTradedFunds => (CollectiveInvestmentVehicle, 'E', "Exchange traded funds (ETFs)"),
+    PensionFunds => (CollectiveInvestmentVehicle, 'S', "Pension funds"),
+    FundsOfFunds => (CollectiveInvestmentVehicle, 'F', "Funds of funds"),
+    PrivateEquityFunds => (CollectiveInvestmentVehicle, 'P', "Private equity funds"),
+    OtherCollectiveInvestmentVehicles => (CollectiveInvestmentVehicle, 'M', "Others (miscellaneous collective investment vehicles)"),
+    // Debt instruments
+    Bonds => (Debt, 'B', "Bonds"),
+    ConvertibleBonds => (Debt, 'C', "Convertible bonds"),
+    BondsWithWarrants => (Debt, 'W', "Bonds with warrants attached"),
+    MediumTermNotes => (Debt, 'T', "Medium-term notes"),
+    CapitalProtectionStructuredProducts => (Debt, 'S', "Structured products (with capital protection)"),
+    NoCapitalProtectionStructuredProducts => (Debt, 'E', "Structured products (without capital protection)"),
+    MortgageBackedSecurities => (Debt, 'G', "Mortgage-backed securities"),
+    AssetBackedSecurities => (Debt, 'A', "Asset-backed securities"),
+    MunicipalBonds => (Debt, 'N', "Municipal bonds"),
+    DebtDepositoryReceipts => (Debt, 'D', "Depository receipts on debt instruments"),
+    MoneyMarketInstruments => (Debt, 'Y', "Money market instruments"),
+    OtherDebt => (Debt, 'M', "Others (miscellaneous debt instruments)"),
+    // Entitlements (rights)
+    AllotmentRights => (Entitlement, 'A', "Allotment (bonus) rights"),
+    SubscriptionRights => (Entitlement, 'S', "Subscription rights"),
+    PurchaseRights => (Entitlement, 'P', "Purchase rights"),
+    Warrants => (Entitlement, 'W', "Warrants"),
+    MiniFutureCertificates => (Entitlement, 'F', "Mini-future certificates, constant leverage certificates"),
+    EntitlementDepositoryReceipts => (Entitlement, 'D', "Depository receipts on entitlements"),
+    OtherEntitlements => (Entitlement, 'M', "Others (miscellaneous entitlements)"),
+    // Listed options
+    CallOptions => (ListedOption, 'C', "Call options"),
+    PutOptions => (ListedOption, 'P', "Put options"),
+    OtherListedOptions => (ListedOption, 'M', "Others (miscellaneous listed options)"),
+    // Futures
+    FinancialFutures => (Future, 'F', "Financial futures"),
+    CommodityFutures => (Future, 'C', "Commodities futures"),
+    // Swaps
+    RatesSwaps => (Swap, 'R', "Rates swaps"),
+    CommoditySwaps => (Swap, 'T', "Commodities swaps"),
+    EquitySwaps => (Swap, 'E', "Equity swaps"),
+    CreditSwaps => (Swap, 'C', "Credit swaps"),
+    FxSwaps => (Swap, 'F', "Foreign exchange swaps"),
+    OtherSwaps => (Swap, 'M', "Others (miscellaneous swaps)"),
+    // Non-listed and complex listed options
+    RatesOptions => (NonListedOption, 'R', "Rates options"),
+    CommodityOptions => (NonListedOption, 'T', "Commodities options"),
+    EquityOptions => (NonListedOption, 'E', "Equity options"),
+    CreditOptions => (NonListedOption, 'C', "Credit options"),
+    FxOptions => (NonListedOption, 'F', "Foreign exchange options"),
+    OtherNonListedOptions => (NonListedOption, 'M', "Others (miscellaneous non-listed options)"),
+    // Spots
+    FxSpots => (Spot, 'F', "Foreign exchange spots"),
+    CommoditySpots => (Spot, 'T', "Commodities spots"),
+    // Forwards
+    EquityForwards => (Forward, 'E', "Equity forwards"),
+    FxForwards => (Forward, 'F', "Foreign exchange forwards"),
+    CreditForwards => (Forward, 'C', "Credit forwards"),
+    RatesForwards => (Forward, 'R', "Rates forwards"),
+    CommodityForwards => (Forward, 'T', "Commodities forwards"),
+    // Strategies
+    RatesStrategies => (Strategy, 'R', "Rates strategies"),
+    CommodityStrategies => (Strategy, 'T', "Commodities strategies"),
+    EquityStrategies => (Strategy, 'E', "Equity strategies"),
+    CreditStrategies => (Strategy, 'C', "Credit strategies"),
+    FxStrategies => (Strategy, 'F', "Foreign exchange strategies"),
+    MixedAssetStrategies => (Strategy, 'Y', "Mixed assets strategies"),
+    OtherStrategies => (Strategy, 'M', "Others (miscellaneous strategies)"),
+    // Financing
+    LoanLeases => (Financing, 'L', "Loan-lease"),
+    RepurchaseAgreements => (Financing, 'R', "Repurchase agreements"),
+    SecuritiesLending => (Financing, 'S', "Securities lending"),
+    // Referential instruments
+    Currencies => (ReferentialInstrument, 'C', "Currencies"),
+    Commodities => (ReferentialInstrument, 'T', "Commodities"),
+    InterestRates => (ReferentialInstrument, 'R', "Interest rates"),
+    Indices => (ReferentialInstrument, 'I', "Indices"),
+    Baskets => (ReferentialInstrument, 'B', "Baskets"),
+    StockDividends => (ReferentialInstrument, 'D', "Stock dividends"),
+    OtherReferentialInstruments => (ReferentialInstrument, 'M', "Others (miscellaneous referential instruments)"),
+    // Miscellaneous
+    CombinedInstruments => (Other, 'C', "Combined instruments"),
+    OtherAssets => (Other, 'M', "Other assets (miscellaneous)"),
+}
+
+/// Voting right attribute of common or ordinary shares (attribute 1 of `ES`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum VotingRight {
+    /// Each share has one vote.
+    #[strum(serialize = "V")]
+    Voting,
+    /// The shareholder has no voting right.
+    #[strum(serialize = "N")]
+    NonVoting,
+    /// The shareholder may be entitled to vote only under certain conditions.
+    #[strum(serialize = "R")]
+    Restricted,
+    /// The shareholder is entitled to more than one vote per share.
+    #[strum(serialize = "E")]
+    Enhanced,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Ownership or transfer restrictions on shares (attribute 2 of `ES`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum TransferRestriction {
+    /// The ownership or transfer of the security is subject to special conditions.
+    #[strum(serialize = "T")]
+    Restricted,
+    /// The ownership or transfer of the security is not subject to special conditions.
+    #[strum(serialize = "U")]
+    Free,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Payment status of shares (attribute 3 of `ES`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum PaymentStatus {
+    /// Nil paid.
+    #[strum(serialize = "O")]
+    NilPaid,
+    /// Partly paid.
+    #[strum(serialize = "P")]
+    PartlyPaid,
+    /// Fully paid.
+    #[strum(serialize = "F")]
+    FullyPaid,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Form of a security (attribute 4 of `ES` and `DB`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum SecurityForm {
+    /// Bearer.
+    #[strum(serialize = "B")]
+    Bearer,
+    /// Registered.
+    #[strum(serialize = "R")]
+    Registered,
+    /// Bearer/registered.
+    #[strum(serialize = "N")]
+    BearerOrRegistered,
+    /// Others (miscellaneous).
+    #[strum(serialize = "M")]
+    Other,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Type of interest or cash payment of a bond (attribute 1 of `DB`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum InterestType {
+    /// Fixed rate.
+    #[strum(serialize = "F")]
+    Fixed,
+    /// Zero rate/discounted.
+    #[strum(serialize = "Z")]
+    Zero,
+    /// Variable.
+    #[strum(serialize = "V")]
+    Variable,
+    /// Cash payment.
+    #[strum(serialize = "C")]
+    CashPayment,
+    /// Payment in kind.
+    #[strum(serialize = "K")]
+    PaymentInKind,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Guarantee or ranking of a bond (attribute 2 of `DB`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum DebtGuarantee {
+    /// Government/state guarantee.
+    #[strum(serialize = "T")]
+    GovernmentGuarantee,
+    /// Joint guarantee.
+    #[strum(serialize = "G")]
+    JointGuarantee,
+    /// Secured.
+    #[strum(serialize = "S")]
+    Secured,
+    /// Unsecured/unguaranteed.
+    #[strum(serialize = "U")]
+    Unsecured,
+    /// Negative pledge.
+    #[strum(serialize = "P")]
+    NegativePledge,
+    /// Senior.
+    #[strum(serialize = "N")]
+    Senior,
+    /// Senior subordinated.
+    #[strum(serialize = "O")]
+    SeniorSubordinated,
+    /// Junior.
+    #[strum(serialize = "Q")]
+    Junior,
+    /// Junior subordinated.
+    #[strum(serialize = "J")]
+    JuniorSubordinated,
+    /// Supranational.
+    #[strum(serialize = "C")]
+    Supranational,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Redemption or reimbursement terms of a bond (attribute 3 of `DB`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum Redemption {
+    /// Fixed maturity.
+    #[strum(serialize = "F")]
+    FixedMaturity,
+    /// Fixed maturity with call feature.
+    #[strum(serialize = "G")]
+    FixedMaturityWithCall,
+    /// Fixed maturity with put feature.
+    #[strum(serialize = "C")]
+    FixedMaturityWithPut,
+    /// Fixed maturity with put and call.
+    #[strum(serialize = "D")]
+    FixedMaturityWithPutAndCall,
+    /// Amortization plan.
+    #[strum(serialize = "A")]
+    Amortization,
+    /// Amortization plan with call feature.
+    #[strum(serialize = "B")]
+    AmortizationWithCall,
+    /// Amortization plan with put feature.
+    #[strum(serialize = "T")]
+    AmortizationWithPut,
+    /// Amortization plan with put and call.
+    #[strum(serialize = "L")]
+    AmortizationWithPutAndCall,
+    /// Perpetual.
+    #[strum(serialize = "P")]
+    Perpetual,
+    /// Perpetual with call feature.
+    #[strum(serialize = "Q")]
+    PerpetualWithCall,
+    /// Perpetual with put feature.
+    #[strum(serialize = "R")]
+    PerpetualWithPut,
+    /// Extendible.
+    #[strum(serialize = "E")]
+    Extendible,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Exercise style of a listed option (attribute 1 of `OC` and `OP`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum ExerciseStyle {
+    /// European: can be exercised only at expiry.
+    #[strum(serialize = "E")]
+    European,
+    /// American: can be exercised at any time up to expiry.
+    #[strum(serialize = "A")]
+    American,
+    /// Bermudan: can be exercised on a series of specified dates.
+    #[strum(serialize = "B")]
+    Bermudan,
+    /// Others (miscellaneous).
+    #[strum(serialize = "M")]
+    Other,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Underlying assets of a listed option or financial future (attribute 2 of `OC`, `OP` and
+/// attribute 1 of `FF`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum FinancialUnderlying {
+    /// Baskets.
+    #[strum(serialize = "B")]
+    Baskets,
+    /// Stock-equities.
+    #[strum(serialize = "S")]
+    Equities,
+    /// Debt instruments.
+    #[strum(serialize = "D")]
+    Debt,
+    /// Commodities.
+    #[strum(serialize = "T")]
+    Commodities,
+    /// Currencies.
+    #[strum(serialize = "C")]
+    Currencies,
+    /// Indices.
+    #[strum(serialize = "I")]
+    Indices,
+    /// Options.
+    #[strum(serialize = "O")]
+    Options,
+    /// Futures.
+    #[strum(serialize = "F")]
+    Futures,
+    /// Swaps.
+    #[strum(serialize = "W")]
+    Swaps,
+    /// Interest rates.
+    #[strum(serialize = "N")]
+    InterestRates,
+    /// Stock dividends.
+    #[strum(serialize = "V")]
+    StockDividends,
+    /// Others (miscellaneous).
+    #[strum(serialize = "M")]
+    Other,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Underlying assets of a commodities future (attribute 1 of `FC`).
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum CommodityUnderlying {
+    /// Extraction resources.
+    #[strum(serialize = "E")]
+    Extraction,
+    /// Agriculture.
+    #[strum(serialize = "A")]
+    Agriculture,
+    /// Industrial products.
+    #[strum(serialize = "I")]
+    Industrial,
+    /// Services.
+    #[strum(serialize = "S")]
+    Services,
+    /// Environmental.
+    #[strum(serialize = "N")]
+    Environmental,
+    /// Polypropylene products.
+    #[strum(serialize = "P")]
+    Polypropylene,
+    /// Generated resources.
+    #[strum(serialize = "H")]
+    Generated,
+    /// Others (miscellaneous).
+    #[strum(serialize = "M")]
+    Other,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Delivery or settlement method of a derivative.
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum Delivery {
+    /// Physical delivery.
+    #[strum(serialize = "P")]
+    Physical,
+    /// Cash settlement.
+    #[strum(serialize = "C")]
+    Cash,
+    /// Non-deliverable.
+    #[strum(serialize = "N")]
+    NonDeliverable,
+    /// Elect at exercise.
+    #[strum(serialize = "E")]
+    ElectAtExercise,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Whether the terms of a listed derivative are standardized.
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq)]
+pub enum Standardization {
+    /// Standardized: the underlying instruments, exercise price, expiration date and contract size
+    /// are standardized.
+    #[strum(serialize = "S")]
+    Standardized,
+    /// Non-standardized.
+    #[strum(serialize = "N")]
+    NonStandardized,
+    /// Not applicable/undefined.
+    #[strum(serialize = "X")]
+    NotApplicable,
+}
+
+/// Attributes of common or ordinary shares (`ES`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CommonShareAttributes {
+    pub voting_right: VotingRight,
+    pub transfer_restriction: TransferRestriction,
+    pub payment_status: PaymentStatus,
+    pub form: SecurityForm,
+}
+
+/// Attributes of bonds (`DB`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BondAttributes {
+    pub interest_type: InterestType,
+    pub guarantee: DebtGuarantee,
+    pub redemption: Redemption,
+    pub form: SecurityForm,
+}
+
+/// Attributes of listed call or put options (`OC` or `OP`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ListedOptionAttributes {
+    pub exercise_style: ExerciseStyle,
+    pub underlying: FinancialUnderlying,
+    pub delivery: Delivery,
+    pub standardization: Standardization,
+}
+
+/// Attributes of financial futures (`FF`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FinancialFutureAttributes {
+    pub underlying: FinancialUnderlying,
+    pub delivery: Delivery,
+    pub standardization: Standardization,
+}
+
+/// Attributes of commodities futures (`FC`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CommodityFutureAttributes {
+    pub underlying: CommodityUnderlying,
+    pub delivery: Delivery,
+    pub standardization: Standardization,
+}
+
+/// The four attribute letters of a CFI code, decoded according to the instrument's group.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CfiAttributes {
+    CommonShare(CommonShareAttributes),
+    Bond(BondAttributes),
+    ListedOption(ListedOptionAttributes),
+    FinancialFuture(FinancialFutureAttributes),
+    CommodityFuture(CommodityFutureAttributes),
+    /// The raw attribute letters, for groups whose attributes are not decoded by this crate or
+    /// where the attributes could not be decoded.
+    Other([char; 4]),
+}
+
+/// Parse a single attribute letter into the relevant enum.
+fn attr<T: FromStr>(c: char) -> Option<T> {
+    T::from_str(c.encode_utf8(&mut [0; 4])).ok()
+}
+
+/// Decode the attribute letters for the given group, returning `None` if the group's attributes
+/// are not decoded or any of the letters is invalid.
+fn decode_attributes(group: CfiGroup, [a, b, c, d]: [char; 4]) -> Option<CfiAttributes> {
+    Some(match group {
+        CfiGroup::CommonShares => CfiAttributes::CommonShare(CommonShareAttributes {
+            voting_right: attr(a)?,
+            transfer_restriction: attr(b)?,
+            payment_status: attr(c)?,
+            form: attr(d)?,
+        }),
+        CfiGroup::Bonds => CfiAttributes::Bond(BondAttributes {
+            interest_type: attr(a)?,
+            guarantee: attr(b)?,
+            redemption: attr(c)?,
+            form: attr(d)?,
+        }),
+        CfiGroup::CallOptions | CfiGroup::PutOptions => CfiAttributes::ListedOption(
+            ListedOptionAttributes {
+                exercise_style: attr(a)?,
+                underlying: attr(b)?,
+                delivery: attr(c)?,
+                standardization: attr(d)?,
+            }
+        ),
+        CfiGroup::FinancialFutures => CfiAttributes::FinancialFuture(FinancialFutureAttributes {
+            underlying: attr(a)?,
+            delivery: attr(b)?,
+            standardization: attr(c)?,
+        }),
+        CfiGroup::CommodityFutures => CfiAttributes::CommodityFuture(CommodityFutureAttributes {
+            underlying: attr(a)?,
+            delivery: attr(b)?,
+            standardization: attr(c)?,
+        }),
+        _ => return None
+    })
+}
+
+/// A Classification of Financial Instruments code (ISO 10962).
+///
+/// Parsing only requires the code to consist of six upper-case ASCII letters. A category or group
+/// which is not defined by the standard is parsed as [`CfiCategory::Unknown`] or
+/// [`CfiGroup::Unknown`]; use [`Cfi::validate`] to reject such codes.
+///
+/// ```
+/// use firds::{Cfi, CfiAttributes, CfiCategory, CfiGroup, VotingRight};
+///
+/// let cfi: Cfi = "ESVUFR".parse().unwrap();
+/// assert_eq!(cfi.category(), CfiCategory::Equity);
+/// assert_eq!(cfi.group(), CfiGroup::CommonShares);
+/// assert!(cfi.is_equity());
+/// if let CfiAttributes::CommonShare(attrs) = cfi.attributes() {
+///     assert_eq!(attrs.voting_right, VotingRight::Voting);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cfi {
+    code: String,
+    group: CfiGroup,
+}
+
+impl Cfi {
+    /// Return the CFI code as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    /// Return the category of the instrument.
+    pub fn category(&self) -> CfiCategory {
+        self.group.category()
+    }
+
+    /// Return the group of the instrument.
+    pub fn group(&self) -> CfiGroup {
+        self.group
+    }
+
+    /// Check that the category and group of the code are defined by ISO 10962:2019.
+    pub fn validate(&self) -> Result<(), CfiError> {
+        match self.group {
+            CfiGroup::Unknown(CfiCategory::Unknown(c), _) => Err(CfiError::BadCategory(c)),
+            CfiGroup::Unknown(category, g) => Err(CfiError::BadGroup(category.code(), g)),
+            _ => Ok(())
+        }
+    }
+
+    /// Whether the category and group of the code are defined by ISO 10962:2019.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Return the four raw attribute letters.
+    pub fn attribute_codes(&self) -> [char; 4] {
+        let mut attrs = ['X'; 4];
+        for (a, c) in attrs.iter_mut().zip(self.code.chars().skip(2)) {
+            *a = c;
+        }
+        attrs
+    }
+
+    /// Decode the attribute letters according to the instrument's group. If the group's
+    /// attributes are not decoded by this crate, or any of the letters is not valid for its
+    /// position, [`CfiAttributes::Other`] is returned.
+    pub fn attributes(&self) -> CfiAttributes {
+        let codes = self.attribute_codes();
+        decode_attributes(self.group, codes).unwrap_or(CfiAttributes::Other(codes))
+    }
+
+    /// Return a human-readable description of the category and group, eg, "Equities: Common/
+    /// ordinary shares".
+    pub fn description(&self) -> String {
+        format!("{}: {}", self.category().description(), self.group.description())
+    }
+
+    /// Whether the instrument is an equity (category `E`).
+    pub fn is_equity(&self) -> bool {
+        self.category() == CfiCategory::Equity
+    }
+
+    /// Whether the instrument is a debt instrument (category `D`).
+    pub fn is_debt(&self) -> bool {
+        self.category() == CfiCategory::Debt
+    }
+
+    /// Whether the instrument is a collective investment vehicle (category `C`).
+    pub fn is_collective_investment_vehicle(&self) -> bool {
+        self.category() == CfiCategory::CollectiveInvestmentVehicle
+    }
+
+    /// Whether the instrument is a derivative, ie, a listed or non-listed option, a future, a
+    /// swap, a forward or a strategy (categories `O`, `H`, `F`, `S`, `J` and `K`).
+    pub fn is_derivative(&self) -> bool {
+        matches!(
+            self.category(),
+            CfiCategory::ListedOption
+            | CfiCategory::NonListedOption
+            | CfiCategory::Future
+            | CfiCategory::Swap
+            | CfiCategory::Forward
+            | CfiCategory::Strategy
+        )
+    }
+
+    /// Whether the instrument is a listed or non-listed option (categories `O` and `H`).
+    pub fn is_option(&self) -> bool {
+        matches!(self.category(), CfiCategory::ListedOption | CfiCategory::NonListedOption)
+    }
+
+    /// Whether the instrument is a future (category `F`).
+    pub fn is_future(&self) -> bool {
+        self.category() == CfiCategory::Future
+    }
+
+    /// Whether the instrument is a swap (category `S`).
+    pub fn is_swap(&self) -> bool {
+        self.category() == CfiCategory::Swap
+    }
+}
+
+impl FromStr for Cfi {
+    type Err = CfiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 6 {
+            return Err(CfiError::BadLength(chars.len()));
+        }
+        if let Some(&c) = chars.iter().find(|c| !c.is_ascii_uppercase()) {
+            return Err(CfiError::BadCharacter(c));
+        }
+        let category = CfiCategory::from_code(chars[0]).unwrap_or(CfiCategory::Unknown(chars[0]));
+        let group = CfiGroup::from_codes(category, chars[1])
+            .unwrap_or(CfiGroup::Unknown(category, chars[1]));
+        Ok(Self { code: s.to_owned(), group })
+    }
+}
+
+impl Display for Cfi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+impl AsRef<str> for Cfi {
+    fn as_ref(&self) -> &str {
+        &self.code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cfi::*;
+
+    #[test]
+    fn test_parse_cfi() {
+        let cfi = Cfi::from_str("ESVUFR").unwrap();
+        assert_eq!(cfi.as_str(), "ESVUFR");
+        assert_eq!(cfi.group(), CfiGroup::CommonShares);
+        assert_eq!(cfi.description(), "Equities: Common/ordinary shares");
+        assert!(cfi.is_equity() && !cfi.is_derivative());
+        assert_eq!(cfi.attributes(), CfiAttributes::CommonShare(CommonShareAttributes {
+            voting_right: VotingRight::Voting,
+            transfer_restriction: TransferRestriction::Free,
+            payment_status: PaymentStatus::FullyPaid,
+            form: SecurityForm::Registered,
+        }));
+
+        let cfi = Cfi::from_str("DBFTFB").unwrap();
+        assert!(cfi.is_debt());
+        assert!(matches!(cfi.attributes(), CfiAttributes::Bond(BondAttributes {
+            interest_type: InterestType::Fixed,
+            guarantee: DebtGuarantee::GovernmentGuarantee,
+            redemption: Redemption::FixedMaturity,
+            form: SecurityForm::Bearer,
+        })));
+
+        let cfi = Cfi::from_str("OCEICS").unwrap();
+        assert!(cfi.is_option() && cfi.is_derivative());
+        assert!(matches!(cfi.attributes(), CfiAttributes::ListedOption(_)));
+
+        let cfi = Cfi::from_str("FFICSX").unwrap();
+        assert!(cfi.is_future());
+        assert!(matches!(cfi.attributes(), CfiAttributes::FinancialFuture(_)));
+
+        // Unknown attribute letters fall back to the raw codes
+        let cfi = Cfi::from_str("ESZZZZ").unwrap();
+        assert_eq!(cfi.attributes(), CfiAttributes::Other(['Z'; 4]));
+        // S and N are standardization values, not transfer restrictions
+        assert_eq!(TransferRestriction::from_str("S").ok(), None);
+        assert_eq!(TransferRestriction::from_str("N").ok(), None);
+        let cfi = Cfi::from_str("ESVSFR").unwrap();
+        assert_eq!(cfi.attributes(), CfiAttributes::Other(['V', 'S', 'F', 'R']));
+        let cfi = Cfi::from_str("ESVNFR").unwrap();
+        assert_eq!(cfi.attributes(), CfiAttributes::Other(['V', 'N', 'F', 'R']));
+        let cfi = Cfi::from_str("SRCCSP").unwrap();
+        assert!(cfi.is_swap());
+        assert_eq!(cfi.attributes(), CfiAttributes::Other(['C', 'C', 'S', 'P']));
+
+        assert!(matches!(Cfi::from_str("ESVUF"), Err(CfiError::BadLength(5))));
+        assert!(matches!(Cfi::from_str("esvufr"), Err(CfiError::BadCharacter('e'))));
+        assert!(Cfi::from_str("ESVUFR").unwrap().is_valid());
+
+        // Unknown categories and groups are kept, but rejected by validate()
+        let cfi = Cfi::from_str("ZSVUFR").unwrap();
+        assert_eq!(cfi.as_str(), "ZSVUFR");
+        assert_eq!(cfi.category(), CfiCategory::Unknown('Z'));
+        assert_eq!(cfi.group(), CfiGroup::Unknown(CfiCategory::Unknown('Z'), 'S'));
+        assert_eq!(cfi.attributes(), CfiAttributes::Other(['V', 'U', 'F', 'R']));
+        assert!(!cfi.is_equity() && !cfi.is_derivative());
+        assert_eq!(cfi.validate(), Err(CfiError::BadCategory('Z')));
+        let cfi = Cfi::from_str("EZVUFR").unwrap();
+        assert!(cfi.is_equity());
+        assert_eq!(cfi.group(), CfiGroup::Unknown(CfiCategory::Equity, 'Z'));
+        assert_eq!(cfi.description(), "Equities: Unknown group");
+        assert_eq!(cfi.validate(), Err(CfiError::BadGroup('E', 'Z')));
+    }
+
+    #[test]
+    fn test_group_codes_round_trip() {
+        for cat in "ECDROFSHIJKLTM".chars() {
+            let category = CfiCategory::from_code(cat).unwrap();
+            assert_eq!(category.code(), cat);
+            for code in 'A'..='Z' {
+                if let Some(group) = CfiGroup::from_codes(category, code) {
+                    assert_eq!(group.category(), category);
+                    assert_eq!(group.code(), code);
+                }
+            }
+        }
+    }
+}
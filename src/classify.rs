@@ -0,0 +1,325 @@
+//! Classification of financial instruments into broad classes, based on their CFI codes and the
+//! attributes present in their reference data.
+
+use crate::cfi::{CfiAttributes, CfiCategory, CfiGroup, FinancialUnderlying};
+use crate::model::ReferenceData;
+use crate::product_enums::{BaseProduct, EnvironmentalSubProduct};
+use strum_macros::{Display, EnumString};
+
+/// A broad class of financial instrument.
+///
+/// The string representation of each variant (as used by [`Display`] and [`FromStr`]) is a short
+/// upper-case code, which is used to store the class in the SQL database (where the codes are
+/// listed in the `InstrumentClass` table).
+///
+/// [`FromStr`]: std::str::FromStr
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, Display)]
+pub enum InstrumentClass {
+    /// Shares and other equities.
+    #[strum(serialize = "EQTY")]
+    Equity,
+    /// Depository receipts on equities, debt instruments or entitlements.
+    #[strum(serialize = "DPRC")]
+    DepositoryReceipt,
+    /// Exchange traded funds.
+    #[strum(serialize = "ETFS")]
+    Etf,
+    /// Collective investment vehicles other than ETFs.
+    #[strum(serialize = "FUND")]
+    Fund,
+    /// Bonds, notes, money market instruments and other debt instruments.
+    #[strum(serialize = "BOND")]
+    Bond,
+    /// Structured products, whether in equity or debt form.
+    #[strum(serialize = "STRC")]
+    StructuredProduct,
+    /// Rights, warrants and certificates.
+    #[strum(serialize = "ENTL")]
+    Entitlement,
+    /// Futures (other than commodity derivatives).
+    #[strum(serialize = "FUTR")]
+    Future,
+    /// Options (other than commodity derivatives).
+    #[strum(serialize = "OPTN")]
+    Option,
+    /// Swaps (other than commodity derivatives).
+    #[strum(serialize = "SWAP")]
+    Swap,
+    /// Foreign exchange forwards.
+    #[strum(serialize = "FXFW")]
+    FxForward,
+    /// Forwards other than FX forwards and commodity derivatives.
+    #[strum(serialize = "FWRD")]
+    Forward,
+    /// Commodity derivatives of any kind.
+    #[strum(serialize = "CMDT")]
+    CommodityDerivative,
+    /// Anything else (eg, spots, strategies, financing instruments).
+    #[strum(serialize = "OTHR")]
+    Other,
+}
+
+impl InstrumentClass {
+    /// Whether instruments of this class are derivatives.
+    pub fn is_derivative(&self) -> bool {
+        matches!(
+            self,
+            Self::Future | Self::Option | Self::Swap | Self::FxForward | Self::Forward
+                | Self::CommodityDerivative
+        )
+    }
+}
+
+/// The asset classes used in the MiFIR transparency regime: the equity and equity-like classes of
+/// RTS 1 and the non-equity classes of RTS 2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, Display)]
+pub enum MifirAssetClass {
+    /// Shares (RTS 1).
+    #[strum(serialize = "SHRS")]
+    Shares,
+    /// Depositary receipts (RTS 1).
+    #[strum(serialize = "DPRS")]
+    DepositaryReceipts,
+    /// Exchange traded funds (RTS 1).
+    #[strum(serialize = "ETFS")]
+    Etfs,
+    /// Certificates (RTS 1).
+    #[strum(serialize = "CRFT")]
+    Certificates,
+    /// Other equity-like financial instruments (RTS 1).
+    #[strum(serialize = "OTHR")]
+    OtherEquityLike,
+    /// Bonds (RTS 2).
+    #[strum(serialize = "BOND")]
+    Bonds,
+    /// Structured finance products, such as asset-backed and mortgage-backed securities (RTS 2).
+    #[strum(serialize = "SFPS")]
+    StructuredFinanceProducts,
+    /// Securitised derivatives, such as warrants and structured products (RTS 2).
+    #[strum(serialize = "SDRV")]
+    SecuritisedDerivatives,
+    /// Interest rate derivatives (RTS 2).
+    #[strum(serialize = "INTR")]
+    InterestRateDerivatives,
+    /// Equity derivatives (RTS 2).
+    #[strum(serialize = "EQUI")]
+    EquityDerivatives,
+    /// Commodity derivatives (RTS 2).
+    #[strum(serialize = "COMM")]
+    CommodityDerivatives,
+    /// Foreign exchange derivatives (RTS 2).
+    #[strum(serialize = "CURR")]
+    FxDerivatives,
+    /// Credit derivatives (RTS 2).
+    #[strum(serialize = "CRDT")]
+    CreditDerivatives,
+    /// Emission allowances and derivatives on them (RTS 2).
+    #[strum(serialize = "EMAL")]
+    EmissionAllowances,
+}
+
+impl MifirAssetClass {
+    /// Whether this is one of the equity or equity-like asset classes covered by RTS 1.
+    pub fn is_equity_like(&self) -> bool {
+        matches!(
+            self,
+            Self::Shares | Self::DepositaryReceipts | Self::Etfs | Self::Certificates
+                | Self::OtherEquityLike
+        )
+    }
+}
+
+/// Determine the asset class of a derivative's underlying from its CFI code, where possible.
+fn derivative_asset_class(group: CfiGroup, attributes: CfiAttributes) -> Option<MifirAssetClass> {
+    use CfiGroup::*;
+    match group {
+        RatesSwaps | RatesOptions | RatesForwards | RatesStrategies => Some(MifirAssetClass::InterestRateDerivatives),
+        EquitySwaps | EquityOptions | EquityForwards | EquityStrategies => Some(MifirAssetClass::EquityDerivatives),
+        CreditSwaps | CreditOptions | CreditForwards | CreditStrategies => Some(MifirAssetClass::CreditDerivatives),
+        FxSwaps | FxOptions | FxSpots | FxForwards | FxStrategies => Some(MifirAssetClass::FxDerivatives),
+        CommodityFutures | CommoditySwaps | CommodityOptions | CommoditySpots | CommodityForwards
+            | CommodityStrategies => Some(MifirAssetClass::CommodityDerivatives),
+        _ => {
+            let underlying = match attributes {
+                CfiAttributes::ListedOption(attrs) => attrs.underlying,
+                CfiAttributes::FinancialFuture(attrs) => attrs.underlying,
+                _ => return None
+            };
+            match underlying {
+                FinancialUnderlying::Equities | FinancialUnderlying::Indices
+                    | FinancialUnderlying::StockDividends => Some(MifirAssetClass::EquityDerivatives),
+                FinancialUnderlying::Debt | FinancialUnderlying::InterestRates => {
+                    Some(MifirAssetClass::InterestRateDerivatives)
+                },
+                FinancialUnderlying::Currencies => Some(MifirAssetClass::FxDerivatives),
+                FinancialUnderlying::Commodities => Some(MifirAssetClass::CommodityDerivatives),
+                _ => None
+            }
+        }
+    }
+}
+
+impl ReferenceData {
+    /// Whether the instrument is a commodity derivative, either because it is flagged as such, it
+    /// has commodity derivative attributes or its CFI code indicates a commodity derivative.
+    fn is_commodity_derivative(&self) -> bool {
+        use CfiGroup::*;
+        let has_commodity_attrs = self.derivative_attributes.as_ref()
+            .and_then(|d| d.asset_class_specific_attributes.as_ref())
+            .is_some_and(|a| a.commodity_attributes.is_some());
+        let commodity_group = matches!(
+            self.cfi.group(),
+            CommodityFutures | CommoditySwaps | CommodityOptions | CommodityForwards | CommodityStrategies
+        ) || matches!(
+            self.cfi.attributes(),
+            CfiAttributes::ListedOption(a) if a.underlying == FinancialUnderlying::Commodities
+        );
+        self.is_commodities_derivative || has_commodity_attrs || commodity_group
+    }
+
+    /// Classify the instrument into a broad [`InstrumentClass`].
+    ///
+    /// Classification is primarily based on the CFI code. Commodity derivatives (as determined by
+    /// the CFI code, the commodities derivative flag or the presence of commodity derivative
+    /// attributes) are always classified as [`InstrumentClass::CommodityDerivative`]. Where the CFI
+    /// category does not determine the class (spots, strategies, financing, referential and
+    /// miscellaneous instruments), an instrument with debt attributes is classified as a bond, and
+    /// a strategy (category `K`) on a currency pair as an FX forward. Anything else in those
+    /// categories, including a miscellaneous instrument with FX derivative attributes, is
+    /// classified as [`InstrumentClass::Other`].
+    pub fn classify(&self) -> InstrumentClass {
+        use CfiGroup::*;
+        let group = self.cfi.group();
+        if self.cfi.is_derivative() && self.is_commodity_derivative() {
+            return InstrumentClass::CommodityDerivative
+        }
+        match (self.cfi.category(), group) {
+            (_, EquityDepositoryReceipts | DebtDepositoryReceipts | EntitlementDepositoryReceipts) => {
+                InstrumentClass::DepositoryReceipt
+            },
+            (_, StructuredParticipationProducts | CapitalProtectionStructuredProducts
+                | NoCapitalProtectionStructuredProducts) => InstrumentClass::StructuredProduct,
+            (CfiCategory::Equity, _) => InstrumentClass::Equity,
+            (_, ExchangeTradedFunds) => InstrumentClass::Etf,
+            (CfiCategory::CollectiveInvestmentVehicle, _) => InstrumentClass::Fund,
+            (CfiCategory::Debt, _) => InstrumentClass::Bond,
+            (CfiCategory::Entitlement, _) => InstrumentClass::Entitlement,
+            (CfiCategory::Future, _) => InstrumentClass::Future,
+            (CfiCategory::ListedOption | CfiCategory::NonListedOption, _) => InstrumentClass::Option,
+            (CfiCategory::Swap, _) => InstrumentClass::Swap,
+            (_, FxForwards) => InstrumentClass::FxForward,
+            (CfiCategory::Forward, _) => InstrumentClass::Forward,
+            _ if self.debt_attributes.is_some() => InstrumentClass::Bond,
+            _ if self.currency_pair().is_some() && self.cfi.is_derivative() => InstrumentClass::FxForward,
+            _ => InstrumentClass::Other
+        }
+    }
+
+    /// Return the MiFIR asset class of the instrument, if it can be determined.
+    ///
+    /// For derivatives, the asset class of the underlying is determined from the CFI code where
+    /// possible, and otherwise from the asset class-specific attributes (if any). Emission
+    /// allowance derivatives are identified by their commodity base product.
+    pub fn mifir_asset_class(&self) -> Option<MifirAssetClass> {
+        use CfiGroup::*;
+        let group = self.cfi.group();
+        match self.classify() {
+            InstrumentClass::Equity => Some(MifirAssetClass::Shares),
+            InstrumentClass::DepositoryReceipt => Some(MifirAssetClass::DepositaryReceipts),
+            InstrumentClass::Etf => Some(MifirAssetClass::Etfs),
+            InstrumentClass::Fund => Some(MifirAssetClass::OtherEquityLike),
+            InstrumentClass::Bond if matches!(group, MortgageBackedSecurities | AssetBackedSecurities) => {
+                Some(MifirAssetClass::StructuredFinanceProducts)
+            },
+            InstrumentClass::Bond => Some(MifirAssetClass::Bonds),
+            InstrumentClass::StructuredProduct | InstrumentClass::Entitlement => {
+                Some(MifirAssetClass::SecuritisedDerivatives)
+            },
+            InstrumentClass::CommodityDerivative => {
+                let is_emissions = self.derivative_attributes.as_ref()
+                    .and_then(|d| d.asset_class_specific_attributes.as_ref())
+                    .and_then(|a| a.commodity_attributes.as_ref())
+                    .is_some_and(|c| matches!(
+                        c.product,
+                        BaseProduct::Environmental(EnvironmentalSubProduct::Emissions(_))
+                    ));
+                if is_emissions {
+                    Some(MifirAssetClass::EmissionAllowances)
+                } else {
+                    Some(MifirAssetClass::CommodityDerivatives)
+                }
+            },
+            InstrumentClass::Future | InstrumentClass::Option | InstrumentClass::Swap
+                | InstrumentClass::FxForward | InstrumentClass::Forward | InstrumentClass::Other => {
+                derivative_asset_class(group, self.cfi.attributes()).or_else(|| {
+                    let ac_attrs = self.derivative_attributes.as_ref()?
+                        .asset_class_specific_attributes.as_ref()?;
+                    if ac_attrs.ir_attributes.is_some() {
+                        Some(MifirAssetClass::InterestRateDerivatives)
+                    } else if ac_attrs.fx_attributes.is_some() {
+                        Some(MifirAssetClass::FxDerivatives)
+                    } else {
+                        None
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::tests::builder;
+    use crate::classify::{InstrumentClass, MifirAssetClass};
+    use crate::{AssetClassSpecificAttributes, DerivativeAttributes, FxDerivativeAttributes, ReferenceData};
+
+    fn ref_data(cfi: &str) -> ReferenceData {
+        builder(cfi).build_unchecked().unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            ("ESVUFR", InstrumentClass::Equity, Some(MifirAssetClass::Shares)),
+            ("EDSXFR", InstrumentClass::DepositoryReceipt, Some(MifirAssetClass::DepositaryReceipts)),
+            ("CEOGEU", InstrumentClass::Etf, Some(MifirAssetClass::Etfs)),
+            ("DBFTFB", InstrumentClass::Bond, Some(MifirAssetClass::Bonds)),
+            ("DAFXXX", InstrumentClass::Bond, Some(MifirAssetClass::StructuredFinanceProducts)),
+            ("RWSCCE", InstrumentClass::Entitlement, Some(MifirAssetClass::SecuritisedDerivatives)),
+            ("FFICSX", InstrumentClass::Future, Some(MifirAssetClass::EquityDerivatives)),
+            ("FCEPSX", InstrumentClass::CommodityDerivative, Some(MifirAssetClass::CommodityDerivatives)),
+            ("OCEFCS", InstrumentClass::Option, None),
+            ("SRCCSP", InstrumentClass::Swap, Some(MifirAssetClass::InterestRateDerivatives)),
+            ("JFTXFP", InstrumentClass::FxForward, Some(MifirAssetClass::FxDerivatives)),
+            ("JEIXSP", InstrumentClass::Forward, Some(MifirAssetClass::EquityDerivatives)),
+            ("LRXXXX", InstrumentClass::Other, None),
+        ];
+        for (cfi, class, asset_class) in cases {
+            let data = ref_data(cfi);
+            assert_eq!(data.classify(), class, "{cfi}");
+            assert_eq!(data.mifir_asset_class(), asset_class, "{cfi}");
+        }
+
+        // Commodity derivative flag takes precedence over the CFI category
+        let mut option = ref_data("OCEFCS");
+        option.is_commodities_derivative = true;
+        assert_eq!(option.classify(), InstrumentClass::CommodityDerivative);
+
+        // Miscellaneous derivative with FX attributes
+        let mut misc = ref_data("MMMXXX");
+        assert_eq!(misc.classify(), InstrumentClass::Other);
+        misc.derivative_attributes = Some(DerivativeAttributes {
+            asset_class_specific_attributes: Some(AssetClassSpecificAttributes {
+                fx_attributes: Some(FxDerivativeAttributes {
+                    notional_currency_2: "USD".parse().ok(),
+                    fx_type: None,
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(misc.mifir_asset_class(), Some(MifirAssetClass::FxDerivatives));
+        assert_eq!("FXFW".parse::<InstrumentClass>().unwrap(), InstrumentClass::FxForward);
+        assert_eq!(InstrumentClass::CommodityDerivative.to_string(), "CMDT");
+    }
+}
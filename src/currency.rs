@@ -0,0 +1,369 @@
+//! Currency codes (ISO 4217).
+
+use crate::error::CurrencyError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Active ISO 4217 currency codes, with their minor units (the number of digits after the decimal
+/// separator, or `None` where this is not applicable) and names. Sorted by code, so that it can be
+/// searched using a binary search.
+const ISO_4217: &[(&str, Option<u8>, &str)] = &[
+    ("AED", Some(2), "UAE Dirham"),
+    ("AFN", Some(2), "Afghani"),
+    ("ALL", Some(2), "Lek"),
+    ("AMD", Some(2), "Armenian Dram"),
+    ("ANG", Some(2), "Netherlands Antillean Guilder"),
+    ("AOA", Some(2), "Kwanza"),
+    ("ARS", Some(2), "Argentine Peso"),
+    ("AUD", Some(2), "Australian Dollar"),
+    ("AWG", Some(2), "Aruban Florin"),
+    ("AZN", Some(2), "Azerbaijan Manat"),
+    ("BAM", Some(2), "Convertible Mark"),
+    ("BBD", Some(2), "Barbados Dollar"),
+    ("BDT", Some(2), "Taka"),
+    ("BGN", Some(2), "Bulgarian Lev"),
+    ("BHD", Some(3), "Bahraini Dinar"),
+    ("BIF", Some(0), "Burundi Franc"),
+    ("BMD", Some(2), "Bermudian Dollar"),
+    ("BND", Some(2), "Brunei Dollar"),
+    ("BOB", Some(2), "Boliviano"),
+    ("BOV", Some(2), "Mvdol"),
+    ("BRL", Some(2), "Brazilian Real"),
+    ("BSD", Some(2), "Bahamian Dollar"),
+    ("BTN", Some(2), "Ngultrum"),
+    ("BWP", Some(2), "Pula"),
+    ("BYN", Some(2), "Belarusian Ruble"),
+    ("BZD", Some(2), "Belize Dollar"),
+    ("CAD", Some(2), "Canadian Dollar"),
+    ("CDF", Some(2), "Congolese Franc"),
+    ("CHE", Some(2), "WIR Euro"),
+    ("CHF", Some(2), "Swiss Franc"),
+    ("CHW", Some(2), "WIR Franc"),
+    ("CLF", Some(4), "Unidad de Fomento"),
+    ("CLP", Some(0), "Chilean Peso"),
+    ("CNY", Some(2), "Yuan Renminbi"),
+    ("COP", Some(2), "Colombian Peso"),
+    ("COU", Some(2), "Unidad de Valor Real"),
+    ("CRC", Some(2), "Costa Rican Colon"),
+    ("CUC", Some(2), "Peso Convertible"),
+    ("CUP", Some(2), "Cuban Peso"),
+    ("CVE", Some(2), "Cabo Verde Escudo"),
+    ("CZK", Some(2), "Czech Koruna"),
+    ("DJF", Some(0), "Djibouti Franc"),
+    ("DKK", Some(2), "Danish Krone"),
+    ("DOP", Some(2), "Dominican Peso"),
+    ("DZD", Some(2), "Algerian Dinar"),
+    ("EGP", Some(2), "Egyptian Pound"),
+    ("ERN", Some(2), "Nakfa"),
+    ("ETB", Some(2), "Ethiopian Birr"),
+    ("EUR", Some(2), "Euro"),
+    ("FJD", Some(2), "Fiji Dollar"),
+    ("FKP", Some(2), "Falkland Islands Pound"),
+    ("GBP", Some(2), "Pound Sterling"),
+    ("GEL", Some(2), "Lari"),
+    ("GHS", Some(2), "Ghana Cedi"),
+    ("GIP", Some(2), "Gibraltar Pound"),
+    ("GMD", Some(2), "Dalasi"),
+    ("GNF", Some(0), "Guinean Franc"),
+    ("GTQ", Some(2), "Quetzal"),
+    ("GYD", Some(2), "Guyana Dollar"),
+    ("HKD", Some(2), "Hong Kong Dollar"),
+    ("HNL", Some(2), "Lempira"),
+    ("HTG", Some(2), "Gourde"),
+    ("HUF", Some(2), "Forint"),
+    ("IDR", Some(2), "Rupiah"),
+    ("ILS", Some(2), "New Israeli Sheqel"),
+    ("INR", Some(2), "Indian Rupee"),
+    ("IQD", Some(3), "Iraqi Dinar"),
+    ("IRR", Some(2), "Iranian Rial"),
+    ("ISK", Some(0), "Iceland Krona"),
+    ("JMD", Some(2), "Jamaican Dollar"),
+    ("JOD", Some(3), "Jordanian Dinar"),
+    ("JPY", Some(0), "Yen"),
+    ("KES", Some(2), "Kenyan Shilling"),
+    ("KGS", Some(2), "Som"),
+    ("KHR", Some(2), "Riel"),
+    ("KMF", Some(0), "Comorian Franc"),
+    ("KPW", Some(2), "North Korean Won"),
+    ("KRW", Some(0), "Won"),
+    ("KWD", Some(3), "Kuwaiti Dinar"),
+    ("KYD", Some(2), "Cayman Islands Dollar"),
+    ("KZT", Some(2), "Tenge"),
+    ("LAK", Some(2), "Lao Kip"),
+    ("LBP", Some(2), "Lebanese Pound"),
+    ("LKR", Some(2), "Sri Lanka Rupee"),
+    ("LRD", Some(2), "Liberian Dollar"),
+    ("LSL", Some(2), "Loti"),
+    ("LYD", Some(3), "Libyan Dinar"),
+    ("MAD", Some(2), "Moroccan Dirham"),
+    ("MDL", Some(2), "Moldovan Leu"),
+    ("MGA", Some(2), "Malagasy Ariary"),
+    ("MKD", Some(2), "Denar"),
+    ("MMK", Some(2), "Kyat"),
+    ("MNT", Some(2), "Tugrik"),
+    ("MOP", Some(2), "Pataca"),
+    ("MRU", Some(2), "Ouguiya"),
+    ("MUR", Some(2), "Mauritius Rupee"),
+    ("MVR", Some(2), "Rufiyaa"),
+    ("MWK", Some(2), "Malawi Kwacha"),
+    ("MXN", Some(2), "Mexican Peso"),
+    ("MXV", Some(2), "Mexican Unidad de Inversion (UDI)"),
+    ("MYR", Some(2), "Malaysian Ringgit"),
+    ("MZN", Some(2), "Mozambique Metical"),
+    ("NAD", Some(2), "Namibia Dollar"),
+    ("NGN", Some(2), "Naira"),
+    ("NIO", Some(2), "Cordoba Oro"),
+    ("NOK", Some(2), "Norwegian Krone"),
+    ("NPR", Some(2), "Nepalese Rupee"),
+    ("NZD", Some(2), "New Zealand Dollar"),
+    ("OMR", Some(3), "Rial Omani"),
+    ("PAB", Some(2), "Balboa"),
+    ("PEN", Some(2), "Sol"),
+    ("PGK", Some(2), "Kina"),
+    ("PHP", Some(2), "Philippine Peso"),
+    ("PKR", Some(2), "Pakistan Rupee"),
+    ("PLN", Some(2), "Zloty"),
+    ("PYG", Some(0), "Guarani"),
+    ("QAR", Some(2), "Qatari Rial"),
+    ("RON", Some(2), "Romanian Leu"),
+    ("RSD", Some(2), "Serbian Dinar"),
+    ("RUB", Some(2), "Russian Ruble"),
+    ("RWF", Some(0), "Rwanda Franc"),
+    ("SAR", Some(2), "Saudi Riyal"),
+    ("SBD", Some(2), "Solomon Islands Dollar"),
+    ("SCR", Some(2), "Seychelles Rupee"),
+    ("SDG", Some(2), "Sudanese Pound"),
+    ("SEK", Some(2), "Swedish Krona"),
+    ("SGD", Some(2), "Singapore Dollar"),
+    ("SHP", Some(2), "Saint Helena Pound"),
+    ("SLE", Some(2), "Leone"),
+    ("SLL", Some(2), "Leone (old)"),
+    ("SOS", Some(2), "Somali Shilling"),
+    ("SRD", Some(2), "Surinam Dollar"),
+    ("SSP", Some(2), "South Sudanese Pound"),
+    ("STN", Some(2), "Dobra"),
+    ("SVC", Some(2), "El Salvador Colon"),
+    ("SYP", Some(2), "Syrian Pound"),
+    ("SZL", Some(2), "Lilangeni"),
+    ("THB", Some(2), "Baht"),
+    ("TJS", Some(2), "Somoni"),
+    ("TMT", Some(2), "Turkmenistan New Manat"),
+    ("TND", Some(3), "Tunisian Dinar"),
+    ("TOP", Some(2), "Pa'anga"),
+    ("TRY", Some(2), "Turkish Lira"),
+    ("TTD", Some(2), "Trinidad and Tobago Dollar"),
+    ("TWD", Some(2), "New Taiwan Dollar"),
+    ("TZS", Some(2), "Tanzanian Shilling"),
+    ("UAH", Some(2), "Hryvnia"),
+    ("UGX", Some(0), "Uganda Shilling"),
+    ("USD", Some(2), "US Dollar"),
+    ("USN", Some(2), "US Dollar (Next day)"),
+    ("UYI", Some(0), "Uruguay Peso en Unidades Indexadas (UI)"),
+    ("UYU", Some(2), "Peso Uruguayo"),
+    ("UYW", Some(4), "Unidad Previsional"),
+    ("UZS", Some(2), "Uzbekistan Sum"),
+    ("VED", Some(2), "Bolivar Soberano (digital)"),
+    ("VES", Some(2), "Bolivar Soberano"),
+    ("VND", Some(0), "Dong"),
+    ("VUV", Some(0), "Vatu"),
+    ("WST", Some(2), "Tala"),
+    ("XAF", Some(0), "CFA Franc BEAC"),
+    ("XAG", None, "Silver"),
+    ("XAU", None, "Gold"),
+    ("XBA", None, "Bond Markets Unit European Composite Unit (EURCO)"),
+    ("XBB", None, "Bond Markets Unit European Monetary Unit (E.M.U.-6)"),
+    ("XBC", None, "Bond Markets Unit European Unit of Account 9 (E.U.A.-9)"),
+    ("XBD", None, "Bond Markets Unit European Unit of Account 17 (E.U.A.-17)"),
+    ("XCD", Some(2), "East Caribbean Dollar"),
+    ("XCG", Some(2), "Caribbean Guilder"),
+    ("XDR", None, "SDR (Special Drawing Right)"),
+    ("XOF", Some(0), "CFA Franc BCEAO"),
+    ("XPD", None, "Palladium"),
+    ("XPF", Some(0), "CFP Franc"),
+    ("XPT", None, "Platinum"),
+    ("XSU", None, "Sucre"),
+    ("XTS", None, "Code reserved for testing"),
+    ("XUA", None, "ADB Unit of Account"),
+    ("XXX", None, "No currency"),
+    ("YER", Some(2), "Yemeni Rial"),
+    ("ZAR", Some(2), "Rand"),
+    ("ZMW", Some(2), "Zambian Kwacha"),
+    ("ZWG", Some(2), "Zimbabwe Gold"),
+    ("ZWL", Some(2), "Zimbabwe Dollar"),
+];
+
+/// A three-letter currency code (ISO 4217).
+///
+/// Any three upper-case ASCII letters are accepted as a `Currency`, so that codes which are not
+/// (or are no longer) in ISO 4217 can still be represented. Use [`Currency::is_iso`] to check
+/// whether the code is an active ISO 4217 code, or [`Currency::new_iso`] to reject codes that are
+/// not.
+///
+/// ```
+/// use firds::Currency;
+///
+/// let eur: Currency = "EUR".parse().unwrap();
+/// assert_eq!(eur.minor_units(), Some(2));
+/// assert_eq!(eur.name(), Some("Euro"));
+/// assert!("eur".parse::<Currency>().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// Create a new currency from the given code, returning an error if it does not consist of
+    /// three upper-case ASCII letters.
+    pub fn new(code: &str) -> Result<Self, CurrencyError> {
+        match code.as_bytes() {
+            &[a, b, c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => Ok(Self([a, b, c])),
+            _ => Err(CurrencyError::BadFormat(code.to_owned()))
+        }
+    }
+
+    /// Create a new currency from the given code, returning an error if it is not an active ISO
+    /// 4217 code.
+    pub fn new_iso(code: &str) -> Result<Self, CurrencyError> {
+        let currency = Self::new(code)?;
+        if currency.is_iso() {
+            Ok(currency)
+        } else {
+            Err(CurrencyError::UnknownCode(code.to_owned()))
+        }
+    }
+
+    /// Return the currency code as a string slice.
+    pub fn as_str(&self) -> &str {
+        // Guaranteed to be ASCII by the constructor.
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Look up the currency in the ISO 4217 table.
+    fn iso_entry(&self) -> Option<&'static (&'static str, Option<u8>, &'static str)> {
+        ISO_4217.binary_search_by(|(code, _, _)| code.cmp(&self.as_str()))
+            .ok()
+            .map(|i| &ISO_4217[i])
+    }
+
+    /// Whether the code is an active ISO 4217 code.
+    pub fn is_iso(&self) -> bool {
+        self.iso_entry().is_some()
+    }
+
+    /// Return the number of minor units of the currency (eg, 2 for EUR, 0 for JPY and 3 for KWD).
+    /// Returns `None` if the currency is not in ISO 4217 or minor units are not applicable (eg,
+    /// for precious metals).
+    pub fn minor_units(&self) -> Option<u8> {
+        self.iso_entry().and_then(|(_, minor_units, _)| *minor_units)
+    }
+
+    /// Return the ISO 4217 name of the currency, if it is in ISO 4217.
+    pub fn name(&self) -> Option<&'static str> {
+        self.iso_entry().map(|(_, _, name)| *name)
+    }
+
+    /// Whether the code represents a precious metal (gold, silver, platinum or palladium) rather
+    /// than a currency.
+    pub fn is_precious_metal(&self) -> bool {
+        matches!(self.as_str(), "XAU" | "XAG" | "XPT" | "XPD")
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Currency {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// A pair of currencies, eg, the two currencies of a foreign exchange derivative.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CurrencyPair {
+    /// The base currency (the first currency of the pair).
+    pub base: Currency,
+    /// The quote currency (the second currency of the pair).
+    pub quote: Currency,
+}
+
+impl CurrencyPair {
+    pub fn new(base: Currency, quote: Currency) -> Self {
+        Self { base, quote }
+    }
+
+    /// Return the pair with the base and quote currencies swapped.
+    pub fn inverse(&self) -> Self {
+        Self { base: self.quote, quote: self.base }
+    }
+
+    /// Whether the given currency is either the base or quote currency of the pair.
+    pub fn contains(&self, currency: Currency) -> bool {
+        self.base == currency || self.quote == currency
+    }
+}
+
+impl Display for CurrencyPair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.base, self.quote)
+    }
+}
+
+impl FromStr for CurrencyPair {
+    type Err = CurrencyError;
+
+    /// Parse a currency pair written either as `EUR/USD` or `EURUSD`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (base, quote) = match s.split_once('/') {
+            Some(pair) => pair,
+            None if s.len() == 6 && s.is_ascii() => s.split_at(3),
+            None => return Err(CurrencyError::BadFormat(s.to_owned()))
+        };
+        Ok(Self::new(base.parse()?, quote.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::currency::{Currency, CurrencyPair, ISO_4217};
+    use crate::error::CurrencyError;
+
+    #[test]
+    fn test_currency() {
+        assert!(ISO_4217.windows(2).all(|w| w[0].0 < w[1].0));
+        let jpy = Currency::new("JPY").unwrap();
+        assert_eq!(jpy.as_str(), "JPY");
+        assert_eq!(jpy.minor_units(), Some(0));
+        assert_eq!(Currency::new("KWD").unwrap().minor_units(), Some(3));
+        assert_eq!(Currency::new("XAU").unwrap().minor_units(), None);
+        assert!(Currency::new("XAU").unwrap().is_precious_metal());
+
+        let cnh = Currency::new("CNH").unwrap();
+        assert!(!cnh.is_iso());
+        assert_eq!(cnh.name(), None);
+        assert!(matches!(Currency::new_iso("CNH"), Err(CurrencyError::UnknownCode(_))));
+        assert!(matches!(Currency::new("EU"), Err(CurrencyError::BadFormat(_))));
+        assert!(matches!(Currency::new("Eur"), Err(CurrencyError::BadFormat(_))));
+        assert!(matches!(Currency::new("EURO"), Err(CurrencyError::BadFormat(_))));
+    }
+
+    #[test]
+    fn test_currency_pair() {
+        let pair: CurrencyPair = "EUR/USD".parse().unwrap();
+        assert_eq!(pair, "EURUSD".parse().unwrap());
+        assert_eq!(pair.to_string(), "EUR/USD");
+        assert_eq!(pair.inverse().to_string(), "USD/EUR");
+        assert!(pair.contains(Currency::new("USD").unwrap()));
+        assert!("EUR-USD".parse::<CurrencyPair>().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Configuration of the HTTP client used to search for and download FIRDS files.
+
+use crate::download::DownloadError;
+use reqwest::{Certificate, Client, IntoUrl, Proxy, Response};
+use serde_json::Value;
+use std::env;
+use std::fs::{read, read_to_string};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Environment variable specifying the proxy URL.
+pub const PROXY_ENV_VAR: &str = "FIRDS_PROXY";
+/// Environment variable specifying the path to a PEM file containing a root CA certificate.
+pub const CA_CERT_ENV_VAR: &str = "FIRDS_CA_CERT";
+/// Environment variable specifying the User-Agent header.
+pub const USER_AGENT_ENV_VAR: &str = "FIRDS_USER_AGENT";
+/// Environment variable specifying the maximum number of requests per second.
+pub const RATE_LIMIT_ENV_VAR: &str = "FIRDS_RATE_LIMIT";
+
+/// Configuration for a [`FirdsClient`]. All settings are optional; unset settings fall back to
+/// `reqwest`'s defaults (and no rate limit).
+///
+/// Configuration can be read from environment variables (see [`ClientConfig::from_env`]) or a
+/// JSON file (see [`ClientConfig::from_file`]), and configurations from several sources can be
+/// combined using [`ClientConfig::or`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientConfig {
+    /// URL of a proxy through which all requests should be sent.
+    pub proxy: Option<String>,
+    /// Path to a PEM file containing one or more root CA certificates to trust, in addition to
+    /// the system's certificates.
+    pub ca_cert: Option<PathBuf>,
+    /// The User-Agent header to send with each request.
+    pub user_agent: Option<String>,
+    /// The maximum number of requests to make per second.
+    pub rate_limit: Option<f64>,
+}
+
+impl ClientConfig {
+    /// Read configuration from the `FIRDS_PROXY`, `FIRDS_CA_CERT`, `FIRDS_USER_AGENT` and
+    /// `FIRDS_RATE_LIMIT` environment variables. Unset or empty variables are ignored.
+    pub fn from_env() -> Result<Self, DownloadError> {
+        let var = |k: &str| env::var(k).ok().filter(|v| !v.is_empty());
+        Ok(Self {
+            proxy: var(PROXY_ENV_VAR),
+            ca_cert: var(CA_CERT_ENV_VAR).map(PathBuf::from),
+            user_agent: var(USER_AGENT_ENV_VAR),
+            rate_limit: var(RATE_LIMIT_ENV_VAR)
+                .map(|v| v.parse().map_err(|_| DownloadError::BadConfig(
+                    format!("{RATE_LIMIT_ENV_VAR} is not a number: {v}")
+                )))
+                .transpose()?,
+        })
+    }
+
+    /// Read configuration from a JSON file containing an object with any of the keys `proxy`,
+    /// `ca_cert`, `user_agent` (strings) and `rate_limit` (a number).
+    pub fn from_file(fpath: &Path) -> Result<Self, DownloadError> {
+        let json: Value = serde_json::from_str(&read_to_string(fpath)?)?;
+        let map = json.as_object().ok_or(DownloadError::BadJson)?;
+        let str_value = |k: &str| match map.get(k) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.to_owned())),
+            Some(_) => Err(DownloadError::BadConfig(format!("{k} must be a string")))
+        };
+        Ok(Self {
+            proxy: str_value("proxy")?,
+            ca_cert: str_value("ca_cert")?.map(PathBuf::from),
+            user_agent: str_value("user_agent")?,
+            rate_limit: match map.get("rate_limit") {
+                None | Some(Value::Null) => None,
+                Some(v) => Some(v.as_f64().ok_or(
+                    DownloadError::BadConfig("rate_limit must be a number".to_owned())
+                )?)
+            },
+        })
+    }
+
+    /// Combine two configurations, taking each setting from `self` if it is set and from `other`
+    /// otherwise.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            proxy: self.proxy.or(other.proxy),
+            ca_cert: self.ca_cert.or(other.ca_cert),
+            user_agent: self.user_agent.or(other.user_agent),
+            rate_limit: self.rate_limit.or(other.rate_limit),
+        }
+    }
+
+    /// Build a [`FirdsClient`] from this configuration.
+    pub fn build(&self) -> Result<FirdsClient, DownloadError> {
+        let mut builder = Client::builder();
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        if let Some(ca_cert) = &self.ca_cert {
+            for cert in Certificate::from_pem_bundle(&read(ca_cert)?)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        let throttle = match self.rate_limit {
+            Some(r) if r > 0.0 && r.is_finite() => Some(Throttle::new(Duration::from_secs_f64(1.0 / r))),
+            Some(r) => return Err(DownloadError::BadConfig(
+                format!("rate limit must be a positive number: {r}")
+            )),
+            None => None
+        };
+        Ok(FirdsClient { client: builder.build()?, throttle })
+    }
+}
+
+/// Spaces out requests so that no more than one is started per `interval`.
+#[derive(Debug)]
+struct Throttle {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl Throttle {
+    fn new(interval: Duration) -> Self {
+        Self { interval, next: Mutex::new(Instant::now()) }
+    }
+
+    /// Wait until the next request is permitted. The lock is held while waiting, so concurrent
+    /// callers are released one at a time.
+    async fn wait(&self) {
+        let mut next = self.next.lock().await;
+        sleep_until(*next).await;
+        *next = Instant::now() + self.interval;
+    }
+}
+
+/// A HTTP client used to search for and download FIRDS files, which applies the rate limit (if
+/// any) specified in its [`ClientConfig`].
+#[derive(Debug, Default)]
+pub struct FirdsClient {
+    client: Client,
+    throttle: Option<Throttle>,
+}
+
+impl FirdsClient {
+    /// Create a new client with default settings and no rate limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send a GET request to the given URL, first waiting if necessary to respect the rate limit.
+    pub async fn get(&self, url: impl IntoUrl) -> Result<Response, DownloadError> {
+        if let Some(throttle) = &self.throttle {
+            throttle.wait().await;
+        }
+        Ok(self.client.get(url).send().await?)
+    }
+}
+
+impl From<Client> for FirdsClient {
+    /// Wrap an existing [`Client`], with no rate limit.
+    fn from(client: Client) -> Self {
+        Self { client, throttle: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::client::{ClientConfig, Throttle};
+    use std::env::temp_dir;
+    use std::fs::write;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    fn test_config_from_file_and_merge() {
+        let path = temp_dir().join("firds_test_client_config.json");
+        write(&path, r#"{"proxy": "http://proxy.example.com:8080", "rate_limit": 2}"#).unwrap();
+        let from_file = ClientConfig::from_file(&path).unwrap();
+        assert_eq!(from_file.proxy.as_deref(), Some("http://proxy.example.com:8080"));
+        assert_eq!(from_file.rate_limit, Some(2.0));
+
+        let from_cli = ClientConfig {
+            user_agent: Some("test-agent".to_owned()),
+            rate_limit: Some(0.5),
+            ..Default::default()
+        };
+        let config = from_cli.or(from_file);
+        assert_eq!(config, ClientConfig {
+            proxy: Some("http://proxy.example.com:8080".to_owned()),
+            ca_cert: None,
+            user_agent: Some("test-agent".to_owned()),
+            rate_limit: Some(0.5),
+        });
+        assert!(config.build().is_ok());
+
+        write(&path, r#"{"rate_limit": "fast"}"#).unwrap();
+        assert!(ClientConfig::from_file(&path).is_err());
+        let bad_cert = ClientConfig { ca_cert: Some(PathBuf::from("/nonexistent.pem")), ..Default::default() };
+        assert!(bad_cert.build().is_err());
+        let bad_rate = ClientConfig { rate_limit: Some(0.0), ..Default::default() };
+        assert!(bad_rate.build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_throttle() {
+        let throttle = Throttle::new(Duration::from_millis(50));
+        let start = Instant::now();
+        for _ in 0..3 {
+            throttle.wait().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
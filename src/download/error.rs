@@ -28,6 +28,16 @@ pub enum DownloadError {
     ZipError(zip::result::ZipError),
     /// Error parsing or creating a URL.
     UrlError(url::ParseError),
+    /// Zip archive failed an integrity check (eg, a bad central directory or CRC32 mismatch). The
+    /// contained string describes the failure.
+    ZipCheckFailed(String),
+    /// XML file in a zip archive is not well-formed (eg, because it is truncated). The contained
+    /// string describes the failure.
+    XmlCheckFailed(String),
+    /// A file name does not follow the FIRDS naming convention.
+    BadFileName(String),
+    /// Invalid client configuration. The contained string describes the problem.
+    BadConfig(String),
 }
 
 impl Display for DownloadError {
@@ -45,6 +55,10 @@ impl Display for DownloadError {
             Self::NoMd5Sum => write!(f, "No MD5 sum was provided"),
             Self::ZipError(e) => write!(f, "Error extracting file from zip archive: {e}"),
             Self::UrlError(e) => write!(f, "Error constructing URL: {e}"),
+            Self::ZipCheckFailed(e) => write!(f, "Zip integrity check failed: {e}"),
+            Self::XmlCheckFailed(e) => write!(f, "XML integrity check failed: {e}"),
+            Self::BadFileName(n) => write!(f, "File name does not follow FIRDS convention: {n}"),
+            Self::BadConfig(e) => write!(f, "Invalid client configuration: {e}"),
         }
     }
 }
@@ -1,14 +1,30 @@
 #![cfg(feature = "download-cli")]
 
-use chrono::NaiveDate;
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use clap::Parser;
-use std::path::PathBuf;
+use std::fs::{metadata, remove_file, File};
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Instant;
 use log::warn;
-use futures::future::join_all;
+use futures::stream::{FuturesUnordered, StreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use firds::download::{search_esma, search_fca, FirdsDocType, FirdsSource, StreamProgress};
-
-mod error;
+use serde_json::{json, Value};
+use firds::download::{
+    latest_complete_set,
+    search,
+    ClientConfig,
+    DownloadError,
+    DownloadState,
+    FirdsClient,
+    FirdsDoc,
+    FirdsDocType,
+    FirdsSource,
+    SearchQuery,
+    StreamProgress,
+    VerifyMode
+};
 
 struct ProgressBarHolder(ProgressBar);
 
@@ -36,80 +52,332 @@ fn new_progress_bar(file_name: &str) -> ProgressBar {
     )
 }
 
+/// How many days back to search when no start date is given for `--latest` or `--since-state`.
+const DEFAULT_LOOKBACK_DAYS: u64 = 14;
+
 #[derive(Parser, Debug)]
 struct Args {
-    /// Start date of period to search.
-    from_date: NaiveDate,
-    /// End date of period to search.
-    to_date: NaiveDate,
+    /// Start date of period to search. Optional if --latest or --since-state is given.
+    #[clap(required_unless_present_any = ["latest", "since_state"])]
+    from_date: Option<NaiveDate>,
+    /// End date of period to search. Optional if --latest or --since-state is given.
+    #[clap(required_unless_present_any = ["latest", "since_state"])]
+    to_date: Option<NaiveDate>,
+    /// Only download the most recent complete publication set of the given type (eg, all FULINS
+    /// files published on the latest date for which every part is available).
+    #[clap(long, conflicts_with = "since_state")]
+    latest: Option<FirdsDocType>,
+    /// Only download files published since the last run, as recorded in the given JSON state
+    /// file, and update the state file afterwards. Downloads DLTINS files unless --file-type is
+    /// given.
+    #[clap(long)]
+    since_state: Option<PathBuf>,
     /// Directory to download files to.
     #[clap(short, long)]
     to_dir: Option<PathBuf>,
-    /// Type of FIRDS file to search for.
+    /// Type of FIRDS file to search for. Can be given more than once.
     #[clap(short, long)]
-    file_type: Option<FirdsDocType>,
+    file_type: Vec<FirdsDocType>,
+    /// Only download FULINS files for instruments in the given CFI categories (eg, "DE" for debt
+    /// and equities). Does not affect DLTINS or FULCAN files.
+    #[clap(short, long)]
+    cfi: Option<String>,
+    /// Only download files whose name matches the given glob pattern. Can be given more than once.
+    #[clap(short = 'n', long)]
+    file_name: Vec<String>,
+    /// Search by file timestamp rather than by publication date.
+    #[clap(long, action)]
+    by_timestamp: bool,
     /// Where to search for the files.
     #[clap(short, long, default_value = "esma")]
     source: FirdsSource,
-    /// Verify the MD5 sum of a file downloaded from ESMA. Does nothing where files are downloaded
-    /// from the FCA.
-    #[clap(short, long, action)]
-    verify: bool,
+    /// How to verify downloaded files. Passing the flag with no value verifies the MD5 sum of files
+    /// downloaded from ESMA; other modes must be given with an equals sign (eg, `--verify=full`).
+    /// As the FCA does not publish MD5 sums, checksum verification of FCA files falls back to
+    /// checking the zip file's CRCs.
+    #[clap(
+        short,
+        long,
+        value_enum,
+        default_value = "none",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "checksum"
+    )]
+    verify: VerifyMode,
     /// Overwrite an existing file with the same name.
     #[clap(short, long, action)]
     overwrite: bool,
     /// Keep zip files after extracting to XML (by default, these are deleted).
     #[clap(short, long, action)]
-    keep_zip: bool
+    keep_zip: bool,
+    /// Only list the files found by the search; do not download them.
+    #[clap(short, long, action)]
+    list_only: bool,
+    /// Print one JSON object per file to stdout, describing the file and the outcome of
+    /// downloading it.
+    #[clap(short, long, action)]
+    json: bool,
+    /// Write one JSON object per file to the given file, describing the file and the outcome of
+    /// downloading it.
+    #[clap(short, long)]
+    report: Option<PathBuf>,
+    /// JSON file from which to read HTTP client configuration. Settings given as command line
+    /// options or environment variables take precedence over those in the file.
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// URL of a proxy to send all requests through. Can also be set using the FIRDS_PROXY
+    /// environment variable.
+    #[clap(long)]
+    proxy: Option<String>,
+    /// Path to a PEM file containing additional root CA certificates to trust. Can also be set
+    /// using the FIRDS_CA_CERT environment variable.
+    #[clap(long)]
+    ca_cert: Option<PathBuf>,
+    /// User-Agent header to send with each request. Can also be set using the FIRDS_USER_AGENT
+    /// environment variable.
+    #[clap(long)]
+    user_agent: Option<String>,
+    /// Maximum number of HTTP requests to make per second. Can also be set using the
+    /// FIRDS_RATE_LIMIT environment variable.
+    #[clap(long)]
+    rate_limit: Option<f64>,
+}
+
+/// Build the HTTP client configuration from command line options, environment variables and the
+/// config file (if any), in that order of precedence.
+fn client_config(args: &Args) -> Result<ClientConfig, DownloadError> {
+    let from_args = ClientConfig {
+        proxy: args.proxy.clone(),
+        ca_cert: args.ca_cert.clone(),
+        user_agent: args.user_agent.clone(),
+        rate_limit: args.rate_limit,
+    };
+    let from_file = match &args.config {
+        Some(path) => ClientConfig::from_file(path)?,
+        None => ClientConfig::default()
+    };
+    Ok(from_args.or(ClientConfig::from_env()?).or(from_file))
+}
+
+/// Writes JSON records describing each file to stdout and/or a report file, one per line.
+struct Reporter {
+    to_stdout: bool,
+    report_file: Option<File>,
+}
+
+impl Reporter {
+    fn new(to_stdout: bool, report_path: Option<&Path>) -> std::io::Result<Self> {
+        Ok(Self {
+            to_stdout,
+            report_file: report_path.map(File::create).transpose()?,
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        self.to_stdout || self.report_file.is_some()
+    }
+
+    fn emit(&mut self, record: &Value) -> std::io::Result<()> {
+        if self.to_stdout {
+            writeln!(stdout(), "{record}")?;
+        }
+        if let Some(f) = &mut self.report_file {
+            writeln!(f, "{record}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the result of verifying a downloaded file, for reporting purposes. If the download
+/// failed for some other reason (such as a network error) before it could be verified, the
+/// verification is reported as "not_run".
+fn checksum_status(verify: VerifyMode, result: &Result<PathBuf, DownloadError>) -> &'static str {
+    match result {
+        _ if verify == VerifyMode::None => "skipped",
+        Err(DownloadError::Md5CheckFailed(_))
+        | Err(DownloadError::ZipCheckFailed(_))
+        | Err(DownloadError::XmlCheckFailed(_)) => "failed",
+        Err(DownloadError::NoMd5Sum) => "unavailable",
+        Err(_) => "not_run",
+        Ok(_) => "passed",
+    }
+}
+
+/// Download and extract a single file, returning a JSON record describing the outcome.
+async fn download_doc(
+    doc: &FirdsDoc,
+    client: &FirdsClient,
+    to_dir: &Path,
+    args: &Args,
+    verify: VerifyMode,
+    progress: ProgressBarHolder
+) -> Value {
+    let start = Instant::now();
+    let mut zip_path = None;
+    let mut xml_path = None;
+    let mut size = None;
+    let mut error = None;
+    let zip_res = doc.download_zip_with_progress(
+        client,
+        to_dir,
+        args.overwrite,
+        verify,
+        &progress
+    ).await;
+    let status = checksum_status(verify, &zip_res);
+    match zip_res {
+        Ok(zip_fpath) => {
+            size = metadata(&zip_fpath).ok().map(|m| m.len());
+            progress.on_msg("Extracting...");
+            match doc.extract_xml(&zip_fpath, to_dir, args.overwrite) {
+                Ok(xml_fpath) => {
+                    xml_path = Some(xml_fpath);
+                    if args.keep_zip {
+                        zip_path = Some(zip_fpath);
+                    } else if let Err(e) = remove_file(&zip_fpath) {
+                        error = Some(e.to_string());
+                        zip_path = Some(zip_fpath);
+                    }
+                },
+                Err(e) => {
+                    error = Some(e.to_string());
+                    zip_path = Some(zip_fpath);
+                }
+            }
+        },
+        Err(e) => error = Some(e.to_string())
+    }
+    progress.on_msg(if error.is_some() { "Failed" } else { "Done" });
+    let mut record = doc.to_json();
+    if let Value::Object(map) = &mut record {
+        map.insert("zip_path".to_owned(), json!(zip_path));
+        map.insert("xml_path".to_owned(), json!(xml_path));
+        map.insert("size".to_owned(), json!(size));
+        map.insert("verify_mode".to_owned(), json!(verify.to_string()));
+        map.insert("checksum_status".to_owned(), json!(status));
+        map.insert("elapsed_secs".to_owned(), json!(start.elapsed().as_secs_f64()));
+        map.insert("error".to_owned(), json!(error));
+    }
+    record
 }
 
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
-    let ft_str = if let Some(ft) = args.file_type {
-        ft.to_string()
-    } else {
+    let now = Utc::now();
+    let lookback_start = now - Days::new(DEFAULT_LOOKBACK_DAYS);
+    let state = args.since_state.as_deref()
+        .map(DownloadState::load)
+        .transpose()
+        .expect("Could not load state file");
+    let doc_types = match (args.latest, &state) {
+        (Some(doc_type), _) => vec![doc_type],
+        (None, Some(_)) if args.file_type.is_empty() => vec![FirdsDocType::Dltins],
+        _ => args.file_type.clone()
+    };
+    let from_dt: DateTime<Utc> = match (&state, args.from_date) {
+        (Some(DownloadState { since: Some(since), .. }), _) => *since,
+        (_, Some(d)) => d.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+        (_, None) => lookback_start
+    };
+    let to_dt = match args.to_date {
+        Some(d) if state.is_none() => d.and_hms_opt(23, 59, 59).unwrap().and_utc(),
+        _ => now
+    };
+    let ft_str = if doc_types.is_empty() {
         "all".to_string()
+    } else {
+        doc_types.iter().map(FirdsDocType::to_string).collect::<Vec<String>>().join("/")
     };
     eprintln!(
         "Searching {} FIRDS for {} files from {} to {}.",
         args.source,
         ft_str,
-        args.from_date,
-        args.to_date,
+        from_dt,
+        to_dt,
     );
-    let client = reqwest::Client::new();
-    let docs = match args.source {
-        FirdsSource::Esma => {
-            let from_dt = args.from_date.and_hms_opt(0, 0, 0)
-                .unwrap()
-                .and_utc();
-            let to_dt = args.to_date.and_hms_opt(23, 59, 59)
-                .unwrap()
-                .and_utc();
-            search_esma(&client, from_dt, to_dt, args.file_type).await.unwrap()
-        },
-        FirdsSource::Fca => 
-            search_fca(&client, args.from_date, args.to_date, args.file_type).await.unwrap()
+    let mut query = SearchQuery::new().doc_types(doc_types);
+    query = if args.by_timestamp || state.is_some() {
+        query.timestamp_between(from_dt, to_dt)
+    } else {
+        query.published_between(from_dt, to_dt)
     };
+    if let Some(cfi) = &args.cfi {
+        query = query.cfi_categories(cfi.chars());
+    }
+    for pattern in &args.file_name {
+        query = query.file_name_pattern(pattern);
+    }
+    let client = client_config(&args)
+        .and_then(|config| config.build())
+        .unwrap_or_else(|e| {
+            eprintln!("Could not configure HTTP client: {e}");
+            exit(2);
+        });
+    let mut docs = search(&client, args.source, &query).await.unwrap();
+    if let Some(doc_type) = args.latest {
+        docs = latest_complete_set(docs, doc_type);
+    }
+    if let Some(state) = &state {
+        docs.retain(|doc| !state.is_downloaded(doc));
+    }
     eprintln!("Found {} files.", docs.len());
-    if let Some(to_dir) = args.to_dir {
-        if args.verify && (args.source == FirdsSource::Fca) {
-            warn!("Verification only possible where FIRDS source is ESMA. Not verifying.")
+    let mut reporter = Reporter::new(args.json, args.report.as_deref())
+        .expect("Could not create report file");
+    let to_dir = match &args.to_dir {
+        Some(d) if !args.list_only => d,
+        _ => {
+            for doc in &docs {
+                if reporter.is_active() {
+                    reporter.emit(&doc.to_json()).expect("Could not write report");
+                } else {
+                    println!("{}\t{}\t{}\t{}", doc.file_name, doc.file_type, doc.timestamp, doc.download_link);
+                }
+            }
+            return;
+        }
+    };
+    let verify = if args.verify == VerifyMode::Checksum && args.source == FirdsSource::Fca {
+        warn!("Checksum verification only possible where FIRDS source is ESMA. Checking zip CRCs instead.");
+        VerifyMode::ZipCrc
+    } else {
+        args.verify
+    };
+    let multi_prog = MultiProgress::new();
+    let (client, args, multi_prog) = (&client, &args, &multi_prog);
+    let mut downloads = docs.iter()
+        .map(|doc| async move {
+            let record = download_doc(
+                doc,
+                client,
+                to_dir,
+                args,
+                verify,
+                ProgressBarHolder(multi_prog.add(new_progress_bar(&doc.file_name)))
+            ).await;
+            (doc, record)
+        })
+        .collect::<FuturesUnordered<_>>();
+    let mut succeeded = vec![];
+    let mut failed = vec![];
+    // Report each file as soon as it has been downloaded, so that the report is not lost if the
+    // program is interrupted.
+    while let Some((doc, record)) = downloads.next().await {
+        if record["error"].is_null() {
+            succeeded.push(doc);
+        } else {
+            failed.push(doc);
+        }
+        if reporter.is_active() {
+            reporter.emit(&record).expect("Could not write report");
         }
-        let multi_prog = MultiProgress::new();
-        let f = docs.iter()
-            .map(|doc| {
-                doc.download_xml_with_progress(
-                    &client,
-                    &to_dir,
-                    args.overwrite,
-                    args.verify,
-                    !args.keep_zip,
-                    ProgressBarHolder(multi_prog.add(new_progress_bar(&doc.file_name)))
-                )
-            });
-        join_all(f).await;
-    }
-    
-}
\ No newline at end of file
+    }
+    if let (Some(mut state), Some(state_path)) = (state, &args.since_state) {
+        state.update(&succeeded, &failed);
+        state.save(state_path).expect("Could not save state file");
+    }
+    if !failed.is_empty() {
+        exit(1);
+    }
+}
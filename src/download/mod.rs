@@ -1,13 +1,30 @@
 //! Code for searching the ESMA or FCA FIRDS websites, downloading ZIP files and extracting XML
 //! files.
 
+mod client;
 mod error;
-
-use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+mod query;
+mod sets;
+mod state;
+mod verify;
+
+pub use client::{
+    ClientConfig,
+    FirdsClient,
+    CA_CERT_ENV_VAR,
+    PROXY_ENV_VAR,
+    RATE_LIMIT_ENV_VAR,
+    USER_AGENT_ENV_VAR
+};
+pub use error::DownloadError;
+pub use query::SearchQuery;
+pub use sets::{latest_complete_set, FileNameInfo};
+pub use state::{DownloadState, DownloadedFile};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
 use futures_util::StreamExt;
 use md5::{Digest, Md5};
-use reqwest::Client;
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
 use std::ffi::OsString;
 use std::fmt::{Display, Formatter};
 use std::fs::{create_dir_all, remove_file, rename, File};
@@ -15,7 +32,6 @@ use std::io;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use crate::download::error::DownloadError;
 
 #[cfg(feature = "download-cli")]
 use clap::ValueEnum;
@@ -119,6 +135,34 @@ impl Display for FirdsSource {
     }
 }
 
+/// How to verify a downloaded zip file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "download-cli", derive(ValueEnum))]
+pub enum VerifyMode {
+    /// Compare the MD5 sum of the file against the checksum published with it. Only possible for
+    /// files downloaded from ESMA; the FCA does not publish checksums.
+    Checksum,
+    /// Check the zip archive's central directory and the CRC32 of each entry. Can be used for
+    /// files from either source.
+    ZipCrc,
+    /// Carry out all available checks: the MD5 checksum (if one was published), the zip checks
+    /// and a check that the XML file is well-formed up to the closing tag of its root element.
+    Full,
+    /// Do not verify the file.
+    None,
+}
+
+impl Display for VerifyMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Checksum => write!(f, "checksum"),
+            Self::ZipCrc => write!(f, "zip-crc"),
+            Self::Full => write!(f, "full"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
 /// A single document reference, returned by searching a FIRDS database (ESMA or FCA).
 pub struct FirdsDoc {
     /// The source of the document.
@@ -171,6 +215,19 @@ impl FirdsDoc {
         }
     }
 
+    /// Return a JSON object describing the document.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "source": self.source.to_string(),
+            "file_id": self.file_id,
+            "file_name": self.file_name,
+            "file_type": self.file_type.to_string(),
+            "timestamp": self.timestamp.to_rfc3339(),
+            "download_link": self.download_link,
+            "checksum": self.checksum,
+        })
+    }
+
     /// Verify the md5 checksum of the file at the given path. Assumes that a checksum is present in
     /// the struct, returning an error if not.
     pub fn verify_file(&self, fpath: &Path) -> Result<(), DownloadError> {
@@ -189,16 +246,35 @@ impl FirdsDoc {
         Ok(())
     }
 
+    /// Verify the zip file at the given path according to the given [`VerifyMode`].
+    ///
+    /// Returns [`DownloadError::NoMd5Sum`] if `mode` is [`VerifyMode::Checksum`] and no checksum is
+    /// present in the struct (which will be the case for FCA documents). [`VerifyMode::Full`] skips
+    /// the checksum comparison where there is no checksum to compare against.
+    pub fn verify(&self, fpath: &Path, mode: VerifyMode) -> Result<(), DownloadError> {
+        match mode {
+            VerifyMode::Checksum => self.verify_file(fpath),
+            VerifyMode::ZipCrc => verify::verify_zip(fpath, false),
+            VerifyMode::Full => {
+                if self.checksum.is_some() {
+                    self.verify_file(fpath)?;
+                }
+                verify::verify_zip(fpath, true)
+            },
+            VerifyMode::None => Ok(())
+        }
+    }
+
     /// Download the zip file from the source, tracking progress of the download.
     ///
     /// This method takes the same arguments as [`FirdsDoc::download_zip`] as well as an additional
     /// `progress` argument, a struct that implements the [`StreamProgress`] trait.
     pub async fn download_zip_with_progress(
         &self,
-        client: &Client,
+        client: &FirdsClient,
         to_dir: &Path,
         overwrite: bool,
-        verify: bool,
+        verify: VerifyMode,
         progress: &impl StreamProgress,
     ) -> Result<PathBuf, DownloadError> {
         progress.on_msg("Downloading...");
@@ -217,7 +293,7 @@ impl FirdsDoc {
             // at downloading
             remove_file(&fpath_part)?
         }
-        let resp = client.get(&self.download_link).send().await?;
+        let resp = client.get(&self.download_link).await?;
         let mut file = File::create(&fpath_part)?;
         progress.on_init(resp.content_length().unwrap_or(0));
 
@@ -228,9 +304,7 @@ impl FirdsDoc {
             progress.on_progress(bytes.len() as u64)
 
         }
-        if verify {
-            self.verify_file(&fpath_part)?;
-        }
+        self.verify(&fpath_part, verify)?;
         rename(&fpath_part, &fpath)?;
         Ok(fpath)
     }
@@ -242,37 +316,59 @@ impl FirdsDoc {
     ///
     /// # Arguments:
     ///
-    /// * `client`: A [`Client`] that will be used to make the request.
+    /// * `client`: A [`FirdsClient`] that will be used to make the request.
     /// * `to_dir`: The directory in which to save the downloaded file. It will be created if it
     ///   does not exist.
     /// * `overwrite`: Whether to overwrite an existing file if it exists at the destination. If
     ///   `false`, an error will be returned if a file already exists.
-    /// * `verify`: Whether to verify the file after it is downloaded by comparing its md5 sum
-    ///   against the checksum stored in the struct. If `true` and no checksum is present in the
-    ///   struct, an error will be returned.
+    /// * `verify`: How to verify the zip file after it is downloaded (see [`VerifyMode`]). If
+    ///   verification fails, an error is returned and the partially downloaded file is left in
+    ///   place.
     ///
     /// To track the progress of the download (eg, using a progress bar), see
     /// [`FirdsDoc::download_zip_with_progress`].
     pub async fn download_zip(
         &self,
-        client: &Client,
+        client: &FirdsClient,
         to_dir: &Path,
         overwrite: bool,
-        verify: bool,
+        verify: VerifyMode,
     ) -> Result<PathBuf, DownloadError> {
         self.download_zip_with_progress(client, to_dir, overwrite, verify, &_NoopProgress).await
     }
 
+    /// Extract the XML file from the zip file at `zip_fpath` (which should have been downloaded using
+    /// [`FirdsDoc::download_zip`]) to `to_dir`, returning the path to the extracted file. If
+    /// `overwrite` is `false`, an error will be returned if the XML file already exists.
+    pub fn extract_xml(
+        &self,
+        zip_fpath: &Path,
+        to_dir: &Path,
+        overwrite: bool
+    ) -> Result<PathBuf, DownloadError> {
+        let zipped_file = File::open(zip_fpath)?;
+        let mut archive = zip::ZipArchive::new(zipped_file)?;
+        let unzipped_fname = self.file_name.replace(".zip", ".xml");
+        let mut zip_file = archive.by_name(&unzipped_fname)?;
+        let unzipped_fpath = to_dir.join(&unzipped_fname);
+        if !overwrite && unzipped_fpath.exists() {
+            return Err(DownloadError::FileExists(unzipped_fpath))
+        }
+        let mut unzipped_file = File::create(&unzipped_fpath)?;
+        io::copy(&mut zip_file, &mut unzipped_file)?;
+        Ok(unzipped_fpath)
+    }
+
     /// Download the XML file from the source, tracking progress of the download.
     ///
     /// This method takes the same arguments as [`FirdsDoc::download_xml`] as well as an additional
     /// `progress` argument, a struct that implements the [`StreamProgress`] trait.
     pub async fn download_xml_with_progress(
         &self,
-        client: &Client,
+        client: &FirdsClient,
         to_dir: &Path,
         overwrite: bool,
-        verify: bool,
+        verify: VerifyMode,
         delete_zip: bool,
         progress: impl StreamProgress
     ) -> Result<PathBuf, DownloadError> {
@@ -283,18 +379,8 @@ impl FirdsDoc {
             verify,
             &progress
         ).await?;
-        let zipped_file = File::open(&zip_fpath)?;
-        let mut archive = zip::ZipArchive::new(zipped_file)?;
-        let unzipped_fname = self.file_name.replace(".zip", ".xml");
-        let mut zip_file = archive.by_name(&unzipped_fname)?;
-        let unzipped_fpath = to_dir.join(&unzipped_fname);
-        if !overwrite && unzipped_fpath.exists() {
-            return Err(DownloadError::FileExists(unzipped_fpath))
-        }
-        let mut unzipped_file = File::create(&unzipped_fpath)?;
         progress.on_msg("Extracting...");
-
-        io::copy(&mut zip_file, &mut unzipped_file)?;
+        let unzipped_fpath = self.extract_xml(&zip_fpath, to_dir, overwrite)?;
         if delete_zip {
             remove_file(&zip_fpath)?;
         }
@@ -306,14 +392,14 @@ impl FirdsDoc {
     ///
     /// # Arguments:
     ///
-    /// * `client`: A [`Client`] that will be used to make the request.
+    /// * `client`: A [`FirdsClient`] that will be used to make the request.
     /// * `to_dir`: The directory in which to save the downloaded file. It will be created if it
     ///   does not exist.
     /// * `overwrite`: Whether to overwrite an existing file if it exists at the destination. If
     ///   `false`, an error will be returned if a file already exists.
-    /// * `verify`: Whether to verify the file after it is downloaded by comparing its md5 sum
-    ///   against the checksum stored in the struct. If `true` and no checksum is present in the
-    ///   struct, an error will be returned.
+    /// * `verify`: How to verify the zip file after it is downloaded (see [`VerifyMode`]). If
+    ///   verification fails, an error is returned and the partially downloaded file is left in
+    ///   place.
     /// * `delete_zip`: Whether to delete the downloaded zip file once the XML file has been
     ///   extracted.
     ///
@@ -321,10 +407,10 @@ impl FirdsDoc {
     /// [`FirdsDoc::download_xml_with_progress`].
     pub async fn download_xml(
         &self,
-        client: &Client,
+        client: &FirdsClient,
         to_dir: &Path,
         overwrite: bool,
-        verify: bool,
+        verify: VerifyMode,
         delete_zip: bool,
     ) -> Result<PathBuf, DownloadError> {
         self.download_xml_with_progress(
@@ -341,36 +427,40 @@ impl FirdsDoc {
 /// Search the ESMA FIRDS database for files from the given time period and, if applicable, of the
 /// given type.
 pub async fn search_esma(
-    client: &Client,
+    client: &FirdsClient,
     from_datetime: DateTime<Utc>,
     to_datetime: DateTime<Utc>,
     file_type: Option<FirdsDocType>
 ) -> Result<Vec<FirdsDoc>, DownloadError> {
-    let from_dt_str = from_datetime.format("%Y-%m-%dT%H:%M:%SZ");
-    let to_dt_str = to_datetime.format("%Y-%m-%dT%H:%M:%SZ");
-    let pub_date_fq = format!("publication_date:[{from_dt_str} TO {to_dt_str}]");
-    let q = if let Some(ft) = file_type {
-        ft.to_string()
-    } else {
-        "*".to_owned()
-    };
+    let mut query = SearchQuery::new().published_between(from_datetime, to_datetime);
+    if let Some(ft) = file_type {
+        query = query.doc_type(ft);
+    }
+    search_esma_query(client, &query).await
+}
+
+/// Search the ESMA FIRDS database for files matching the given [`SearchQuery`].
+pub async fn search_esma_query(
+    client: &FirdsClient,
+    query: &SearchQuery
+) -> Result<Vec<FirdsDoc>, DownloadError> {
+    let filters = query.to_esma_filters();
     let mut start = 0;
     let rows = 100;
     let rows_str = rows.to_string();
     let mut num_found= -1;
     let mut docs: Vec<FirdsDoc> = vec![];
     while (num_found < 0) || (num_found > start) {
-        let url = reqwest::Url::parse_with_params(
-            ESMA_BASE_URL,
-            &[
-                ("q", q.as_str()),
-                ("fq", pub_date_fq.as_str()),
-                ("wt", "json"),
-                ("start", &start.to_string()),
-                ("rows", &rows_str),
-            ]
-        )?;
-        let text = client.get(url).send().await?.text().await?;
+        let start_str = start.to_string();
+        let mut params = vec![
+            ("q", "*"),
+            ("wt", "json"),
+            ("start", start_str.as_str()),
+            ("rows", rows_str.as_str()),
+        ];
+        params.extend(filters.iter().map(|fq| ("fq", fq.as_str())));
+        let url = reqwest::Url::parse_with_params(ESMA_BASE_URL, &params)?;
+        let text = client.get(url).await?.text().await?;
         let json: Value = serde_json::from_str(&text)?;
         let resp_body = json.get("response")
             .and_then(Value::as_object)
@@ -380,11 +470,8 @@ pub async fn search_esma(
                 .and_then(Value::as_i64)
                 .ok_or(DownloadError::BadJson)?;
         }
-        let resp = json.get("response")
-            .and_then(Value::as_object)
-            .ok_or(DownloadError::BadJson)?;
         docs.extend(
-            resp.get("docs")
+            resp_body.get("docs")
                 .and_then(Value::as_array)
                 .ok_or(DownloadError::BadJson)?
                 .iter()
@@ -396,20 +483,30 @@ pub async fn search_esma(
     Ok(docs)
 }
 
+/// Search the FCA FIRDS database for files published between the given dates and, if applicable,
+/// of the given type.
 pub async fn search_fca(
-    client: &Client,
+    client: &FirdsClient,
     from_date: NaiveDate,
     to_date: NaiveDate,
     file_type: Option<FirdsDocType>
 ) -> Result<Vec<FirdsDoc>, DownloadError> {
-    let from_date_str = from_date.format("%Y-%m-%d");
-    let to_date_str = to_date.format("%Y-%m-%d");
-    let pub_date_q = format!("publication_date:[{from_date_str} TO {to_date_str}]");
-    let q = if let Some(ft) = file_type {
-        format!("((file_type:{}) AND ({pub_date_q}))", ft.to_string())
-    } else {
-        format!("({pub_date_q})")
-    };
+    let mut query = SearchQuery::new().published_between(
+        from_date.and_time(NaiveTime::MIN).and_utc(),
+        to_date.and_time(NaiveTime::MIN).and_utc()
+    );
+    if let Some(ft) = file_type {
+        query = query.doc_type(ft);
+    }
+    search_fca_query(client, &query).await
+}
+
+/// Search the FCA FIRDS database for files matching the given [`SearchQuery`].
+pub async fn search_fca_query(
+    client: &FirdsClient,
+    query: &SearchQuery
+) -> Result<Vec<FirdsDoc>, DownloadError> {
+    let q = query.to_fca_query();
     let mut start = 0;
     let rows = 100;
     let rows_str = rows.to_string();
@@ -424,7 +521,7 @@ pub async fn search_fca(
                 ("size", &rows_str),
             ]
         )?;
-        let text = client.get(url).send().await?.text().await?;
+        let text = client.get(url).await?.text().await?;
         let json: Value = serde_json::from_str(&text)?;
         let resp_body = json.get("hits")
             .and_then(Value::as_object)
@@ -447,15 +544,26 @@ pub async fn search_fca(
     Ok(docs)
 }
 
+/// Search the given source for files matching the given [`SearchQuery`].
+pub async fn search(
+    client: &FirdsClient,
+    source: FirdsSource,
+    query: &SearchQuery
+) -> Result<Vec<FirdsDoc>, DownloadError> {
+    match source {
+        FirdsSource::Esma => search_esma_query(client, query).await,
+        FirdsSource::Fca => search_fca_query(client, query).await,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{NaiveDate, TimeZone, Utc};
-    use reqwest::Client;
-    use crate::download::{search_esma, search_fca};
+    use crate::download::{search_esma, search_fca, FirdsClient};
 
     #[tokio::test]
     async fn test_search_fca() {
-        let client = Client::new();
+        let client = FirdsClient::new();
         let all_docs = search_fca(
             &client,
             NaiveDate::from_ymd_opt(2024, 10, 15).expect("Bad date"),
@@ -468,7 +576,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_search_esma() {
-        let client = Client::new();
+        let client = FirdsClient::new();
         let all_docs = search_esma(
             &client,
             Utc.with_ymd_and_hms(2024, 10, 15, 0, 0, 0).unwrap(),
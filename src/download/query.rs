@@ -0,0 +1,246 @@
+//! A builder for search queries against the ESMA and FCA FIRDS file indexes.
+//!
+//! Filters are translated into the query language of the relevant index (Solr filter queries for
+//! ESMA, an Elasticsearch query string for the FCA) so that the filtering is done server-side.
+
+use crate::download::FirdsDocType;
+use chrono::{DateTime, Utc};
+
+/// A window of time, inclusive at both ends.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct DateWindow {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+/// A query for FIRDS files, built up from a number of optional filters.
+///
+/// Filters of different kinds are combined with AND. Multiple values for the same kind of filter
+/// (eg, several document types or CFI categories) are combined with OR.
+///
+/// ```
+/// use firds::download::{FirdsDocType, SearchQuery};
+/// use chrono::{TimeZone, Utc};
+///
+/// let query = SearchQuery::new()
+///     .doc_type(FirdsDocType::Fulins)
+///     .cfi_category('D')
+///     .cfi_category('E')
+///     .published_between(
+///         Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+///         Utc.with_ymd_and_hms(2025, 2, 1, 23, 59, 59).unwrap()
+///     );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    doc_types: Vec<FirdsDocType>,
+    cfi_categories: Vec<char>,
+    file_name_patterns: Vec<String>,
+    publication_window: Option<DateWindow>,
+    timestamp_window: Option<DateWindow>,
+}
+
+/// Escape characters that have a special meaning in Lucene query syntax (which is used by both
+/// Solr and Elasticsearch). If `keep_wildcards` is true, `*` and `?` are not escaped so that they
+/// can be used as glob wildcards.
+fn escape_term(s: &str, keep_wildcards: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '*' | '?' if keep_wildcards => escaped.push(c),
+            '+' | '-' | '&' | '|' | '!' | '(' | ')' | '{' | '}' | '[' | ']' | '^' | '"' | '~'
+            | '*' | '?' | ':' | '\\' | '/' | ' ' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            _ => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+/// Join a list of clauses with OR, wrapping the result in parentheses.
+fn or_clauses(clauses: impl IntoIterator<Item = String>) -> String {
+    format!("({})", clauses.into_iter().collect::<Vec<String>>().join(" OR "))
+}
+
+impl SearchQuery {
+    /// Create a new query with no filters, which will match all files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match files of the given type. Can be called more than once to match several types.
+    pub fn doc_type(mut self, doc_type: FirdsDocType) -> Self {
+        if !self.doc_types.contains(&doc_type) {
+            self.doc_types.push(doc_type);
+        }
+        self
+    }
+
+    /// Only match files of any of the given types.
+    pub fn doc_types(self, doc_types: impl IntoIterator<Item = FirdsDocType>) -> Self {
+        doc_types.into_iter().fold(self, Self::doc_type)
+    }
+
+    /// Only match FULINS files relating to instruments whose CFI code begins with the given letter
+    /// (eg, 'D' for debt instruments or 'E' for equities). Can be called more than once to match
+    /// several categories.
+    ///
+    /// FULINS files are split by CFI category, but DLTINS and FULCAN files are not. So this filter
+    /// only restricts which FULINS files are matched; DLTINS and FULCAN files (if otherwise matched
+    /// by the query) are unaffected.
+    pub fn cfi_category(mut self, category: char) -> Self {
+        let category = category.to_ascii_uppercase();
+        if !self.cfi_categories.contains(&category) {
+            self.cfi_categories.push(category);
+        }
+        self
+    }
+
+    /// Only match FULINS files relating to any of the given CFI categories. See
+    /// [`SearchQuery::cfi_category`].
+    pub fn cfi_categories(self, categories: impl IntoIterator<Item = char>) -> Self {
+        categories.into_iter().fold(self, Self::cfi_category)
+    }
+
+    /// Only match files whose name matches the given glob pattern. `*` matches any number of
+    /// characters and `?` matches a single character. Can be called more than once to match any
+    /// of several patterns.
+    pub fn file_name_pattern(mut self, pattern: &str) -> Self {
+        self.file_name_patterns.push(pattern.to_owned());
+        self
+    }
+
+    /// Only match files published between the given times (inclusive).
+    ///
+    /// The FCA index only records the publication date, not the time, so when searching the FCA
+    /// only the date part of `from` and `to` is used.
+    pub fn published_between(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.publication_window = Some(DateWindow { from, to });
+        self
+    }
+
+    /// Only match files whose timestamp falls between the given times (inclusive). For ESMA this
+    /// is the `timestamp` field of the file record; for the FCA it is the `last_refreshed` field.
+    pub fn timestamp_between(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.timestamp_window = Some(DateWindow { from, to });
+        self
+    }
+
+    /// Return the clauses that are common to both the ESMA and FCA query syntaxes, ie, those
+    /// relating to doc type, CFI category and file name.
+    fn common_clauses(&self) -> Vec<String> {
+        let mut clauses = vec![];
+        if !self.doc_types.is_empty() {
+            clauses.push(format!(
+                "file_type:{}",
+                or_clauses(self.doc_types.iter().map(FirdsDocType::to_string))
+            ));
+        }
+        if !self.cfi_categories.is_empty() {
+            // Only FULINS files are split by CFI category, so let any other files through.
+            let mut cfi_clauses: Vec<String> = self.cfi_categories.iter()
+                .map(|c| format!("file_name:FULINS_{}_*", escape_term(&c.to_string(), false)))
+                .collect();
+            cfi_clauses.push("(*:* -file_type:FULINS)".to_owned());
+            clauses.push(or_clauses(cfi_clauses));
+        }
+        if !self.file_name_patterns.is_empty() {
+            clauses.push(format!(
+                "file_name:{}",
+                or_clauses(self.file_name_patterns.iter().map(|p| escape_term(p, true)))
+            ));
+        }
+        clauses
+    }
+
+    /// Return the Solr filter queries (`fq` parameters) used to search the ESMA FIRDS index.
+    pub fn to_esma_filters(&self) -> Vec<String> {
+        let fmt = "%Y-%m-%dT%H:%M:%SZ";
+        let mut filters = self.common_clauses();
+        if let Some(w) = self.publication_window {
+            filters.push(
+                format!("publication_date:[{} TO {}]", w.from.format(fmt), w.to.format(fmt))
+            );
+        }
+        if let Some(w) = self.timestamp_window {
+            filters.push(format!("timestamp:[{} TO {}]", w.from.format(fmt), w.to.format(fmt)));
+        }
+        filters
+    }
+
+    /// Return the Elasticsearch query string (`q` parameter) used to search the FCA FIRDS index.
+    pub fn to_fca_query(&self) -> String {
+        let mut clauses = self.common_clauses();
+        if let Some(w) = self.publication_window {
+            clauses.push(format!(
+                "publication_date:[{} TO {}]",
+                w.from.format("%Y-%m-%d"),
+                w.to.format("%Y-%m-%d")
+            ));
+        }
+        if let Some(w) = self.timestamp_window {
+            let fmt = "%Y-%m-%dT%H:%M:%SZ";
+            clauses.push(format!(
+                "last_refreshed:[{} TO {}]",
+                escape_term(&w.from.format(fmt).to_string(), false),
+                escape_term(&w.to.format(fmt).to_string(), false)
+            ));
+        }
+        if clauses.is_empty() {
+            "*".to_owned()
+        } else {
+            clauses.iter()
+                .map(|c| format!("({c})"))
+                .collect::<Vec<String>>()
+                .join(" AND ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::query::SearchQuery;
+    use crate::download::FirdsDocType;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_esma_filters() {
+        let query = SearchQuery::new()
+            .doc_types([FirdsDocType::Fulins, FirdsDocType::Dltins])
+            .cfi_categories(['d', 'E'])
+            .published_between(
+                Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 2, 7, 23, 59, 59).unwrap()
+            );
+        assert_eq!(query.to_esma_filters(), vec![
+            "file_type:(FULINS OR DLTINS)",
+            "(file_name:FULINS_D_* OR file_name:FULINS_E_* OR (*:* -file_type:FULINS))",
+            "publication_date:[2025-02-01T00:00:00Z TO 2025-02-07T23:59:59Z]",
+        ]);
+    }
+
+    #[test]
+    fn test_fca_query() {
+        let query = SearchQuery::new()
+            .doc_type(FirdsDocType::Fulins)
+            .file_name_pattern("FULINS_?_2025*")
+            .timestamp_between(
+                Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 2, 7, 12, 30, 0).unwrap()
+            );
+        assert_eq!(
+            query.to_fca_query(),
+            "(file_type:(FULINS)) AND (file_name:(FULINS_?_2025*)) AND \
+            (last_refreshed:[2025\\-02\\-01T00\\:00\\:00Z TO 2025\\-02\\-07T12\\:30\\:00Z])"
+        );
+        assert_eq!(SearchQuery::new().to_fca_query(), "*");
+    }
+
+    #[test]
+    fn test_escape_pattern() {
+        let query = SearchQuery::new().file_name_pattern("FULINS (copy)*.zip");
+        assert_eq!(query.to_esma_filters(), vec!["file_name:(FULINS\\ \\(copy\\)*.zip)"]);
+    }
+}
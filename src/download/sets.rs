@@ -0,0 +1,162 @@
+//! Grouping FIRDS files into publication sets based on their file names.
+//!
+//! FIRDS files are named according to a fixed convention, eg, `FULINS_D_20250201_01of02.zip` is
+//! the first of two FULINS files for debt instruments published on 1 February 2025, and
+//! `DLTINS_20250203_01of01.zip` is the only DLTINS file published on 3 February 2025. A
+//! publication set is all of the files of a given type published on a given date.
+
+use crate::download::{DownloadError, FirdsDoc, FirdsDocType};
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// Information about a FIRDS file, parsed from its name.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FileNameInfo {
+    /// The type of the file.
+    pub doc_type: FirdsDocType,
+    /// For FULINS files, the first letter of the CFI codes of the instruments in the file.
+    pub cfi_category: Option<char>,
+    /// The publication date of the file.
+    pub date: NaiveDate,
+    /// The (1-based) number of this file within its set.
+    pub part: u32,
+    /// The total number of files in the set.
+    pub total: u32,
+}
+
+impl FromStr for FileNameInfo {
+    type Err = DownloadError;
+
+    /// Parse a FIRDS file name (with or without a `.zip` or `.xml` extension).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_name = || DownloadError::BadFileName(s.to_owned());
+        let stem = s.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(s);
+        let tokens: Vec<&str> = stem.split('_').collect();
+        let (doc_type, cfi_category, date, parts) = match tokens.as_slice() {
+            [dt, cfi, date, parts] => {
+                let mut chars = cfi.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_uppercase() => (*dt, Some(c), *date, *parts),
+                    _ => return Err(bad_name())
+                }
+            },
+            [dt, date, parts] => (*dt, None, *date, *parts),
+            _ => return Err(bad_name())
+        };
+        let (part, total) = parts.split_once("of").ok_or_else(bad_name)?;
+        Ok(Self {
+            doc_type: FirdsDocType::from_str(doc_type)?,
+            cfi_category,
+            date: NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| bad_name())?,
+            part: part.parse().map_err(|_| bad_name())?,
+            total: total.parse().map_err(|_| bad_name())?,
+        })
+    }
+}
+
+impl FirdsDoc {
+    /// Parse information about the file from its name.
+    pub fn file_name_info(&self) -> Result<FileNameInfo, DownloadError> {
+        FileNameInfo::from_str(&self.file_name)
+    }
+}
+
+/// Check whether the given files form a complete set, ie, whether, for each CFI category present,
+/// every part from 1 up to the stated total is present.
+fn is_complete(infos: &[FileNameInfo]) -> bool {
+    let mut by_category: BTreeMap<Option<char>, (u32, BTreeSet<u32>)> = BTreeMap::new();
+    for info in infos {
+        let (total, parts) = by_category.entry(info.cfi_category)
+            .or_insert((info.total, BTreeSet::new()));
+        if *total != info.total {
+            return false;
+        }
+        parts.insert(info.part);
+    }
+    !by_category.is_empty() && by_category.values()
+        .all(|(total, parts)| (1..=*total).all(|p| parts.contains(&p)))
+}
+
+/// Return the files belonging to the most recent complete publication set of the given type.
+///
+/// Files of other types, and files whose names cannot be parsed, are ignored. A set is considered
+/// complete if, for each CFI category present, all of the parts of that category are present
+/// (eg, both `01of02` and `02of02`). Note that this cannot detect a set which is missing an entire
+/// CFI category. If no complete set is found, an empty vector is returned.
+pub fn latest_complete_set(docs: Vec<FirdsDoc>, doc_type: FirdsDocType) -> Vec<FirdsDoc> {
+    let mut by_date: BTreeMap<NaiveDate, Vec<(FileNameInfo, FirdsDoc)>> = BTreeMap::new();
+    for doc in docs {
+        if let Ok(info) = doc.file_name_info() {
+            if info.doc_type == doc_type {
+                by_date.entry(info.date).or_default().push((info, doc));
+            }
+        }
+    }
+    while let Some((_, set)) = by_date.pop_last() {
+        let infos: Vec<FileNameInfo> = set.iter().map(|(info, _)| *info).collect();
+        if is_complete(&infos) {
+            return set.into_iter().map(|(_, doc)| doc).collect();
+        }
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::sets::{latest_complete_set, FileNameInfo};
+    use crate::download::{FirdsDoc, FirdsDocType, FirdsSource};
+    use chrono::{DateTime, NaiveDate};
+    use std::str::FromStr;
+
+    fn doc(file_name: &str) -> FirdsDoc {
+        FirdsDoc {
+            source: FirdsSource::Esma,
+            download_link: format!("https://example.com/{file_name}"),
+            file_id: file_name.to_owned(),
+            file_name: file_name.to_owned(),
+            file_type: FileNameInfo::from_str(file_name).unwrap().doc_type,
+            timestamp: DateTime::parse_from_rfc3339("2025-02-01T08:00:00Z").unwrap(),
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        let info = FileNameInfo::from_str("FULINS_D_20250201_01of02.zip").unwrap();
+        assert_eq!(info, FileNameInfo {
+            doc_type: FirdsDocType::Fulins,
+            cfi_category: Some('D'),
+            date: NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            part: 1,
+            total: 2,
+        });
+        let info = FileNameInfo::from_str("DLTINS_20250203_01of01.xml").unwrap();
+        assert_eq!(info.doc_type, FirdsDocType::Dltins);
+        assert_eq!(info.cfi_category, None);
+        assert!(FileNameInfo::from_str("FULINS_D_20250201.zip").is_err());
+        assert!(FileNameInfo::from_str("FOOBAR_20250201_01of01.zip").is_err());
+    }
+
+    #[test]
+    fn test_latest_complete_set() {
+        let docs = vec![
+            doc("FULINS_D_20250125_01of02.zip"),
+            doc("FULINS_D_20250125_02of02.zip"),
+            doc("FULINS_E_20250125_01of01.zip"),
+            doc("FULINS_D_20250201_01of02.zip"),
+            doc("FULINS_E_20250201_01of01.zip"),
+            doc("DLTINS_20250203_01of01.zip"),
+        ];
+        let set = latest_complete_set(docs, FirdsDocType::Fulins);
+        let names: Vec<&str> = set.iter().map(|d| d.file_name.as_str()).collect();
+        assert_eq!(names, vec![
+            "FULINS_D_20250125_01of02.zip",
+            "FULINS_D_20250125_02of02.zip",
+            "FULINS_E_20250125_01of01.zip",
+        ]);
+        let set = latest_complete_set(vec![doc("DLTINS_20250203_01of01.zip")], FirdsDocType::Dltins);
+        assert_eq!(set.len(), 1);
+        assert!(latest_complete_set(vec![], FirdsDocType::Fulcan).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! A small persistent record of previous downloads, used to download only those files which have
+//! been published since the last run.
+
+use crate::download::{DownloadError, FirdsDoc};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::fs::{read_to_string, rename, write};
+use std::io;
+use std::path::Path;
+
+/// A file which has been downloaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadedFile {
+    /// The name of the file.
+    pub file_name: String,
+    /// The timestamp of the file.
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The state of previous downloads.
+///
+/// `since` is the time from which the next search should start. Files with a timestamp at or
+/// after `since` which have already been downloaded are recorded in `downloaded`, so that they are
+/// not downloaded again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DownloadState {
+    /// The timestamp from which to search for new files, or `None` if nothing has been downloaded.
+    pub since: Option<DateTime<Utc>>,
+    /// Files which have already been downloaded.
+    pub downloaded: Vec<DownloadedFile>,
+}
+
+impl DownloadState {
+    /// Load the state from the JSON file at the given path. If the file does not exist, an empty
+    /// state is returned.
+    pub fn load(fpath: &Path) -> Result<Self, DownloadError> {
+        let text = match read_to_string(fpath) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into())
+        };
+        Self::from_json(&serde_json::from_str(&text)?)
+    }
+
+    /// Save the state as JSON to the given path. The state is written to a temporary file which
+    /// is then renamed, so that an interrupted write does not corrupt an existing state file.
+    pub fn save(&self, fpath: &Path) -> Result<(), DownloadError> {
+        let mut tmp_path = fpath.as_os_str().to_owned();
+        tmp_path.push(".part");
+        write(&tmp_path, serde_json::to_string_pretty(&self.to_json())?)?;
+        rename(&tmp_path, fpath)?;
+        Ok(())
+    }
+
+    /// Parse the state from a JSON object.
+    pub fn from_json(json: &Value) -> Result<Self, DownloadError> {
+        let since = match json.get("since") {
+            None | Some(Value::Null) => None,
+            Some(v) => Some(parse_timestamp(v)?)
+        };
+        let downloaded = json.get("downloaded")
+            .and_then(Value::as_array)
+            .ok_or(DownloadError::BadJson)?
+            .iter()
+            .map(|f| Ok(DownloadedFile {
+                file_name: f.get("file_name")
+                    .and_then(Value::as_str)
+                    .ok_or(DownloadError::BadJson)?
+                    .to_owned(),
+                timestamp: parse_timestamp(f.get("timestamp").ok_or(DownloadError::BadJson)?)?,
+            }))
+            .collect::<Result<Vec<DownloadedFile>, DownloadError>>()?;
+        Ok(Self { since, downloaded })
+    }
+
+    /// Return a JSON object representing the state.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "since": self.since.map(|t| t.to_rfc3339()),
+            "downloaded": self.downloaded.iter().map(|f| json!({
+                "file_name": f.file_name,
+                "timestamp": f.timestamp.to_rfc3339(),
+            })).collect::<Vec<Value>>(),
+        })
+    }
+
+    /// Whether the given document has already been downloaded.
+    pub fn is_downloaded(&self, doc: &FirdsDoc) -> bool {
+        self.downloaded.iter().any(|f| f.file_name == doc.file_name)
+    }
+
+    /// Update the state after a run. `succeeded` are the documents that were downloaded
+    /// successfully and `failed` are those that were not.
+    ///
+    /// If any downloads failed, `since` is moved forward only as far as the earliest failed
+    /// document, so that it is retried on the next run. Otherwise it is moved forward to the
+    /// latest downloaded document. Records of downloaded files from before `since` are dropped, as
+    /// they will not be returned by future searches.
+    pub fn update(&mut self, succeeded: &[&FirdsDoc], failed: &[&FirdsDoc]) {
+        for doc in succeeded {
+            if !self.is_downloaded(doc) {
+                self.downloaded.push(DownloadedFile {
+                    file_name: doc.file_name.clone(),
+                    timestamp: doc.timestamp.to_utc(),
+                });
+            }
+        }
+        let new_since = match failed.iter().map(|d| d.timestamp.to_utc()).min() {
+            Some(t) => Some(t),
+            None => self.downloaded.iter().map(|f| f.timestamp).max(),
+        };
+        if let Some(t) = new_since {
+            if self.since.is_none_or(|s| t > s) {
+                self.since = Some(t);
+            }
+        }
+        if let Some(since) = self.since {
+            self.downloaded.retain(|f| f.timestamp >= since);
+        }
+    }
+}
+
+/// Parse an RFC 3339 timestamp from a JSON string value.
+fn parse_timestamp(v: &Value) -> Result<DateTime<Utc>, DownloadError> {
+    Ok(DateTime::parse_from_rfc3339(v.as_str().ok_or(DownloadError::BadJson)?)?.to_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::state::DownloadState;
+    use crate::download::{FirdsDoc, FirdsDocType, FirdsSource};
+    use chrono::DateTime;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    fn doc(file_name: &str, timestamp: &str) -> FirdsDoc {
+        FirdsDoc {
+            source: FirdsSource::Esma,
+            download_link: format!("https://example.com/{file_name}"),
+            file_id: file_name.to_owned(),
+            file_name: file_name.to_owned(),
+            file_type: FirdsDocType::Dltins,
+            timestamp: DateTime::parse_from_rfc3339(timestamp).unwrap(),
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn test_update_and_round_trip() {
+        let a = doc("DLTINS_20250203_01of01.zip", "2025-02-03T08:00:00Z");
+        let b = doc("DLTINS_20250204_01of01.zip", "2025-02-04T08:00:00Z");
+        let c = doc("DLTINS_20250205_01of01.zip", "2025-02-05T08:00:00Z");
+
+        let mut state = DownloadState::default();
+        state.update(&[&a, &c], &[&b]);
+        assert_eq!(state.since, Some(b.timestamp.to_utc()));
+        assert!(!state.is_downloaded(&a));
+        assert!(!state.is_downloaded(&b));
+        assert!(state.is_downloaded(&c));
+
+        state.update(&[&b], &[]);
+        assert_eq!(state.since, Some(c.timestamp.to_utc()));
+        assert!(state.is_downloaded(&c));
+        assert_eq!(state.downloaded.len(), 1);
+
+        let path = temp_dir().join("firds_test_state.json");
+        state.save(&path).unwrap();
+        assert_eq!(DownloadState::load(&path).unwrap(), state);
+        remove_file(&path).unwrap();
+        assert_eq!(DownloadState::load(&path).unwrap(), DownloadState::default());
+    }
+}
@@ -0,0 +1,116 @@
+//! Integrity checks for downloaded zip files which do not depend on a published checksum.
+
+use crate::download::error::DownloadError;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Read the given zip entry to the end, discarding the data. The `zip` crate checks the CRC32 of
+/// the entry once it has been read in full, so this will return an error if the entry is corrupt
+/// or truncated.
+fn check_entry_crc(name: &str, entry: &mut impl Read) -> Result<(), DownloadError> {
+    io::copy(entry, &mut io::sink())
+        .map_err(|e| DownloadError::ZipCheckFailed(format!("{name}: {e}")))?;
+    Ok(())
+}
+
+/// Parse the given zip entry as XML, checking that it is well-formed and that the root element is
+/// closed. As the entry is read in full, this also checks the entry's CRC32.
+fn check_entry_xml(name: &str, entry: impl Read) -> Result<(), DownloadError> {
+    let mut reader = Reader::from_reader(BufReader::new(entry));
+    let mut buf = Vec::new();
+    let mut depth = 0;
+    let mut root_closed = false;
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| match e {
+            quick_xml::Error::Io(e) => DownloadError::ZipCheckFailed(format!("{name}: {e}")),
+            e => DownloadError::XmlCheckFailed(format!(
+                "{name}: {e} (at byte {})", reader.buffer_position()
+            ))
+        })?;
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    root_closed = true;
+                }
+            },
+            Event::Empty(_) if depth == 0 => root_closed = true,
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    if root_closed {
+        Ok(())
+    } else {
+        Err(DownloadError::XmlCheckFailed(format!("{name}: root element is not closed")))
+    }
+}
+
+/// Check the integrity of the zip file at the given path, by reading its central directory and
+/// checking the CRC32 of every entry. If `check_xml` is true, any XML entries in the archive are
+/// also checked to be well-formed up to the closing tag of the root element.
+pub(crate) fn verify_zip(fpath: &Path, check_xml: bool) -> Result<(), DownloadError> {
+    let mut archive = ZipArchive::new(File::open(fpath)?)
+        .map_err(|e| DownloadError::ZipCheckFailed(e.to_string()))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| DownloadError::ZipCheckFailed(e.to_string()))?;
+        let name = entry.name().to_owned();
+        if check_xml && name.to_lowercase().ends_with(".xml") {
+            check_entry_xml(&name, &mut entry)?;
+        } else {
+            check_entry_crc(&name, &mut entry)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::error::DownloadError;
+    use crate::download::verify::verify_zip;
+    use std::env::temp_dir;
+    use std::fs::{write, File};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_zip(name: &str, xml: &str) -> PathBuf {
+        let path = temp_dir().join(name);
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        writer.start_file("test.xml", SimpleFileOptions::default()).unwrap();
+        writer.write_all(xml.as_bytes()).unwrap();
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_verify_good_zip() {
+        let path = write_zip("firds_verify_good.zip", "<?xml version=\"1.0\"?><a><b>1</b></a>");
+        assert!(verify_zip(&path, false).is_ok());
+        assert!(verify_zip(&path, true).is_ok());
+    }
+
+    #[test]
+    fn test_verify_truncated_xml() {
+        let path = write_zip("firds_verify_trunc_xml.zip", "<?xml version=\"1.0\"?><a><b>1</b>");
+        assert!(verify_zip(&path, false).is_ok());
+        assert!(matches!(verify_zip(&path, true), Err(DownloadError::XmlCheckFailed(_))));
+    }
+
+    #[test]
+    fn test_verify_truncated_zip() {
+        let path = write_zip("firds_verify_trunc_zip.zip", "<a><b>1</b></a>");
+        let bytes = std::fs::read(&path).unwrap();
+        write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(verify_zip(&path, false), Err(DownloadError::ZipCheckFailed(_))));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 
 #[derive(Debug)]
 pub enum ProductError {
@@ -12,12 +14,202 @@ impl From<strum::ParseError> for ProductError {
     }
 }
 
+/// An error encountered when validating an identifier such as an ISIN or LEI.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IdentifierError {
+    /// The identifier is not of the expected length.
+    BadLength { expected: usize, found: usize },
+    /// The identifier contains a character that is not permitted at its position.
+    BadCharacter(char),
+    /// The identifier's check digit(s) do not match its other characters.
+    BadCheckDigit,
+}
+
+impl Display for IdentifierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadLength { expected, found } => write!(
+                f, "Expected identifier of length {expected}, found length {found}"
+            ),
+            Self::BadCharacter(c) => write!(f, "Unexpected character in identifier: {c:?}"),
+            Self::BadCheckDigit => write!(f, "Identifier check digit does not match"),
+        }
+    }
+}
+
+impl std::error::Error for IdentifierError {}
+
+/// An error encountered when parsing a CFI code.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CfiError {
+    /// The code is not six characters long. Contains the actual length.
+    BadLength(usize),
+    /// The code contains a character other than an upper-case ASCII letter.
+    BadCharacter(char),
+    /// The first letter of the code is not a known category.
+    BadCategory(char),
+    /// The second letter of the code is not a known group within the category.
+    BadGroup(char, char),
+}
+
+impl Display for CfiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadLength(n) => write!(f, "Expected CFI code of length 6, found length {n}"),
+            Self::BadCharacter(c) => write!(f, "Unexpected character in CFI code: {c:?}"),
+            Self::BadCategory(c) => write!(f, "Unknown CFI category: {c}"),
+            Self::BadGroup(c, g) => write!(f, "Unknown CFI group: {c}{g}"),
+        }
+    }
+}
+
+impl std::error::Error for CfiError {}
+
+/// An error encountered when validating a FISN.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FisnError {
+    /// The FISN is longer than 35 characters. Contains the actual length.
+    TooLong(usize),
+    /// The FISN contains a character that is not permitted.
+    BadCharacter(char),
+    /// The FISN does not contain a forward slash separating the issuer name and description.
+    NoSeparator,
+    /// The issuer name is empty.
+    EmptyIssuer,
+    /// The issuer name is longer than 15 characters. Contains the actual length.
+    IssuerTooLong(usize),
+    /// The description is empty.
+    EmptyDescription,
+    /// The description is longer than 19 characters. Contains the actual length.
+    DescriptionTooLong(usize),
+}
+
+impl Display for FisnError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLong(n) => write!(f, "FISN is longer than 35 characters: {n}"),
+            Self::BadCharacter(c) => write!(f, "Unexpected character in FISN: {c:?}"),
+            Self::NoSeparator => write!(f, "FISN does not contain a '/' separator"),
+            Self::EmptyIssuer => write!(f, "FISN issuer name is empty"),
+            Self::IssuerTooLong(n) => write!(f, "FISN issuer name is longer than 15 characters: {n}"),
+            Self::EmptyDescription => write!(f, "FISN description is empty"),
+            Self::DescriptionTooLong(n) => write!(
+                f, "FISN description is longer than 19 characters: {n}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FisnError {}
+
+/// An error encountered when parsing a currency code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurrencyError {
+    /// The code does not consist of three upper-case ASCII letters.
+    BadFormat(String),
+    /// The code is well-formed but is not an active ISO 4217 code.
+    UnknownCode(String),
+}
+
+impl Display for CurrencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadFormat(s) => write!(f, "Badly formatted currency code: {s:?}"),
+            Self::UnknownCode(s) => write!(f, "Unknown ISO 4217 currency code: {s}"),
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {}
+
+/// An error encountered when loading a MIC registry.
+#[cfg(feature = "mic-registry")]
+#[derive(Debug)]
+pub enum MicRegistryError {
+    /// IO error reading the registry file.
+    Io(std::io::Error),
+    /// Error parsing the registry as CSV.
+    Csv(csv::Error),
+    /// The registry does not contain a column that we need. Contains the column name.
+    MissingColumn(&'static str),
+    /// A row of the registry contains an invalid value. Contains a description of the problem.
+    BadRow(String),
+}
+
+#[cfg(feature = "mic-registry")]
+impl Display for MicRegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Could not read MIC registry: {e}"),
+            Self::Csv(e) => write!(f, "Could not parse MIC registry: {e}"),
+            Self::MissingColumn(c) => write!(f, "MIC registry has no {c:?} column"),
+            Self::BadRow(s) => write!(f, "Bad row in MIC registry: {s}"),
+        }
+    }
+}
+
+#[cfg(feature = "mic-registry")]
+impl std::error::Error for MicRegistryError {}
+
+#[cfg(feature = "mic-registry")]
+impl From<std::io::Error> for MicRegistryError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(feature = "mic-registry")]
+impl From<csv::Error> for MicRegistryError {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+/// An error encountered when building a [`ReferenceData`](crate::ReferenceData) using a
+/// [`ReferenceDataBuilder`](crate::ReferenceDataBuilder).
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// A mandatory field was not set. Contains the name of the field.
+    MissingField(&'static str),
+    /// An identifier was invalid. Contains the invalid identifier.
+    InvalidIdentifier(String, IdentifierError),
+    /// The record failed one or more validation rules with [`Severity::Error`](crate::validate::Severity::Error).
+    Invalid(Vec<crate::validate::Finding>),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "Missing field: {field}"),
+            Self::InvalidIdentifier(id, e) => write!(f, "Invalid identifier {id}: {e}"),
+            Self::Invalid(findings) => {
+                write!(f, "Validation failed: ")?;
+                for (i, finding) in findings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}: {}", finding.rule, finding.message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
 #[derive(Debug)]
 pub enum ParseError {
     /// Error parsing an enum variant.
     Enum,
     /// Error parsing a commodity product.
-    Product(ProductError)
+    Product(ProductError),
+    /// Invalid identifier (eg, an ISIN with a bad check digit).
+    Identifier(IdentifierError),
+    /// Invalid CFI code.
+    Cfi(CfiError),
+    /// Invalid currency code.
+    Currency(CurrencyError)
 }
 
 impl From<strum::ParseError> for ParseError {
@@ -30,4 +222,22 @@ impl From<ProductError> for ParseError {
     fn from(e: ProductError) -> Self {
         Self::Product(e)
     }
-}
\ No newline at end of file
+}
+
+impl From<IdentifierError> for ParseError {
+    fn from(e: IdentifierError) -> Self {
+        Self::Identifier(e)
+    }
+}
+
+impl From<CfiError> for ParseError {
+    fn from(e: CfiError) -> Self {
+        Self::Cfi(e)
+    }
+}
+
+impl From<CurrencyError> for ParseError {
+    fn from(e: CurrencyError) -> Self {
+        Self::Currency(e)
+    }
+}
@@ -0,0 +1,297 @@
+//! Parsing of Financial Instrument Short Names (FISNs), as defined in ISO 18774.
+//!
+//! A FISN consists of an abbreviated issuer name (up to 15 characters), a forward slash and an
+//! abbreviated description of the instrument (up to 19 characters), eg, `APPLE INC/SH` or
+//! `BUNDESREPUB.DEU/.5 BD 20260215`. For debt instruments, the description generally contains the
+//! coupon and the maturity date.
+
+use crate::error::FisnError;
+use crate::model::{DebtAttributes, InterestRate};
+use chrono::NaiveDate;
+use std::fmt::{Display, Formatter};
+
+/// The maximum length of a FISN.
+const MAX_LEN: usize = 35;
+/// The maximum length of the issuer short name part of a FISN.
+const MAX_ISSUER_LEN: usize = 15;
+/// The maximum length of the description part of a FISN.
+const MAX_DESCRIPTION_LEN: usize = 19;
+/// Abbreviations used in FISN descriptions to indicate a floating or variable coupon.
+const FLOATING_ABBREVIATIONS: [&str; 4] = ["FLTG", "FRN", "VAR", "FLT"];
+/// Abbreviations used in FISN descriptions to indicate a zero coupon.
+const ZERO_ABBREVIATIONS: [&str; 2] = ["ZERO", "ZCP"];
+
+/// The coupon of a debt instrument, as stated in its FISN.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FisnCoupon {
+    /// A fixed coupon, expressed as a percentage.
+    Fixed(f64),
+    /// A zero coupon.
+    Zero,
+    /// A floating or variable coupon.
+    Floating,
+}
+
+/// An inconsistency between a FISN and the [`DebtAttributes`] of the same instrument.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FisnInconsistency {
+    /// The FISN states a different fixed coupon to the debt attributes.
+    CouponMismatch { fisn: f64, data: f64 },
+    /// The FISN states a fixed (or zero) coupon where the debt attributes state a floating rate,
+    /// or vice versa.
+    CouponTypeMismatch { fisn: FisnCoupon },
+    /// The FISN states a different maturity date to the debt attributes (or the debt attributes
+    /// do not state a maturity date).
+    MaturityMismatch { fisn: NaiveDate, data: Option<NaiveDate> },
+    /// The FISN describes the instrument as perpetual but the debt attributes state a maturity
+    /// date.
+    PerpetualWithMaturity { data: NaiveDate },
+}
+
+/// Parse a coupon rate as written in a FISN, eg, `1.25`, `.5`, `4 1/2` or `3.75%`. `fraction` is
+/// the token following the rate, if any, which may be a fraction such as `1/2`.
+fn parse_rate(token: &str, fraction: Option<&str>) -> Option<f64> {
+    let token = token.strip_suffix('%').unwrap_or(token);
+    if !token.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let mut rate: f64 = token.parse().ok()?;
+    if let Some((num, denom)) = fraction.and_then(|f| f.strip_suffix('%').unwrap_or(f).split_once('/')) {
+        if let (Ok(num), Ok(denom)) = (num.parse::<f64>(), denom.parse::<f64>()) {
+            if denom != 0.0 {
+                rate += num / denom;
+            }
+        }
+    }
+    Some(rate)
+}
+
+/// A Financial Instrument Short Name (ISO 18774).
+///
+/// A `Fisn` can be constructed from any string, as FISNs in upstream data do not always conform to
+/// the standard. Use [`Fisn::validate`] to check the structure.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fisn(String);
+
+impl Fisn {
+    /// Create a new FISN from the given string. The FISN is not validated.
+    pub fn new(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+
+    /// Check that the FISN has the structure required by ISO 18774: an issuer name of up to 15
+    /// characters and a description of up to 19 characters, separated by a forward slash, and
+    /// consisting only of printable ASCII characters.
+    pub fn validate(&self) -> Result<(), FisnError> {
+        let len = self.0.chars().count();
+        if len > MAX_LEN {
+            return Err(FisnError::TooLong(len));
+        }
+        if let Some(c) = self.0.chars().find(|c| !(c.is_ascii_graphic() || *c == ' ')) {
+            return Err(FisnError::BadCharacter(c));
+        }
+        let (issuer, description) = self.0.split_once('/').ok_or(FisnError::NoSeparator)?;
+        if issuer.trim().is_empty() {
+            return Err(FisnError::EmptyIssuer);
+        }
+        if issuer.len() > MAX_ISSUER_LEN {
+            return Err(FisnError::IssuerTooLong(issuer.len()));
+        }
+        if description.trim().is_empty() {
+            return Err(FisnError::EmptyDescription);
+        }
+        if description.len() > MAX_DESCRIPTION_LEN {
+            return Err(FisnError::DescriptionTooLong(description.len()));
+        }
+        Ok(())
+    }
+
+    /// Whether the FISN has the structure required by ISO 18774 (see [`Fisn::validate`]).
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Return the FISN as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the issuer short name, ie, the part of the FISN before the first forward slash. If
+    /// there is no forward slash, the whole FISN is returned.
+    pub fn issuer(&self) -> &str {
+        self.0.split_once('/').map_or(self.0.as_str(), |(issuer, _)| issuer).trim()
+    }
+
+    /// Return the instrument description, ie, the part of the FISN after the first forward slash.
+    /// If there is no forward slash, an empty string is returned.
+    pub fn description(&self) -> &str {
+        self.0.split_once('/').map_or("", |(_, desc)| desc).trim()
+    }
+
+    /// Iterate over the whitespace-separated tokens of the description.
+    fn description_tokens(&self) -> impl Iterator<Item = &str> {
+        self.description().split_whitespace()
+    }
+
+    /// Return the coupon stated in the description, if any. This is only meaningful for debt
+    /// instruments.
+    ///
+    /// The coupon is taken from the first token of the description that is a number (eg, `1.25`,
+    /// `.5` or `4 1/2`) or a recognised abbreviation for a zero (`ZERO`, `ZCP`) or floating
+    /// (`FLTG`, `FRN`, `VAR`, `FLT`) coupon.
+    pub fn coupon(&self) -> Option<FisnCoupon> {
+        let tokens: Vec<&str> = self.description_tokens().collect();
+        for (i, token) in tokens.iter().enumerate() {
+            if FLOATING_ABBREVIATIONS.contains(token) {
+                return Some(FisnCoupon::Floating);
+            }
+            if ZERO_ABBREVIATIONS.contains(token) {
+                return Some(FisnCoupon::Zero);
+            }
+            // Skip anything that looks like a date rather than a rate.
+            if token.len() == 8 && token.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if let Some(rate) = parse_rate(token, tokens.get(i + 1).copied()) {
+                return Some(if rate == 0.0 { FisnCoupon::Zero } else { FisnCoupon::Fixed(rate) });
+            }
+        }
+        None
+    }
+
+    /// Return the maturity date stated in the description, if any, ie, the first token in the
+    /// form `YYYYMMDD`.
+    pub fn maturity(&self) -> Option<NaiveDate> {
+        self.description_tokens()
+            .filter(|t| t.len() == 8)
+            .find_map(|t| NaiveDate::parse_from_str(t, "%Y%m%d").ok())
+    }
+
+    /// Whether the description describes the instrument as perpetual (`PERP`).
+    pub fn is_perpetual(&self) -> bool {
+        self.description_tokens().any(|t| t == "PERP")
+    }
+
+    /// Compare the coupon and maturity stated in the FISN with the given [`DebtAttributes`] and
+    /// return any inconsistencies found. Where the FISN does not state a coupon or maturity, that
+    /// check is skipped.
+    pub fn check_debt_attributes(&self, attrs: &DebtAttributes) -> Vec<FisnInconsistency> {
+        let mut inconsistencies = vec![];
+        match (self.coupon(), &attrs.interest_rate) {
+            (Some(FisnCoupon::Fixed(fisn)), InterestRate::Fixed(data))
+                if (fisn - data).abs() > 1e-6 => {
+                inconsistencies.push(FisnInconsistency::CouponMismatch { fisn, data: *data });
+            },
+            (Some(FisnCoupon::Zero), InterestRate::Fixed(data)) if *data != 0.0 => {
+                inconsistencies.push(FisnInconsistency::CouponMismatch { fisn: 0.0, data: *data });
+            },
+            (Some(c @ FisnCoupon::Floating), InterestRate::Fixed(_))
+            | (Some(c @ (FisnCoupon::Fixed(_) | FisnCoupon::Zero)), InterestRate::Floating(..)) => {
+                inconsistencies.push(FisnInconsistency::CouponTypeMismatch { fisn: c });
+            },
+            _ => {}
+        }
+        if let Some(fisn) = self.maturity() {
+            if attrs.maturity_date != Some(fisn) {
+                inconsistencies.push(FisnInconsistency::MaturityMismatch {
+                    fisn,
+                    data: attrs.maturity_date
+                });
+            }
+        } else if self.is_perpetual() {
+            if let Some(data) = attrs.maturity_date {
+                inconsistencies.push(FisnInconsistency::PerpetualWithMaturity { data });
+            }
+        }
+        inconsistencies
+    }
+}
+
+impl Display for Fisn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for Fisn {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Fisn> for String {
+    fn from(fisn: Fisn) -> Self {
+        fisn.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::FisnError;
+    use crate::fisn::{Fisn, FisnCoupon, FisnInconsistency};
+    use crate::{DebtAttributes, FloatingRate, InterestRate};
+    use chrono::NaiveDate;
+
+    fn debt_attrs(interest_rate: InterestRate, maturity_date: Option<NaiveDate>) -> DebtAttributes {
+        DebtAttributes {
+            total_issued_amount: 1_000_000.0,
+            maturity_date,
+            nominal_currency: "EUR".parse().unwrap(),
+            nominal_value_per_unit: 1000.0,
+            interest_rate,
+            seniority: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_fisn() {
+        let fisn = Fisn::new("BUNDESREPUB.DEU/.5 BD 20260215");
+        assert!(fisn.is_valid());
+        assert_eq!(fisn.issuer(), "BUNDESREPUB.DEU");
+        assert_eq!(fisn.description(), ".5 BD 20260215");
+        assert_eq!(fisn.coupon(), Some(FisnCoupon::Fixed(0.5)));
+        assert_eq!(fisn.maturity(), NaiveDate::from_ymd_opt(2026, 2, 15));
+
+        assert_eq!(Fisn::new("US TREASURY/4 1/2 BD 20331115").coupon(), Some(FisnCoupon::Fixed(4.5)));
+        assert_eq!(Fisn::new("DEUTSCHE BANK/FLTG MTN 20250520").coupon(), Some(FisnCoupon::Floating));
+        assert_eq!(Fisn::new("KFW/ZERO BD 20300101").coupon(), Some(FisnCoupon::Zero));
+        assert!(Fisn::new("BARCLAYS PLC/7.125 BD PERP").is_perpetual());
+
+        let fisn = Fisn::new("APPLE INC/SH");
+        assert_eq!(fisn.coupon(), None);
+        assert_eq!(fisn.maturity(), None);
+
+        assert!(matches!(Fisn::new("APPLE INC SH").validate(), Err(FisnError::NoSeparator)));
+        assert!(matches!(Fisn::new("/SH").validate(), Err(FisnError::EmptyIssuer)));
+        assert!(matches!(
+            Fisn::new("A VERY LONG ISSUER NAME/SH").validate(),
+            Err(FisnError::IssuerTooLong(23))
+        ));
+        assert_eq!(Fisn::new("NO SLASH").issuer(), "NO SLASH");
+        assert_eq!(Fisn::new("NO SLASH").description(), "");
+    }
+
+    #[test]
+    fn test_check_debt_attributes() {
+        let maturity = NaiveDate::from_ymd_opt(2026, 2, 15);
+        let fisn = Fisn::new("BUNDESREPUB.DEU/.5 BD 20260215");
+        assert!(fisn.check_debt_attributes(&debt_attrs(InterestRate::Fixed(0.5), maturity)).is_empty());
+        assert_eq!(
+            fisn.check_debt_attributes(&debt_attrs(InterestRate::Fixed(5.0), None)),
+            vec![
+                FisnInconsistency::CouponMismatch { fisn: 0.5, data: 5.0 },
+                FisnInconsistency::MaturityMismatch { fisn: maturity.unwrap(), data: None },
+            ]
+        );
+        let floating = InterestRate::Floating(FloatingRate { name: None, term: None }, None);
+        assert_eq!(
+            fisn.check_debt_attributes(&debt_attrs(floating, maturity)),
+            vec![FisnInconsistency::CouponTypeMismatch { fisn: FisnCoupon::Fixed(0.5) }]
+        );
+        assert_eq!(
+            Fisn::new("BARCLAYS PLC/7.125 BD PERP")
+                .check_debt_attributes(&debt_attrs(InterestRate::Fixed(7.125), maturity)),
+            vec![FisnInconsistency::PerpetualWithMaturity { data: maturity.unwrap() }]
+        );
+    }
+}
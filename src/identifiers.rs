@@ -0,0 +1,404 @@
+//! Types representing the standard identifiers used in FIRDS data.
+//!
+//! Each identifier type can be constructed in a validating way (using `new` or [`FromStr`]) or
+//! without validation (using `new_unchecked`). Unchecked construction is useful when handling data
+//! from upstream sources, where an invalid identifier should be flagged rather than cause the
+//! whole record to be rejected; such identifiers can later be checked using `validate` or
+//! `is_valid`.
+
+use crate::error::IdentifierError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Return the numeric value of an alphanumeric character, where `0`-`9` map to 0-9 and `A`-`Z`
+/// map to 10-35. Return an error for any other character.
+fn alphanumeric_value(c: char) -> Result<u32, IdentifierError> {
+    match c {
+        '0'..='9' | 'A'..='Z' => Ok(c.to_digit(36).unwrap()),
+        _ => Err(IdentifierError::BadCharacter(c))
+    }
+}
+
+/// Check that `s` has the expected length.
+fn check_length(s: &str, expected: usize) -> Result<(), IdentifierError> {
+    let found = s.chars().count();
+    if found == expected {
+        Ok(())
+    } else {
+        Err(IdentifierError::BadLength { expected, found })
+    }
+}
+
+/// An International Securities Identification Number (ISO 6166).
+///
+/// An ISIN consists of a two-letter country code, a nine-character alphanumeric national
+/// securities identifying number (NSIN) and a single check digit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Isin(String);
+
+impl Isin {
+    /// Create a new ISIN from the given string, returning an error if it is not valid.
+    pub fn new(s: &str) -> Result<Self, IdentifierError> {
+        let isin = Self::new_unchecked(s);
+        isin.validate()?;
+        Ok(isin)
+    }
+
+    /// Create a new ISIN from the given string without checking that it is valid.
+    pub fn new_unchecked(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+
+    /// Calculate the check digit for the first eleven characters of an ISIN.
+    fn calculate_check_digit(payload: &str) -> Result<u32, IdentifierError> {
+        // Convert each character to its numeric value and concatenate the resulting digits.
+        let mut digits = vec![];
+        for c in payload.chars() {
+            let v = alphanumeric_value(c)?;
+            if v >= 10 {
+                digits.push(v / 10);
+            }
+            digits.push(v % 10);
+        }
+        // Luhn algorithm, doubling every other digit starting with the rightmost.
+        let sum: u32 = digits.iter().rev().enumerate()
+            .map(|(i, &d)| if i % 2 == 0 {
+                let d = d * 2;
+                d / 10 + d % 10
+            } else {
+                d
+            })
+            .sum();
+        Ok((10 - sum % 10) % 10)
+    }
+
+    /// Check that the ISIN has the correct structure and check digit.
+    pub fn validate(&self) -> Result<(), IdentifierError> {
+        check_length(&self.0, 12)?;
+        let chars: Vec<char> = self.0.chars().collect();
+        if let Some(&c) = chars[..2].iter().find(|c| !c.is_ascii_uppercase()) {
+            return Err(IdentifierError::BadCharacter(c));
+        }
+        let check_digit = chars[11].to_digit(10).ok_or(IdentifierError::BadCharacter(chars[11]))?;
+        if Self::calculate_check_digit(&chars[..11].iter().collect::<String>())? == check_digit {
+            Ok(())
+        } else {
+            Err(IdentifierError::BadCheckDigit)
+        }
+    }
+
+    /// Whether the ISIN has the correct structure and check digit.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Return the ISIN as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the two-letter prefix of the ISIN. This is usually the ISO 3166-1 alpha-2 code of the
+    /// country of the issuer (or of the depository, for securities issued internationally), but
+    /// some other prefixes are in use, such as `XS` for international securities cleared through
+    /// Euroclear or Clearstream and `EU` for securities issued by the European Union.
+    ///
+    /// Returns an empty string if the (unchecked) ISIN is too short to have a prefix.
+    pub fn country_code(&self) -> &str {
+        self.0.get(..2).unwrap_or_default()
+    }
+
+    /// Whether the ISIN has a prefix which does not relate to a single country, such as `XS` or
+    /// `EU`.
+    pub fn is_international(&self) -> bool {
+        matches!(self.country_code(), "XS" | "XA" | "XB" | "XC" | "XD" | "XF" | "EU")
+    }
+
+    /// Return the national securities identifying number (NSIN) part of the ISIN, ie, the nine
+    /// characters between the prefix and the check digit.
+    ///
+    /// Returns an empty string if the (unchecked) ISIN is too short.
+    pub fn nsin(&self) -> &str {
+        self.0.get(2..11).unwrap_or_default()
+    }
+
+    /// Return the check digit of the ISIN, if present.
+    pub fn check_digit(&self) -> Option<char> {
+        self.0.chars().nth(11)
+    }
+}
+
+impl Display for Isin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Isin {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Isin {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Isin> for String {
+    fn from(isin: Isin) -> Self {
+        isin.0
+    }
+}
+
+/// A Legal Entity Identifier (ISO 17442).
+///
+/// A LEI consists of a four-character prefix identifying the Local Operating Unit (LOU) that
+/// issued it, two reserved characters (usually `00`), a twelve-character entity-specific part and
+/// two check digits calculated according to ISO 7064 MOD 97-10.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lei(String);
+
+impl Lei {
+    /// Create a new LEI from the given string, returning an error if it is not valid.
+    pub fn new(s: &str) -> Result<Self, IdentifierError> {
+        let lei = Self::new_unchecked(s);
+        lei.validate()?;
+        Ok(lei)
+    }
+
+    /// Create a new LEI from the given string without checking that it is valid.
+    pub fn new_unchecked(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+
+    /// Check that the LEI has the correct structure and check digits.
+    ///
+    /// The MOD 97-10 check detects all single character substitutions and all transpositions of
+    /// adjacent characters.
+    pub fn validate(&self) -> Result<(), IdentifierError> {
+        check_length(&self.0, 20)?;
+        let mut remainder = 0;
+        for (i, c) in self.0.chars().enumerate() {
+            if i >= 18 && !c.is_ascii_digit() {
+                return Err(IdentifierError::BadCharacter(c));
+            }
+            let v = alphanumeric_value(c)?;
+            remainder = (remainder * if v >= 10 { 100 } else { 10 } + v) % 97;
+        }
+        if remainder == 1 {
+            Ok(())
+        } else {
+            Err(IdentifierError::BadCheckDigit)
+        }
+    }
+
+    /// Whether the LEI has the correct structure and check digits.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Return the LEI as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the four-character prefix identifying the LOU that issued the LEI.
+    ///
+    /// Returns an empty string if the (unchecked) LEI is too short to have a prefix.
+    pub fn lou_prefix(&self) -> &str {
+        self.0.get(..4).unwrap_or_default()
+    }
+
+    /// Return the twelve-character entity-specific part of the LEI.
+    ///
+    /// Returns an empty string if the (unchecked) LEI is too short.
+    pub fn entity_part(&self) -> &str {
+        self.0.get(6..18).unwrap_or_default()
+    }
+
+    /// Return the two check digits at the end of the LEI.
+    ///
+    /// Returns an empty string if the (unchecked) LEI is too short.
+    pub fn check_digits(&self) -> &str {
+        self.0.get(18..20).unwrap_or_default()
+    }
+}
+
+impl Display for Lei {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Lei {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Lei {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Lei> for String {
+    fn from(lei: Lei) -> Self {
+        lei.0
+    }
+}
+
+/// A Market Identifier Code (ISO 10383).
+///
+/// A MIC consists of four alphanumeric characters and identifies a trading venue (such as a
+/// regulated market, MTF or OTF) or a systematic internaliser. A MIC is either an *operating* MIC,
+/// identifying the entity operating one or more markets, or a *segment* MIC, identifying a
+/// particular market segment and linked to an operating MIC. The MIC does not itself indicate
+/// which it is; with the `mic-registry` feature enabled, this and other details can be looked up
+/// in the ISO 10383 registry (see `MicRegistry`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mic(String);
+
+impl Mic {
+    /// Create a new MIC from the given string, returning an error if it is not valid.
+    pub fn new(s: &str) -> Result<Self, IdentifierError> {
+        let mic = Self::new_unchecked(s);
+        mic.validate()?;
+        Ok(mic)
+    }
+
+    /// Create a new MIC from the given string without checking that it is valid.
+    pub fn new_unchecked(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+
+    /// Check that the MIC consists of four upper-case alphanumeric characters.
+    pub fn validate(&self) -> Result<(), IdentifierError> {
+        check_length(&self.0, 4)?;
+        match self.0.chars().find(|c| !c.is_ascii_uppercase() && !c.is_ascii_digit()) {
+            Some(c) => Err(IdentifierError::BadCharacter(c)),
+            None => Ok(())
+        }
+    }
+
+    /// Whether the MIC consists of four upper-case alphanumeric characters.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Return the MIC as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether this is the `XOFF` MIC, used for transactions in listed instruments that are not
+    /// executed on a trading venue.
+    pub fn is_off_exchange(&self) -> bool {
+        self.0 == "XOFF"
+    }
+}
+
+impl Display for Mic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Mic {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Mic {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Mic> for String {
+    fn from(mic: Mic) -> Self {
+        mic.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::IdentifierError;
+    use crate::identifiers::{Isin, Lei, Mic};
+
+    #[test]
+    fn test_isin() {
+        for s in ["US0378331005", "GB0002634946", "XS2431015523", "IE00B4L5Y983", "DE000BAY0017"] {
+            let isin = Isin::new(s).unwrap();
+            assert_eq!(isin.as_str(), s);
+            assert_eq!(s.parse::<Isin>().unwrap(), isin);
+        }
+        let isin = Isin::new("US0378331005").unwrap();
+        assert_eq!(isin.country_code(), "US");
+        assert_eq!(isin.nsin(), "037833100");
+        assert_eq!(isin.check_digit(), Some('5'));
+        assert!(!isin.is_international());
+        assert!(Isin::new("XS2431015523").unwrap().is_international());
+
+        assert!(matches!(Isin::new("US0378331006"), Err(IdentifierError::BadCheckDigit)));
+        assert!(matches!(
+            Isin::new("US037833100"),
+            Err(IdentifierError::BadLength { expected: 12, found: 11 })
+        ));
+        assert!(matches!(Isin::new("us0378331005"), Err(IdentifierError::BadCharacter('u'))));
+        assert!(matches!(Isin::new("US03783310-5"), Err(IdentifierError::BadCharacter('-'))));
+
+        let unchecked = Isin::new_unchecked("X");
+        assert!(!unchecked.is_valid());
+        assert_eq!(unchecked.country_code(), "");
+        assert_eq!(unchecked.nsin(), "");
+    }
+
+    #[test]
+    fn test_lei() {
+        for s in ["5493001KJTIIGC8Y1R12", "213800D1EI4B9WTWWD28", "529900T8BM49AURSDO55"] {
+            let lei = Lei::new(s).unwrap();
+            assert_eq!(lei.as_str(), s);
+            assert_eq!(s.parse::<Lei>().unwrap(), lei);
+        }
+        let lei = Lei::new("5493001KJTIIGC8Y1R12").unwrap();
+        assert_eq!(lei.lou_prefix(), "5493");
+        assert_eq!(lei.entity_part(), "1KJTIIGC8Y1R");
+        assert_eq!(lei.check_digits(), "12");
+
+        // Transposed characters
+        assert!(matches!(Lei::new("5493001KJTIIGC8Y1R21"), Err(IdentifierError::BadCheckDigit)));
+        assert!(matches!(Lei::new("549300K1JTIIGC8Y1R12"), Err(IdentifierError::BadCheckDigit)));
+        assert!(matches!(
+            Lei::new("5493001KJTIIGC8Y1R1"),
+            Err(IdentifierError::BadLength { expected: 20, found: 19 })
+        ));
+        assert!(matches!(Lei::new("5493001KJTIIGC8Y1R1X"), Err(IdentifierError::BadCharacter('X'))));
+        assert!(matches!(Lei::new("5493001kJTIIGC8Y1R12"), Err(IdentifierError::BadCharacter('k'))));
+        assert_eq!(Lei::new_unchecked("549").lou_prefix(), "");
+    }
+
+    #[test]
+    fn test_mic() {
+        for s in ["XLON", "XETR", "BATE", "1GSL"] {
+            let mic = Mic::new(s).unwrap();
+            assert_eq!(mic.as_str(), s);
+            assert_eq!(s.parse::<Mic>().unwrap(), mic);
+        }
+        assert!(Mic::new("XOFF").unwrap().is_off_exchange());
+        assert!(matches!(
+            Mic::new("XLONX"),
+            Err(IdentifierError::BadLength { expected: 4, found: 5 })
+        ));
+        assert!(matches!(Mic::new("xlon"), Err(IdentifierError::BadCharacter('x'))));
+        assert!(!Mic::new_unchecked("XL-N").is_valid());
+    }
+}
@@ -6,9 +6,17 @@
 //! representing FIRDS data.
 
 mod model;
+mod builder;
+mod cfi;
+mod classify;
+mod currency;
+mod fisn;
 mod enums;
 mod product_enums;
 mod error;
+mod identifiers;
+
+pub mod validate;
 
 #[cfg(feature = "download")]
 pub mod download;
@@ -17,9 +25,21 @@ pub mod download;
 pub mod xml;
 
 #[cfg(feature = "sql")]
-mod sql;
+pub mod sql;
+
+#[cfg(feature = "mic-registry")]
+mod mic_registry;
 
 pub use model::*;
+pub use builder::*;
 pub use enums::*;
 pub use product_enums::*;
-pub use error::*;
\ No newline at end of file
+pub use cfi::*;
+pub use classify::*;
+pub use currency::*;
+pub use error::*;
+pub use fisn::*;
+pub use identifiers::*;
+
+#[cfg(feature = "mic-registry")]
+pub use mic_registry::*;
@@ -0,0 +1,243 @@
+//! Lookup of trading venue details in the ISO 10383 Market Identifier Code registry.
+//!
+//! A snapshot of the full registry, as published in September 2026, is embedded in the crate and is
+//! available using [`MicRegistry::embedded`]. It includes expired MICs, which may still appear in
+//! historical FIRDS data, as well as special MICs such as `XOFF` and `XXXX`. The current registry
+//! can be downloaded as CSV from the ISO 10383 website and loaded at runtime using
+//! [`MicRegistry::from_file`].
+//!
+//! Note that `SINT`, which FIRDS uses to denote a systematic internaliser, is not a registered MIC.
+
+use crate::error::MicRegistryError;
+use crate::identifiers::Mic;
+use csv::StringRecord;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+use strum_macros::{Display, EnumString};
+
+/// The embedded snapshot of the registry, in the CSV format of the published registry.
+const EMBEDDED_REGISTRY: &str = include_str!("../data/mic_registry.csv");
+
+/// Whether a MIC identifies an operating entity or a segment of a market.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, Display)]
+pub enum MicType {
+    /// An operating MIC, identifying the entity operating one or more markets.
+    #[strum(serialize = "OPRT")]
+    Operating,
+    /// A segment MIC, identifying a particular segment of a market.
+    #[strum(serialize = "SGMT")]
+    Segment,
+}
+
+/// The details of a single MIC in the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicEntry {
+    /// The MIC.
+    pub mic: Mic,
+    /// The operating MIC to which this MIC belongs. For an operating MIC, this is the MIC itself.
+    pub operating_mic: Mic,
+    /// Whether this is an operating or segment MIC.
+    pub mic_type: MicType,
+    /// The name of the market or institution.
+    pub name: String,
+    /// The ISO 3166 alpha-2 code of the country in which the venue is located (`ZZ` for MICs
+    /// that do not relate to a specific country, such as `XOFF`).
+    pub country: String,
+    /// The city in which the venue is located.
+    pub city: String,
+    /// Whether the MIC is active (as opposed to expired or updated).
+    pub active: bool,
+}
+
+/// A collection of MIC details, keyed by MIC.
+#[derive(Debug, Clone, Default)]
+pub struct MicRegistry {
+    entries: HashMap<Mic, MicEntry>,
+}
+
+/// Return the index of the column with the given name in the header row.
+fn column(headers: &StringRecord, name: &'static str) -> Result<usize, MicRegistryError> {
+    headers.iter()
+        .position(|h| h.trim() == name)
+        .ok_or(MicRegistryError::MissingColumn(name))
+}
+
+impl MicRegistry {
+    /// Return the registry snapshot embedded in the crate.
+    pub fn embedded() -> &'static Self {
+        static REGISTRY: OnceLock<MicRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(|| Self::from_reader(EMBEDDED_REGISTRY.as_bytes())
+            .expect("Embedded MIC registry is invalid"))
+    }
+
+    /// Load a registry from CSV data in the format published by ISO (ie, with a header row
+    /// containing at least the `MIC`, `OPERATING MIC`, `OPRT/SGMT`,
+    /// `MARKET NAME-INSTITUTION DESCRIPTION`, `ISO COUNTRY CODE (ISO 3166)`, `CITY` and `STATUS`
+    /// columns). Other columns are ignored.
+    pub fn from_reader(reader: impl Read) -> Result<Self, MicRegistryError> {
+        let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let headers = csv_reader.headers()?.clone();
+        let mic_col = column(&headers, "MIC")?;
+        let op_mic_col = column(&headers, "OPERATING MIC")?;
+        let type_col = column(&headers, "OPRT/SGMT")?;
+        let name_col = column(&headers, "MARKET NAME-INSTITUTION DESCRIPTION")?;
+        let country_col = column(&headers, "ISO COUNTRY CODE (ISO 3166)")?;
+        let city_col = column(&headers, "CITY")?;
+        let status_col = column(&headers, "STATUS")?;
+
+        let mut entries = HashMap::new();
+        for record in csv_reader.records() {
+            let record = record?;
+            let field = |i: usize| record.get(i).unwrap_or_default().trim();
+            let parse_mic = |i: usize| Mic::new(field(i))
+                .map_err(|e| MicRegistryError::BadRow(format!("invalid MIC {:?}: {e}", field(i))));
+            let mic = parse_mic(mic_col)?;
+            let entry = MicEntry {
+                operating_mic: parse_mic(op_mic_col)?,
+                mic_type: field(type_col).parse().map_err(|_| MicRegistryError::BadRow(
+                    format!("unknown MIC type for {mic}: {:?}", field(type_col))
+                ))?,
+                name: field(name_col).to_owned(),
+                country: field(country_col).to_owned(),
+                city: field(city_col).to_owned(),
+                active: field(status_col) == "ACTIVE",
+                mic: mic.clone(),
+            };
+            entries.insert(mic, entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Load a registry from a CSV file (see [`MicRegistry::from_reader`] for the format).
+    pub fn from_file(fpath: &Path) -> Result<Self, MicRegistryError> {
+        Self::from_reader(std::fs::File::open(fpath)?)
+    }
+
+    /// Return the details of the given MIC, if it is in the registry.
+    pub fn get(&self, mic: &Mic) -> Option<&MicEntry> {
+        self.entries.get(mic)
+    }
+
+    /// Return the details of the operating MIC to which the given MIC belongs, if both are in the
+    /// registry.
+    pub fn operating_entry(&self, mic: &Mic) -> Option<&MicEntry> {
+        self.get(mic).and_then(|e| self.get(&e.operating_mic))
+    }
+
+    /// Return the details of all segment MICs belonging to the given operating MIC.
+    pub fn segments<'a>(&'a self, operating_mic: &'a Mic) -> impl Iterator<Item = &'a MicEntry> {
+        self.entries.values()
+            .filter(move |e| e.mic_type == MicType::Segment && &e.operating_mic == operating_mic)
+    }
+
+    /// Return an iterator over all entries in the registry, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &MicEntry> {
+        self.entries.values()
+    }
+
+    /// Return the number of MICs in the registry.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Mic {
+    /// Return the details of this MIC from the embedded registry snapshot, if present.
+    pub fn registry_entry(&self) -> Option<&'static MicEntry> {
+        MicRegistry::embedded().get(self)
+    }
+
+    /// Return the name of the venue identified by this MIC, according to the embedded registry
+    /// snapshot.
+    pub fn venue_name(&self) -> Option<&'static str> {
+        self.registry_entry().map(|e| e.name.as_str())
+    }
+
+    /// Return the ISO 3166 country code of the venue identified by this MIC, according to the
+    /// embedded registry snapshot.
+    pub fn country(&self) -> Option<&'static str> {
+        self.registry_entry().map(|e| e.country.as_str())
+    }
+
+    /// Return the operating MIC to which this MIC belongs, according to the embedded registry
+    /// snapshot.
+    pub fn operating_mic(&self) -> Option<&'static Mic> {
+        self.registry_entry().map(|e| &e.operating_mic)
+    }
+
+    /// Whether this is an operating MIC (`Some(true)`) or a segment MIC (`Some(false)`), according
+    /// to the embedded registry snapshot.
+    pub fn is_operating(&self) -> Option<bool> {
+        self.registry_entry().map(|e| e.mic_type == MicType::Operating)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::MicRegistryError;
+    use crate::identifiers::Mic;
+    use crate::mic_registry::{MicRegistry, MicType};
+
+    #[test]
+    fn test_embedded_registry() {
+        let registry = MicRegistry::embedded();
+        assert!(!registry.is_empty());
+        // The full registry is embedded, including expired MICs.
+        assert_eq!(registry.len(), 2883);
+        assert_eq!(registry.iter().filter(|e| e.active).count(), 2296);
+        let xetr = Mic::new("XETR").unwrap();
+        let entry = registry.get(&xetr).unwrap();
+        assert_eq!(entry.mic_type, MicType::Operating);
+        assert_eq!(entry.name, "XETRA");
+        assert_eq!(xetr.country(), Some("DE"));
+        assert_eq!(xetr.is_operating(), Some(true));
+        let xlon = Mic::new("XLON").unwrap();
+        assert_eq!(xlon.venue_name(), Some("LONDON STOCK EXCHANGE"));
+        assert_eq!(xlon.operating_mic(), Some(&xlon));
+        assert!(registry.segments(&xlon).any(|e| e.mic.as_str() == "AIMX"));
+        assert_eq!(Mic::new("XOFF").unwrap().country(), Some("ZZ"));
+
+        // Venues outside Europe are included too.
+        let xnys = Mic::new("XNYS").unwrap();
+        assert_eq!(xnys.venue_name(), Some("NEW YORK STOCK EXCHANGE, INC."));
+        assert_eq!(xnys.country(), Some("US"));
+        let xtks = Mic::new("XTKS").unwrap();
+        assert_eq!(xtks.is_operating(), Some(false));
+        assert_eq!(registry.operating_entry(&xtks).unwrap().name, "JAPAN EXCHANGE GROUP");
+        assert_eq!(Mic::new("XHKG").unwrap().country(), Some("HK"));
+        assert_eq!(registry.get(&Mic::new("XASX").unwrap()).unwrap().city, "SYDNEY");
+        assert!(!registry.get(&Mic::new("XBSP").unwrap()).unwrap().active);
+        // Every segment's operating MIC should itself be in the registry
+        for entry in registry.iter() {
+            assert!(registry.operating_entry(&entry.mic).is_some(), "{}", entry.mic);
+        }
+        assert_eq!(Mic::new("ZZZZ").unwrap().venue_name(), None);
+    }
+
+    #[test]
+    fn test_registry_from_reader() {
+        let csv = "\"OPRT/SGMT\",\"MIC\",\"OPERATING MIC\",\"MARKET NAME-INSTITUTION DESCRIPTION\",\
+            \"ISO COUNTRY CODE (ISO 3166)\",\"CITY\",\"STATUS\",\"WEBSITE\"\n\
+            \"OPRT\",\"ABCD\",\"ABCD\",\"EXAMPLE EXCHANGE, INC.\",\"IE\",\"DUBLIN\",\"EXPIRED\",\"\"\n";
+        let registry = MicRegistry::from_reader(csv.as_bytes()).unwrap();
+        assert_eq!(registry.len(), 1);
+        let entry = registry.get(&Mic::new("ABCD").unwrap()).unwrap();
+        assert_eq!(entry.name, "EXAMPLE EXCHANGE, INC.");
+        assert!(!entry.active);
+
+        let bad_type = "MIC,OPERATING MIC,OPRT/SGMT,MARKET NAME-INSTITUTION DESCRIPTION,\
+            ISO COUNTRY CODE (ISO 3166),CITY,STATUS\nABCD,ABCD,XXXX,NAME,IE,DUBLIN,ACTIVE\n";
+        assert!(matches!(MicRegistry::from_reader(bad_type.as_bytes()), Err(MicRegistryError::BadRow(_))));
+        assert!(matches!(
+            MicRegistry::from_reader("MIC,CITY\n".as_bytes()),
+            Err(MicRegistryError::MissingColumn("OPERATING MIC"))
+        ));
+    }
+}
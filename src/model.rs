@@ -1,4 +1,8 @@
 use crate::enums::{DebtSeniority, DeliveryType, FinalPriceType, FxType, IndexName, OptionExerciseStyle, OptionType, StrikePriceType, TermUnit, TransactionType};
+use crate::cfi::Cfi;
+use crate::currency::{Currency, CurrencyPair};
+use crate::fisn::Fisn;
+use crate::identifiers::{Isin, Lei, Mic};
 use crate::product_enums::BaseProduct;
 use chrono::{DateTime, NaiveDate, Utc};
 
@@ -22,7 +26,7 @@ pub struct StrikePrice {
     /// Whether the price is currently not available and is pending.
     pub pending: bool,
     /// The currency in which the price is denominated (if appropriate).
-    pub currency: Option<String>,
+    pub currency: Option<Currency>,
 }
 
 /// An index or benchmark rate used in the reference data for certain financial instruments.
@@ -37,7 +41,7 @@ pub struct FloatingRate {
 /// An index is effectively a [`FloatingRate`], with an optional ISIN code.
 #[derive(Debug)]
 pub struct Index {
-    pub isin: Option<String>,
+    pub isin: Option<Isin>,
     pub name: FloatingRate
 }
 
@@ -46,8 +50,8 @@ pub struct Index {
 /// venue.
 #[derive(Debug)]
 pub struct TradingVenueAttributes {
-    /// The Market Identifier Code (ISO 20022) for the trading venue or systemic internaliser.
-    pub trading_venue: String,
+    /// The Market Identifier Code (ISO 10383) for the trading venue or systemic internaliser.
+    pub trading_venue: Mic,
     /// Whether the issuer has requested or approved the trading or admission to trading of their
     /// financial instruments on a trading venue.
     pub requested_admission: bool,
@@ -64,6 +68,21 @@ pub struct TradingVenueAttributes {
     pub termination_date: Option<DateTime<Utc>>,
 }
 
+impl TradingVenueAttributes {
+    /// Create new trading venue attributes for the given venue, with no dates and
+    /// `requested_admission` set to `false`.
+    pub fn new(trading_venue: Mic) -> Self {
+        Self {
+            trading_venue,
+            requested_admission: false,
+            approval_date: None,
+            request_date: None,
+            admission_or_first_trade_date: None,
+            termination_date: None,
+        }
+    }
+}
+
 /// Data about the interest rate applicable to a debt instrument.
 #[derive(Debug)]
 pub enum InterestRate {
@@ -106,7 +125,7 @@ pub struct TechnicalAttributes {
     pub publication_period: Option<PublicationPeriod>,
     /// The MIC of the trading venue that reported the record considered as the reference
     /// for the published data.
-    pub relevant_trading_venue: Option<String>,
+    pub relevant_trading_venue: Option<Mic>,
 }
 
 /// Reference data for bonds or other forms of securitised debt.
@@ -119,7 +138,7 @@ pub struct DebtAttributes {
     /// with defined maturity.
     pub maturity_date: Option<NaiveDate>,
     /// The currency of the nominal value.
-    pub nominal_currency: String,
+    pub nominal_currency: Currency,
     /// The nominal value of each traded unit. If not available, the minimum traded amount
     /// is included. Amount is expressed in the `nominal_currency`.
     pub nominal_value_per_unit: f64,
@@ -151,7 +170,7 @@ pub struct InterestRateDerivativeAttributes {
     /// in which leg 2 of the contract is denominated. For swaptions where
     /// the underlying swap is multi-currency, the currency in which leg 2
     /// of the swap is denominated.
-    pub notional_currency_2: Option<String>,
+    pub notional_currency_2: Option<Currency>,
     /// The fixed rate of leg 2 of the trade, if applicable. Expressed as a percentage.
     pub interest_rate_2: Option<InterestRate>,
 }
@@ -160,7 +179,7 @@ pub struct InterestRateDerivativeAttributes {
 #[derive(Debug)]
 pub struct FxDerivativeAttributes {
     /// The second currency of the currency pair.
-    pub notional_currency_2: Option<String>,
+    pub notional_currency_2: Option<Currency>,
     /// The type of underlying currency.
     pub fx_type: Option<FxType>,
 }
@@ -177,20 +196,20 @@ pub enum UnderlyingSingle {
     ///   trading venue. Where the underlying is a stock dividend, then the ISIN code of the
     ///   related share entitling the underlying dividend shall be provided.
     /// - For Credit Default Swaps, the ISIN of the reference obligation shall be provided.
-    Isin(String),
+    Isin(Isin),
     /// An index underlying a derivative.
     Index(Index),
     /// The LEI of an issuer underlying a derivative.
-    Lei(String),
+    Lei(Lei),
 }
 
 /// Reference data for a basket of assets which underlie a derivative instrument.
 #[derive(Debug)]
 pub struct UnderlyingBasket {
     /// A list of ISINs of the financial instruments in the basket.
-    pub isin: Vec<String>,
+    pub isin: Vec<Isin>,
     /// A list of LEIs of issuers in the basket.
-    pub issuer_lei: Vec<String>,
+    pub issuer_lei: Vec<Lei>,
 }
 
 
@@ -250,21 +269,21 @@ pub struct DerivativeAttributes {
 #[derive(Debug)]
 pub struct ReferenceData {
     /// The International Securities Identifier Number (ISO 6166) of the financial instrument.
-    pub isin: String,
+    pub isin: Isin,
     /// The full name of the financial instrument. This should give a good indication of the
     /// issuer and the particulars of the instrument.
     pub full_name: String,
     /// The Classification of Financial Instruments code (ISO 10962) of the financial instrument.
-    pub cfi: String,
+    pub cfi: Cfi,
     /// Whether the financial instrument falls within the definition of a "commodities derivative"
     /// under Article 2(1)(30) of Regulation (EU) No 600/2014.
     pub is_commodities_derivative: bool,
     /// The Legal Entity Identifier (ISO 17442) for the issuer. In certain cases, eg derivative
     /// instruments issued by the trading venue, this field will be populated with the trading
     /// venue operator's LEI.
-    pub issuer_lei: String,
+    pub issuer_lei: Lei,
     /// The Financial Instrument Short Name (ISO 18774) for the financial instrument.
-    pub fisn: String,
+    pub fisn: Fisn,
     /// Data relating to the trading or admission to trading of the financial instrument
     /// on a trading venue.
     pub trading_venue_attrs: TradingVenueAttributes,
@@ -273,7 +292,7 @@ pub struct ReferenceData {
     /// of the pair. In the case of swaptions where the underlying swap is single currency,
     /// this will be the notional currency of the underlying swap. For swaptions where the
     /// underlying is multi-currency, this will be the notional currency of leg 1 of the swap.
-    pub notional_currency: String,
+    pub notional_currency: Currency,
     /// Technical attributes of the financial instrument.
     pub technical_attributes: Option<TechnicalAttributes>,
     /// If the instrument is a debt instrument, certain debt-related attributes.
@@ -282,6 +301,75 @@ pub struct ReferenceData {
     pub derivative_attributes: Option<DerivativeAttributes>,
 }
 
+impl ReferenceData {
+    /// For a foreign exchange derivative, return the currency pair made up of the notional
+    /// currency and the second notional currency. Returns `None` if the instrument does not have
+    /// FX attributes or no second currency is specified.
+    pub fn currency_pair(&self) -> Option<CurrencyPair> {
+        let quote = self.derivative_attributes.as_ref()?
+            .asset_class_specific_attributes.as_ref()?
+            .fx_attributes.as_ref()?
+            .notional_currency_2?;
+        Some(CurrencyPair::new(self.notional_currency, quote))
+    }
+
+    /// Return the MIC of the trading venue.
+    pub fn venue(&self) -> &Mic {
+        &self.trading_venue_attrs.trading_venue
+    }
+
+    /// Return the maturity date of a debt instrument, if any.
+    pub fn maturity(&self) -> Option<NaiveDate> {
+        self.debt_attributes.as_ref()?.maturity_date
+    }
+
+    /// Return the expiry date of a derivative, if any.
+    pub fn expiry(&self) -> Option<NaiveDate> {
+        self.derivative_attributes.as_ref()?.expiry_date
+    }
+
+    /// Return the ISINs of any instruments or indices underlying a derivative (whether a single
+    /// underlying or a basket).
+    pub fn underlying_isins(&self) -> Vec<&Isin> {
+        match self.derivative_attributes.as_ref().and_then(|d| d.underlying.as_ref()) {
+            Some(DerivativeUnderlying::Single(UnderlyingSingle::Isin(isin))) => vec![isin],
+            Some(DerivativeUnderlying::Single(UnderlyingSingle::Index(index))) => index.isin.iter().collect(),
+            Some(DerivativeUnderlying::Basket(basket)) => basket.isin.iter().collect(),
+            _ => vec![]
+        }
+    }
+
+    /// Return the LEIs of any issuers underlying a derivative (whether a single underlying or a
+    /// basket).
+    pub fn underlying_leis(&self) -> Vec<&Lei> {
+        match self.derivative_attributes.as_ref().and_then(|d| d.underlying.as_ref()) {
+            Some(DerivativeUnderlying::Single(UnderlyingSingle::Lei(lei))) => vec![lei],
+            Some(DerivativeUnderlying::Basket(basket)) => basket.issuer_lei.iter().collect(),
+            _ => vec![]
+        }
+    }
+
+    /// Return the benchmark or reference rate relevant to the instrument, if any. This is the
+    /// reference rate of an interest rate derivative, the benchmark of a floating rate debt
+    /// instrument or the index underlying a derivative (in that order of preference).
+    pub fn reference_rate(&self) -> Option<&FloatingRate> {
+        let deriv_attrs = self.derivative_attributes.as_ref();
+        let ir_rate = deriv_attrs
+            .and_then(|d| d.asset_class_specific_attributes.as_ref())
+            .and_then(|a| a.ir_attributes.as_ref())
+            .map(|a| &a.reference_rate);
+        let debt_rate = self.debt_attributes.as_ref().and_then(|d| match &d.interest_rate {
+            InterestRate::Floating(rate, _) => Some(rate),
+            InterestRate::Fixed(_) => None
+        });
+        let index = deriv_attrs.and_then(|d| match &d.underlying {
+            Some(DerivativeUnderlying::Single(UnderlyingSingle::Index(index))) => Some(&index.name),
+            _ => None
+        });
+        ir_rate.or(debt_rate).or(index)
+    }
+}
+
 /// Reference data for a newly added financial instrument.
 pub struct NewRecord(pub ReferenceData);
 
@@ -293,6 +381,22 @@ pub struct TerminatedRecord(pub ReferenceData);
 
 /// Record that was cancelled on a trading venue.
 pub struct CancelledRecord {
-    pub(crate) isin: String,
-    pub(crate) trading_venue: String,
+    pub(crate) isin: Isin,
+    pub(crate) trading_venue: Mic,
+}
+
+impl CancelledRecord {
+    pub fn new(isin: Isin, trading_venue: Mic) -> Self {
+        Self { isin, trading_venue }
+    }
+
+    /// The ISIN of the cancelled record.
+    pub fn isin(&self) -> &Isin {
+        &self.isin
+    }
+
+    /// The MIC of the trading venue on which the record was cancelled.
+    pub fn trading_venue(&self) -> &Mic {
+        &self.trading_venue
+    }
 }
\ No newline at end of file
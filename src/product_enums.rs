@@ -1,6 +1,8 @@
 use crate::error::ProductError;
 use std::str::FromStr;
-use strum_macros::{Display, EnumString};
+use std::iter::once;
+use strum::{EnumMessage, IntoEnumIterator};
+use strum_macros::{Display, EnumIter, EnumMessage, EnumString};
 
 /// Verifies that `fsp` is not `None`, and then calls the given function on the `&str` wrapped by
 /// `fsp`. Used to conveniently construct subproduct enum members which require an associated
@@ -29,13 +31,26 @@ fn without_fsp_or_err<T>(sp: T, fsp: Option<&str>) -> Result<T, ProductError> {
 fn optional_fsp<T: FromStr>(fsp: Option<&str>) -> Result<Option<T>, ProductError> 
 where ProductError: From<<T as FromStr>::Err> {
     if let Some(s) = fsp {
-        Ok(Some(T::from_str(&s)?))
+        Ok(Some(T::from_str(s)?))
     } else {
         Ok(None)
     }
 }
 
-trait SubProduct {
+/// Return the human-readable description of a product enum variant.
+fn describe(e: &impl EnumMessage) -> &'static str {
+    e.get_message().unwrap_or_default()
+}
+
+/// Return a sub-product with no further sub-product, followed by the sub-product with each
+/// possible further sub-product.
+fn with_optional_fsp<T: IntoEnumIterator, S>(variant: fn(Option<T>) -> S) -> impl Iterator<Item = S> {
+    once(variant(None)).chain(T::iter().map(move |fsp| variant(Some(fsp))))
+}
+
+/// Behaviour common to the sub-product enums, each of which represents the sub-products of a
+/// single [`BaseProduct`] (together with any further sub-products).
+pub trait SubProduct {
     /// Try to create a variant of this enum based on the 4-character codes for the sub-product and,
     /// if applicable, the further sub-product.
     fn try_from_codes(sub_prod: &str, further_sub_prod: Option<&str>) 
@@ -51,43 +66,51 @@ trait SubProduct {
         let (sp, fsp) = self.to_codes();
         (Some(sp), fsp)
     }
+
+    /// Return a tuple containing human-readable descriptions of the sub-product and, if
+    /// applicable, its further sub-product.
+    fn descriptions(&self) -> (&'static str, Option<&'static str>);
+
+    /// Return every sub-product, combined with each of its possible further sub-products (and,
+    /// where the further sub-product is optional, without one).
+    fn all() -> Vec<Self> where Self: Sized;
 }
 
 /// Classification of commodity and emission allowances derivatives.
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum BaseProduct {
-    #[strum(serialize = "AGRI")]
+    #[strum(serialize = "AGRI", message = "Agricultural")]
     Agricultural(AgriculturalSubProduct),
-    #[strum(serialize = "NRGY")]
+    #[strum(serialize = "NRGY", message = "Energy")]
     Energy(EnergySubProduct),
-    #[strum(serialize = "ENVR")]
+    #[strum(serialize = "ENVR", message = "Environmental")]
     Environmental(EnvironmentalSubProduct),
-    #[strum(serialize = "FRGT")]
+    #[strum(serialize = "FRGT", message = "Freight")]
     Freight(FreightSubProduct),
-    #[strum(serialize = "FRTL")]
+    #[strum(serialize = "FRTL", message = "Fertilizer")]
     Fertilizer(FertilizerSubProduct),
-    #[strum(serialize = "INDP")]
+    #[strum(serialize = "INDP", message = "Industrial products")]
     IndustrialProducts(IndustrialProductsSubProduct),
-    #[strum(serialize = "METL")]
+    #[strum(serialize = "METL", message = "Metals")]
     Metals(MetalsSubProduct),
-    #[strum(serialize = "MCEX")]
+    #[strum(serialize = "MCEX", message = "Multi-commodity exotic")]
     MultiCommodityExotic,
-    #[strum(serialize = "PAPR")]
+    #[strum(serialize = "PAPR", message = "Paper")]
     Paper(PaperSubProduct),
-    #[strum(serialize = "POLY")]
+    #[strum(serialize = "POLY", message = "Polypropylene")]
     Polypropylene(PolypropyleneSubProduct),
-    #[strum(serialize = "INFL")]
+    #[strum(serialize = "INFL", message = "Inflation")]
     Inflation,
-    #[strum(serialize = "OEST")]
+    #[strum(serialize = "OEST", message = "Official economic statistics")]
     OfficialEconomicStatistics,
     /// Other C10 (as defined in Table 10.1 of Section 10 of Annex III to Commission Delegated
     /// Regulation supplementing Regulation (EU) No 600/2014 of the European Parliament and of the
     /// Council with regard to regulatory technical standards on transparency requirements for
     /// trading venues and investment firms in respect of bonds, structured finance products,
     /// emission allowances and derivatives)
-    #[strum(serialize = "OTHC")]
+    #[strum(serialize = "OTHC", message = "Other C10")]
     OtherC10(OtherC10SubProduct),
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
@@ -154,21 +177,79 @@ impl BaseProduct {
         };
         (self.to_string(), sp, fsp)
     }
+
+    /// Return human-readable descriptions of the base product, the sub-product (if any) and the
+    /// further sub-product (if any).
+    pub fn descriptions(&self) -> (&'static str, Option<&'static str>, Option<&'static str>) {
+        let (sp, fsp) = match self {
+            Self::Agricultural(sp) => sp.descriptions(),
+            Self::Energy(sp) => sp.descriptions(),
+            Self::Environmental(sp) => sp.descriptions(),
+            Self::Freight(sp) => sp.descriptions(),
+            Self::Fertilizer(sp) => sp.descriptions(),
+            Self::IndustrialProducts(sp) => sp.descriptions(),
+            Self::Metals(sp) => sp.descriptions(),
+            Self::Paper(sp) => sp.descriptions(),
+            Self::Polypropylene(sp) => sp.descriptions(),
+            Self::OtherC10(sp) => sp.descriptions(),
+            _ => return (describe(self), None, None)
+        };
+        (describe(self), Some(sp), fsp)
+    }
+
+    /// Return a single description of the full classification, eg, "Metals / Precious / Gold".
+    pub fn full_description(&self) -> String {
+        match self.descriptions() {
+            (p, Some(sp), Some(fsp)) => format!("{p} / {sp} / {fsp}"),
+            (p, Some(sp), None) => format!("{p} / {sp}"),
+            (p, _, _) => p.to_owned()
+        }
+    }
+
+    /// Return every possible classification, ie, every base product combined with each of its
+    /// possible sub-products and further sub-products.
+    pub fn all() -> Vec<Self> {
+        let mut all = vec![];
+        all.extend(AgriculturalSubProduct::all().into_iter().map(Self::Agricultural));
+        all.extend(EnergySubProduct::all().into_iter().map(Self::Energy));
+        all.extend(EnvironmentalSubProduct::all().into_iter().map(Self::Environmental));
+        all.extend(FreightSubProduct::all().into_iter().map(Self::Freight));
+        all.extend(FertilizerSubProduct::all().into_iter().map(Self::Fertilizer));
+        all.extend(IndustrialProductsSubProduct::all().into_iter().map(Self::IndustrialProducts));
+        all.extend(MetalsSubProduct::all().into_iter().map(Self::Metals));
+        all.push(Self::MultiCommodityExotic);
+        all.extend(PaperSubProduct::all().into_iter().map(Self::Paper));
+        all.extend(PolypropyleneSubProduct::all().into_iter().map(Self::Polypropylene));
+        all.push(Self::Inflation);
+        all.push(Self::OfficialEconomicStatistics);
+        all.extend(OtherC10SubProduct::all().into_iter().map(Self::OtherC10));
+        all.push(Self::Other);
+        all
+    }
 }
 
 
 
 /// Sub-classification of products.
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum AgriculturalSubProduct {
+    #[strum(serialize = "GROS", message = "Grains and oil seeds")]
     GrainsAndOilSeeds(GrainsAndOilSeedsFurtherSubProduct),
+    #[strum(serialize = "SOFT", message = "Softs")]
     Softs(SoftsFurtherSubProduct),
+    #[strum(serialize = "POTA", message = "Potato")]
     Potato,
+    #[strum(serialize = "OOLI", message = "Olive oil")]
     OliveOil(Option<OliveOilFurtherSubProduct>),
+    #[strum(serialize = "DIRY", message = "Dairy")]
     Dairy,
+    #[strum(serialize = "FRST", message = "Forestry")]
     Forestry,
+    #[strum(serialize = "SEAF", message = "Seafood")]
     Seafood,
+    #[strum(serialize = "LSTK", message = "Livestock")]
     Livestock,
+    #[strum(serialize = "GRIN", message = "Grain")]
     Grain(Option<GrainFurtherSubProduct>),
 }
 
@@ -199,32 +280,55 @@ impl SubProduct for AgriculturalSubProduct {
         let further_code = match self {
             Self::GrainsAndOilSeeds(fsp) => Some(fsp.to_string()),
             Self::Softs(fsp) => Some(fsp.to_string()),
-            Self::OliveOil(fsp) => if let Some(c) = fsp { Some(c.to_string()) } else { None },
-            Self::Grain(fsp) => if let Some(c) = fsp { Some(c.to_string()) } else { None },
+            Self::OliveOil(fsp) => fsp.as_ref().map(|c| c.to_string()),
+            Self::Grain(fsp) => fsp.as_ref().map(|c| c.to_string()),
             _ => None
         };
         (self.to_string(), further_code)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (
+            describe(self),
+            match self {
+                Self::GrainsAndOilSeeds(fsp) => Some(describe(fsp)),
+                Self::Softs(fsp) => Some(describe(fsp)),
+                Self::OliveOil(fsp) => fsp.as_ref().map(describe),
+                Self::Grain(fsp) => fsp.as_ref().map(describe),
+                _ => None
+            }
+        )
+    }
+
+    fn all() -> Vec<Self> {
+        GrainsAndOilSeedsFurtherSubProduct::iter().map(Self::GrainsAndOilSeeds)
+            .chain(SoftsFurtherSubProduct::iter().map(Self::Softs))
+            .chain([Self::Potato])
+            .chain(with_optional_fsp(Self::OliveOil))
+            .chain([Self::Dairy, Self::Forestry, Self::Seafood, Self::Livestock])
+            .chain(with_optional_fsp(Self::Grain))
+            .collect()
+    }
 }
 
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum EnergySubProduct {
-    #[strum(serialize = "ELEC")]
+    #[strum(serialize = "ELEC", message = "Electricity")]
     Electricity(ElectricityFurtherSubProduct),
-    #[strum(serialize = "NGAS")]
+    #[strum(serialize = "NGAS", message = "Natural gas")]
     NaturalGas(Option<NaturalGasFurtherSubProduct>),
-    #[strum(serialize = "OILP")]
+    #[strum(serialize = "OILP", message = "Oil")]
     Oil(Option<OilFurtherSubProduct>),
-    #[strum(serialize = "COAL")]
+    #[strum(serialize = "COAL", message = "Coal")]
     Coal,
-    #[strum(serialize = "INRG")]
+    #[strum(serialize = "INRG", message = "Inter-energy")]
     InterEnergy,
-    #[strum(serialize = "RNNG")]
+    #[strum(serialize = "RNNG", message = "Renewable energy")]
     RenewableEnergy,
-    #[strum(serialize = "LGHT")]
+    #[strum(serialize = "LGHT", message = "Light ends")]
     LightEnds,
-    #[strum(serialize = "DIST")]
+    #[strum(serialize = "DIST", message = "Distillates")]
     Distillates,
 }
 
@@ -253,21 +357,47 @@ impl SubProduct for EnergySubProduct {
             self.to_string(),
             match self {
                 Self::Electricity(fsp) => Some(fsp.to_string()),
-                Self::NaturalGas(fsp) => if let Some(c) = fsp { Some(c.to_string()) } else { None },
-                Self::Oil(fsp) => if let Some(c) = fsp { Some(c.to_string()) } else { None },
+                Self::NaturalGas(fsp) => fsp.as_ref().map(|c| c.to_string()),
+                Self::Oil(fsp) => fsp.as_ref().map(|c| c.to_string()),
                 _ => None
             }
         )
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (
+            describe(self),
+            match self {
+                Self::Electricity(fsp) => Some(describe(fsp)),
+                Self::NaturalGas(fsp) => fsp.as_ref().map(describe),
+                Self::Oil(fsp) => fsp.as_ref().map(describe),
+                _ => None
+            }
+        )
+    }
+
+    fn all() -> Vec<Self> {
+        ElectricityFurtherSubProduct::iter().map(Self::Electricity)
+            .chain(with_optional_fsp(Self::NaturalGas))
+            .chain(with_optional_fsp(Self::Oil))
+            .chain([
+                Self::Coal,
+                Self::InterEnergy,
+                Self::RenewableEnergy,
+                Self::LightEnds,
+                Self::Distillates
+            ])
+            .collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum EnvironmentalSubProduct {
-    #[strum(serialize = "EMIS")]
+    #[strum(serialize = "EMIS", message = "Emissions")]
     Emissions(Option<EmissionsFurtherSubProduct>),
-    #[strum(serialize = "WTHR")]
+    #[strum(serialize = "WTHR", message = "Weather")]
     Weather,
-    #[strum(serialize = "CRBR")]
+    #[strum(serialize = "CRBR", message = "Carbon related")]
     CarbonRelated,
 }
 
@@ -289,17 +419,36 @@ impl SubProduct for EnvironmentalSubProduct {
             self.to_string(),
             match self {
                 Self::Emissions(fsp) =>
-                    if let Some(fsp) = fsp { Some(fsp.to_string()) } else { None },
+                    fsp.as_ref().map(|fsp| fsp.to_string()),
+                _ => None
+            }
+        )
+    }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (
+            describe(self),
+            match self {
+                Self::Emissions(fsp) => fsp.as_ref().map(describe),
                 _ => None
             }
         )
     }
+
+    fn all() -> Vec<Self> {
+        with_optional_fsp(Self::Emissions)
+            .chain([Self::Weather, Self::CarbonRelated])
+            .collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum FreightSubProduct {
+    #[strum(serialize = "WETF", message = "Wet freight")]
     Wet(Option<WetFreightFurtherSubProduct>),
+    #[strum(serialize = "DRYF", message = "Dry freight")]
     Dry(Option<DryFreightFurtherSubProduct>),
+    #[strum(serialize = "CSHP", message = "Container ships")]
     ContainerShips,
 }
 
@@ -320,27 +469,45 @@ impl SubProduct for FreightSubProduct {
         (
             self.to_string(),
             match self {
-                Self::Wet(fsp) => if let Some(fsp) = fsp { Some(fsp.to_string()) } else { None },
-                Self::Dry(fsp) => if let Some(fsp) = fsp { Some(fsp.to_string()) } else { None },
+                Self::Wet(fsp) => fsp.as_ref().map(|fsp| fsp.to_string()),
+                Self::Dry(fsp) => fsp.as_ref().map(|fsp| fsp.to_string()),
+                _ => None
+            }
+        )
+    }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (
+            describe(self),
+            match self {
+                Self::Wet(fsp) => fsp.as_ref().map(describe),
+                Self::Dry(fsp) => fsp.as_ref().map(describe),
                 _ => None
             }
         )
     }
+
+    fn all() -> Vec<Self> {
+        with_optional_fsp(Self::Wet)
+            .chain(with_optional_fsp(Self::Dry))
+            .chain([Self::ContainerShips])
+            .collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage, EnumIter)]
 pub enum FertilizerSubProduct {
-    #[strum(serialize = "AMMO")]
+    #[strum(serialize = "AMMO", message = "Ammonia")]
     Ammonia,
-    #[strum(serialize = "DAPH")]
+    #[strum(serialize = "DAPH", message = "Diammonium phosphate (DAP)")]
     Dap,
-    #[strum(serialize = "PTSH")]
+    #[strum(serialize = "PTSH", message = "Potash")]
     Potash,
-    #[strum(serialize = "SLPH")]
+    #[strum(serialize = "SLPH", message = "Sulphur")]
     Sulphur,
-    #[strum(serialize = "UREA")]
+    #[strum(serialize = "UREA", message = "Urea")]
     Urea,
-    #[strum(serialize = "UAAN")]
+    #[strum(serialize = "UAAN", message = "Urea and ammonium nitrate (UAN)")]
     Uan,
 }
 
@@ -363,13 +530,21 @@ impl SubProduct for FertilizerSubProduct {
     fn to_codes(&self) -> (String, Option<String>) {
         (self.to_string(), None)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (describe(self), None)
+    }
+
+    fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum IndustrialProductsSubProduct {
-    #[strum(serialize = "CSTR")]
+    #[strum(serialize = "CSTR", message = "Construction")]
     Construction,
-    #[strum(serialize = "MFTG")]
+    #[strum(serialize = "MFTG", message = "Manufacturing")]
     Manufacturing,
 }
 
@@ -388,13 +563,21 @@ impl SubProduct for IndustrialProductsSubProduct {
     fn to_codes(&self) -> (String, Option<String>) {
         (self.to_string(), None)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (describe(self), None)
+    }
+
+    fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage)]
 pub enum MetalsSubProduct {
-    #[strum(serialize = "NPRM")]
+    #[strum(serialize = "NPRM", message = "Non-precious")]
     NonPrecious(NonPreciousMetalsFurtherSubProduct),
-    #[strum(serialize = "PRME")]
+    #[strum(serialize = "PRME", message = "Precious")]
     Precious(PreciousMetalsFurtherSubProduct),
 }
 
@@ -423,13 +606,33 @@ impl SubProduct for MetalsSubProduct {
             }
         )
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (
+            describe(self),
+            match self {
+                Self::NonPrecious(fsp) => Some(describe(fsp)),
+                Self::Precious(fsp) => Some(describe(fsp)),
+            }
+        )
+    }
+
+    fn all() -> Vec<Self> {
+        NonPreciousMetalsFurtherSubProduct::iter().map(Self::NonPrecious)
+            .chain(PreciousMetalsFurtherSubProduct::iter().map(Self::Precious))
+            .collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage, EnumIter)]
 pub enum PaperSubProduct {
+    #[strum(serialize = "CBRD", message = "Containerboard")]
     Containerboard,
+    #[strum(serialize = "NSPT", message = "Newsprint")]
     Newsprint,
+    #[strum(serialize = "PULP", message = "Pulp")]
     Pulp,
+    #[strum(serialize = "RCVP", message = "Recovered paper")]
     RecoveredPaper,
 }
 
@@ -450,10 +653,19 @@ impl SubProduct for PaperSubProduct {
     fn to_codes(&self) -> (String, Option<String>) {
         (self.to_string(), None)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (describe(self), None)
+    }
+
+    fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, PartialEq, Display, EnumMessage, EnumIter)]
 pub enum PolypropyleneSubProduct {
+    #[strum(serialize = "PLST", message = "Plastic")]
     Plastic,
 }
 
@@ -471,13 +683,21 @@ impl SubProduct for PolypropyleneSubProduct {
     fn to_codes(&self) -> (String, Option<String>) {
         (self.to_string(), None)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (describe(self), None)
+    }
+
+    fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum OtherC10SubProduct {
-    #[strum(serialize = "DLVR")]
+    #[strum(serialize = "DLVR", message = "Deliverable")]
     Deliverable,
-    #[strum(serialize = "NDLV")]
+    #[strum(serialize = "NDLV", message = "Non-deliverable")]
     NonDeliverable,
 }
 
@@ -496,197 +716,250 @@ impl SubProduct for OtherC10SubProduct {
     fn to_codes(&self) -> (String, Option<String>) {
         (self.to_string(), None)
     }
+
+    fn descriptions(&self) -> (&'static str, Option<&'static str>) {
+        (describe(self), None)
+    }
+
+    fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
 }
 
 /// Further sub-classifications of products.
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum GrainsAndOilSeedsFurtherSubProduct {
-    #[strum(serialize = "FWHT")]
+    #[strum(serialize = "FWHT", message = "Feed wheat")]
     FeedWheat,
-    #[strum(serialize = "SOYB")]
+    #[strum(serialize = "SOYB", message = "Soybeans")]
     Soybeans,
-    #[strum(serialize = "CORN")]
+    #[strum(serialize = "CORN", message = "Corn")]
     Corn,
-    #[strum(serialize = "RPSD")]
+    #[strum(serialize = "RPSD", message = "Rapeseed")]
     Rapeseed,
-    #[strum(serialize = "RICE")]
+    #[strum(serialize = "RICE", message = "Rice")]
     Rice,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum SoftsFurtherSubProduct {
-    #[strum(serialize = "CCOA")]
+    #[strum(serialize = "CCOA", message = "Cocoa")]
     Cocoa,
-    #[strum(serialize = "ROBU")]
+    #[strum(serialize = "ROBU", message = "Robusta coffee")]
     RobustaCoffee,
-    #[strum(serialize = "WHSG")]
+    #[strum(serialize = "WHSG", message = "White sugar")]
     WhiteSugar,
-    #[strum(serialize = "BRWN")]
+    #[strum(serialize = "BRWN", message = "Raw sugar")]
     RawSugar,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum OliveOilFurtherSubProduct {
-    #[strum(serialize = "LAMP")]
+    #[strum(serialize = "LAMP", message = "Lampante")]
     Lampante,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum GrainFurtherSubProduct {
-    #[strum(serialize = "MWHT")]
+    #[strum(serialize = "MWHT", message = "Milling wheat")]
     MillingWheat,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum ElectricityFurtherSubProduct {
-    #[strum(serialize = "BSLD")]
+    #[strum(serialize = "BSLD", message = "Base load")]
     BaseLoad,
-    #[strum(serialize = "FITR")]
+    #[strum(serialize = "FITR", message = "Financial transmission rights")]
     FinancialTransmissionRights,
-    #[strum(serialize = "PKLD")]
+    #[strum(serialize = "PKLD", message = "Peak load")]
     PeakLoad,
-    #[strum(serialize = "OFFP")]
+    #[strum(serialize = "OFFP", message = "Off-peak")]
     OffPeak,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum NaturalGasFurtherSubProduct {
-    #[strum(serialize = "GASP")]
+    #[strum(serialize = "GASP", message = "GASPOOL")]
     Gaspool,
-    #[strum(serialize = "LNGG")]
+    #[strum(serialize = "LNGG", message = "LNG")]
     Lng,
-    #[strum(serialize = "NBPG")]
+    #[strum(serialize = "NBPG", message = "NBP")]
     Nbp,
-    #[strum(serialize = "NCGG")]
+    #[strum(serialize = "NCGG", message = "NCG")]
     Ncg,
-    #[strum(serialize = "TTFG")]
+    #[strum(serialize = "TTFG", message = "TTF")]
     Ttf,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum OilFurtherSubProduct {
-    #[strum(serialize = "BAKK")]
+    #[strum(serialize = "BAKK", message = "Bakken")]
     Bakken,
-    #[strum(serialize = "BDSL")]
+    #[strum(serialize = "BDSL", message = "Biodiesel")]
     Biodiesel,
-    #[strum(serialize = "BRNT")]
+    #[strum(serialize = "BRNT", message = "Brent")]
     Brent,
-    #[strum(serialize = "BRNX")]
+    #[strum(serialize = "BRNX", message = "Brent NX")]
     BrentNx,
-    #[strum(serialize = "CNDA")]
+    #[strum(serialize = "CNDA", message = "Canadian")]
     Canadian,
-    #[strum(serialize = "COND")]
+    #[strum(serialize = "COND", message = "Condensate")]
     Condensate,
-    #[strum(serialize = "DSEL")]
+    #[strum(serialize = "DSEL", message = "Diesel")]
     Diesel,
-    #[strum(serialize = "DUBA")]
+    #[strum(serialize = "DUBA", message = "Dubai")]
     Dubai,
-    #[strum(serialize = "ESPO")]
+    #[strum(serialize = "ESPO", message = "ESPO")]
     Espo,
-    #[strum(serialize = "ETHA")]
+    #[strum(serialize = "ETHA", message = "Ethanol")]
     Ethanol,
-    #[strum(serialize = "FUEL")]
+    #[strum(serialize = "FUEL", message = "Fuel")]
     Fuel,
-    #[strum(serialize = "FOIL")]
+    #[strum(serialize = "FOIL", message = "Fuel oil")]
     FuelOil,
-    #[strum(serialize = "GOIL")]
+    #[strum(serialize = "GOIL", message = "Gasoil")]
     Gasoil,
-    #[strum(serialize = "GSLN")]
+    #[strum(serialize = "GSLN", message = "Gasoline")]
     Gasoline,
-    #[strum(serialize = "HEAT")]
+    #[strum(serialize = "HEAT", message = "Heating oil")]
     HeatingOil,
-    #[strum(serialize = "JTFL")]
+    #[strum(serialize = "JTFL", message = "Jet fuel")]
     JetFuel,
-    #[strum(serialize = "KERO")]
+    #[strum(serialize = "KERO", message = "Kerosene")]
     Kerosene,
-    #[strum(serialize = "LLSO")]
+    #[strum(serialize = "LLSO", message = "Light Louisiana Sweet")]
     LightLouisianaSweet,
-    #[strum(serialize = "MARS")]
+    #[strum(serialize = "MARS", message = "Mars")]
     Mars,
-    #[strum(serialize = "NAPH")]
+    #[strum(serialize = "NAPH", message = "Naphtha")]
     Naphtha,
-    #[strum(serialize = "NGLO")]
+    #[strum(serialize = "NGLO", message = "NGL")]
     Ngl,
-    #[strum(serialize = "TAPI")]
+    #[strum(serialize = "TAPI", message = "Tapis")]
     Tapis,
-    #[strum(serialize = "URAL")]
+    #[strum(serialize = "URAL", message = "Urals")]
     Urals,
-    #[strum(serialize = "WTIO")]
+    #[strum(serialize = "WTIO", message = "WTI")]
     Wti,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum EmissionsFurtherSubProduct {
-    #[strum(serialize = "CERE")]
+    #[strum(serialize = "CERE", message = "CER")]
     Cer,
-    #[strum(serialize = "ERUE")]
+    #[strum(serialize = "ERUE", message = "ERU")]
     Eru,
-    #[strum(serialize = "EUAE")]
+    #[strum(serialize = "EUAE", message = "EUAE")]
     Euae,
-    #[strum(serialize = "EUAA")]
+    #[strum(serialize = "EUAA", message = "EUAA")]
     Euaa,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum WetFreightFurtherSubProduct {
-    #[strum(serialize = "TNKR")]
+    #[strum(serialize = "TNKR", message = "Tankers")]
     Tankers,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum DryFreightFurtherSubProduct {
-    #[strum(serialize = "DBCR")]
+    #[strum(serialize = "DBCR", message = "Dry bulk carriers")]
     DryBulkCarriers,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum NonPreciousMetalsFurtherSubProduct {
-    #[strum(serialize = "ALUM")]
+    #[strum(serialize = "ALUM", message = "Aluminium")]
     Aluminium,
-    #[strum(serialize = "ALUA")]
+    #[strum(serialize = "ALUA", message = "Aluminium alloy")]
     AluminiumAlloy,
-    #[strum(serialize = "CBLT")]
+    #[strum(serialize = "CBLT", message = "Cobalt")]
     Cobalt,
-    #[strum(serialize = "COPR")]
+    #[strum(serialize = "COPR", message = "Copper")]
     Copper,
-    #[strum(serialize = "IRON")]
+    #[strum(serialize = "IRON", message = "Iron ore")]
     IronOre,
-    #[strum(serialize = "LEAD")]
+    #[strum(serialize = "LEAD", message = "Lead")]
     Lead,
-    #[strum(serialize = "MOLY")]
+    #[strum(serialize = "MOLY", message = "Molybdenum")]
     Molybdenum,
-    #[strum(serialize = "NASC")]
+    #[strum(serialize = "NASC", message = "NASAAC")]
     Nasaac,
-    #[strum(serialize = "NICK")]
+    #[strum(serialize = "NICK", message = "Nickel")]
     Nickel,
-    #[strum(serialize = "STEL")]
+    #[strum(serialize = "STEL", message = "Steel")]
     Steel,
-    #[strum(serialize = "TINN")]
+    #[strum(serialize = "TINN", message = "Tin")]
     Tin,
-    #[strum(serialize = "ZINC")]
+    #[strum(serialize = "ZINC", message = "Zinc")]
     Zinc,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
 }
 
-#[derive(Debug, EnumString, Display)]
+#[derive(Debug, Clone, PartialEq, EnumString, Display, EnumMessage, EnumIter)]
 pub enum PreciousMetalsFurtherSubProduct {
-    #[strum(serialize = "GOLD")]
+    #[strum(serialize = "GOLD", message = "Gold")]
     Gold,
-    #[strum(serialize = "SLVR")]
+    #[strum(serialize = "SLVR", message = "Silver")]
     Silver,
-    #[strum(serialize = "PTNM")]
+    #[strum(serialize = "PTNM", message = "Platinum")]
     Platinum,
-    #[strum(serialize = "PLDM")]
+    #[strum(serialize = "PLDM", message = "Palladium")]
     Palladium,
-    #[strum(serialize = "OTHR")]
+    #[strum(serialize = "OTHR", message = "Other")]
     Other,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::product_enums::BaseProduct;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_product_codes_round_trip() {
+        let all = BaseProduct::all();
+        let mut seen = HashSet::new();
+        for product in &all {
+            let (p, sp, fsp) = product.to_codes();
+            for code in [Some(&p), sp.as_ref(), fsp.as_ref()].into_iter().flatten() {
+                assert!(code.len() == 4 && code.chars().all(|c| c.is_ascii_uppercase()), "{code}");
+            }
+            let parsed = BaseProduct::try_from_codes(&p, sp.as_deref(), fsp.as_deref()).unwrap();
+            assert_eq!(&parsed, product);
+            assert!(seen.insert((p, sp, fsp)), "duplicate codes for {product:?}");
+
+            let (pd, spd, fspd) = product.descriptions();
+            assert!(!pd.is_empty());
+            assert_eq!(spd.is_some(), product.to_codes().1.is_some(), "{product:?}");
+            assert_eq!(fspd.is_some(), product.to_codes().2.is_some(), "{product:?}");
+        }
+        assert_eq!(
+            BaseProduct::try_from_codes("AGRI", Some("GROS"), Some("FWHT")).unwrap().to_codes(),
+            ("AGRI".to_owned(), Some("GROS".to_owned()), Some("FWHT".to_owned()))
+        );
+        assert_eq!(
+            BaseProduct::try_from_codes("METL", Some("PRME"), Some("GOLD")).unwrap().full_description(),
+            "Metals / Precious / Gold"
+        );
+    }
+
+    #[test]
+    fn test_schema_contains_all_codes() {
+        let schema = include_str!("../sql/schema.sql");
+        for product in BaseProduct::all() {
+            let (p, sp, fsp) = product.to_codes();
+            for code in [Some(p), sp, fsp].into_iter().flatten() {
+                assert!(schema.contains(&format!("('{code}', ")), "{code} missing from schema");
+            }
+        }
+    }
+}
@@ -0,0 +1,903 @@
+//! A fast path for loading large numbers of records (such as a full set of FULINS files) into an
+//! SQLite database.
+//!
+//! [`ToDb`](crate::sql::ToDb) inserts each nested struct with its own `INSERT` statement and reads
+//! back its ID before inserting the parent. The [`BulkLoader`] instead allocates row IDs itself,
+//! buffers the rows for each table and writes them using multi-row `INSERT` statements. Because
+//! every full batch produces the same SQL, the statements are prepared once and then re-used from
+//! the connection's statement cache.
+//!
+//! Like the [`ToDb`](crate::sql::ToDb) impls, the loader re-uses existing `Term`, `FloatingRate`
+//! and `FirdsIndex` rows rather than inserting duplicates.
+//!
+//! The loader assumes that nothing else writes to the database while it is running, and it does not
+//! supersede existing records with the same ISIN and MIC, so it is intended for the initial import
+//! of a FULINS file set into an empty (or freshly initialised) database. DLTINS deltas should be
+//! applied using [`RefDataDbEntry::mark_prev_record`] and [`ToDb`](crate::sql::ToDb) as usual.
+
+use crate::sql::error::SqlError;
+use crate::sql::to_db::RefDataDbEntry;
+use crate::*;
+use sqlx::{Connection, Executor, QueryBuilder, Row, Sqlite, SqliteConnection, SqliteTransaction};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+use strum_macros::{Display, EnumString};
+
+/// The maximum number of bound parameters in a single statement. The SQLite bundled with [`sqlx`]
+/// is always recent enough (3.32 or later) to have this limit.
+const MAX_VARIABLES: usize = 32766;
+
+/// Values of the SQLite [`journal_mode`](https://www.sqlite.org/pragma.html#pragma_journal_mode)
+/// PRAGMA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum JournalMode {
+    #[strum(serialize = "DELETE")]
+    Delete,
+    #[strum(serialize = "TRUNCATE")]
+    Truncate,
+    #[strum(serialize = "PERSIST")]
+    Persist,
+    #[strum(serialize = "MEMORY")]
+    Memory,
+    #[strum(serialize = "WAL")]
+    Wal,
+    #[strum(serialize = "OFF")]
+    Off,
+}
+
+/// Values of the SQLite [`synchronous`](https://www.sqlite.org/pragma.html#pragma_synchronous)
+/// PRAGMA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum Synchronous {
+    #[strum(serialize = "OFF", serialize = "0")]
+    Off,
+    #[strum(serialize = "NORMAL", serialize = "1")]
+    Normal,
+    #[strum(serialize = "FULL", serialize = "2")]
+    Full,
+    #[strum(serialize = "EXTRA", serialize = "3")]
+    Extra,
+}
+
+/// PRAGMAs affecting the speed (and durability) of writes to an SQLite database. Only the PRAGMAs
+/// which are `Some` are changed.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Pragmas {
+    pub journal_mode: Option<JournalMode>,
+    pub synchronous: Option<Synchronous>,
+}
+
+impl Pragmas {
+    /// Set the PRAGMAs on the given connection, which must not be in a transaction. Returns the
+    /// previous values of the PRAGMAs that were changed, which can be passed to this method again
+    /// to restore them.
+    pub async fn apply(&self, conn: &mut SqliteConnection) -> Result<Pragmas, SqlError> {
+        let mut prev = Pragmas::default();
+        if let Some(mode) = self.journal_mode {
+            let current: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&mut *conn).await?;
+            prev.journal_mode = current.parse().ok();
+            conn.execute(format!("PRAGMA journal_mode = {mode}").as_str()).await?;
+        }
+        if let Some(sync) = self.synchronous {
+            let current: i64 = sqlx::query_scalar("PRAGMA synchronous").fetch_one(&mut *conn).await?;
+            prev.synchronous = current.to_string().parse().ok();
+            conn.execute(format!("PRAGMA synchronous = {sync}").as_str()).await?;
+        }
+        Ok(prev)
+    }
+}
+
+/// Options controlling a bulk load.
+#[derive(Debug, Clone)]
+pub struct BulkLoadOptions {
+    /// The number of records to buffer before writing them to the database.
+    pub batch_size: usize,
+    /// Whether to drop the indexes on the database before loading and re-create them afterwards,
+    /// which is considerably faster than updating them as each batch is inserted.
+    pub defer_indexes: bool,
+    /// The PRAGMAs to use while loading with [`bulk_load`]. The previous values are restored
+    /// afterwards.
+    pub pragmas: Pragmas,
+}
+
+impl Default for BulkLoadOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 10_000,
+            defer_indexes: true,
+            pragmas: Pragmas::default(),
+        }
+    }
+}
+
+impl BulkLoadOptions {
+    /// Options for the fastest possible import, with journaling and syncing disabled. If the
+    /// process crashes or the machine loses power during the load, the database may be corrupted,
+    /// so this should only be used when the database can be rebuilt from the source files.
+    pub fn fast_import() -> Self {
+        Self {
+            pragmas: Pragmas {
+                journal_mode: Some(JournalMode::Off),
+                synchronous: Some(Synchronous::Off),
+            },
+            ..Self::default()
+        }
+    }
+}
+
+/// Statistics about a completed bulk load.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadStats {
+    /// The number of [`RefDataDbEntry`] records loaded.
+    pub records: u64,
+    /// The total number of rows inserted across all tables.
+    pub rows: u64,
+    /// The number of batches written.
+    pub batches: u64,
+    /// The time taken by the load, including re-creating any deferred indexes.
+    pub elapsed: Duration,
+}
+
+impl LoadStats {
+    /// The number of records loaded per second.
+    pub fn records_per_second(&self) -> f64 {
+        self.records as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl Display for LoadStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "Loaded {} records ({} rows in {} batches) in {:.2}s ({:.0} records/s)",
+            self.records, self.rows, self.batches, self.elapsed.as_secs_f64(), self.records_per_second()
+        )
+    }
+}
+
+/// A single value to be bound to a statement.
+enum Value {
+    Null,
+    Int(i64),
+    Real(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Value::Int(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::Int(v.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Real(v)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Text(v)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Text(v.to_string())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+/// The buffered rows for a single table.
+struct Table {
+    name: &'static str,
+    /// The table's columns. If the first column is `id`, IDs are allocated by the loader.
+    columns: &'static [&'static str],
+    rows: Vec<Vec<Value>>,
+    next_id: i64,
+}
+
+impl Table {
+    const fn new(name: &'static str, columns: &'static [&'static str]) -> Self {
+        Self { name, columns, rows: Vec::new(), next_id: 1 }
+    }
+
+    fn has_id(&self) -> bool {
+        self.columns.first() == Some(&"id")
+    }
+
+    /// Buffer a row, allocating and returning its ID. `values` should not include the ID.
+    fn push(&mut self, mut values: Vec<Value>) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        values.insert(0, Value::Int(id));
+        self.rows.push(values);
+        id
+    }
+
+    /// Buffer a row of a table which has no `id` column.
+    fn push_row(&mut self, values: Vec<Value>) {
+        self.rows.push(values);
+    }
+
+    /// Set `next_id` to follow the largest ID already in the database.
+    async fn init_id(&mut self, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+        if self.has_id() {
+            let max: i64 = sqlx::query_scalar(&format!("SELECT COALESCE(MAX(id), 0) FROM {}", self.name))
+                .fetch_one(&mut **tx)
+                .await?;
+            self.next_id = max + 1;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered rows to the database, returning the number of rows written.
+    async fn flush(&mut self, tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+        let max_rows = MAX_VARIABLES / self.columns.len();
+        for chunk in self.rows.chunks(max_rows) {
+            let mut query = QueryBuilder::<Sqlite>::new(
+                format!("INSERT INTO {} ({}) ", self.name, self.columns.join(", "))
+            );
+            query.push_values(chunk, |mut b, row| {
+                for value in row {
+                    match value {
+                        Value::Null => b.push_bind(None::<i64>),
+                        Value::Int(v) => b.push_bind(*v),
+                        Value::Real(v) => b.push_bind(*v),
+                        Value::Bool(v) => b.push_bind(*v),
+                        Value::Text(v) => b.push_bind(v.as_str()),
+                    };
+                }
+            });
+            query.build().execute(&mut **tx).await?;
+        }
+        let n = self.rows.len() as u64;
+        self.rows.clear();
+        Ok(n)
+    }
+}
+
+/// Buffered rows for every table, in the order they must be written so that rows are always
+/// written after the rows they reference.
+struct Batch {
+    term: Table,
+    floating_rate: Table,
+    firds_index: Table,
+    strike_price: Table,
+    trading_venue_attrs: Table,
+    publication_period: Table,
+    technical_attrs: Table,
+    interest_rate: Table,
+    debt_attrs: Table,
+    commodity_attrs: Table,
+    ir_attrs: Table,
+    fx_attrs: Table,
+    basket: Table,
+    basket_isin: Table,
+    basket_lei: Table,
+    underlying_single: Table,
+    underlying: Table,
+    asset_class_attrs: Table,
+    derivative_attrs: Table,
+    ref_data: Table,
+    records: usize,
+    /// The IDs of the shared [`Term`], [`FloatingRate`] and [`Index`] rows, either already in the
+    /// database or allocated by the loader, keyed by their values.
+    term_ids: HashMap<(i32, String), i64>,
+    floating_rate_ids: HashMap<(Option<String>, Option<i64>), i64>,
+    index_ids: HashMap<(Option<String>, i64), i64>,
+}
+
+impl Batch {
+    fn new() -> Self {
+        Self {
+            term: Table::new("Term", &["id", "number", "unit"]),
+            floating_rate: Table::new("FloatingRate", &["id", "name", "term_id"]),
+            firds_index: Table::new("FirdsIndex", &["id", "isin", "name_id"]),
+            strike_price: Table::new("StrikePrice", &["id", "price_type", "price", "pending", "currency"]),
+            trading_venue_attrs: Table::new("TradingVenueAttributes", &[
+                "id", "trading_venue", "requested_admission", "approval_date", "request_date",
+                "admission_or_first_trade_date", "termination_date"
+            ]),
+            publication_period: Table::new("PublicationPeriod", &["id", "from_date", "to_date"]),
+            technical_attrs: Table::new("TechnicalAttributes", &[
+                "id", "relevant_competent_authority", "publication_period_id", "relevant_trading_venue"
+            ]),
+            interest_rate: Table::new("InterestRate", &["id", "fixed", "floating_rate_id", "spread"]),
+            debt_attrs: Table::new("DebtAttributes", &[
+                "id", "total_issued_amount", "maturity_date", "nominal_currency",
+                "nominal_value_per_unit", "interest_rate_id", "seniority"
+            ]),
+            commodity_attrs: Table::new("CommodityDerivativeAttributes", &[
+                "id", "product", "subproduct", "further_subproduct", "transaction_type", "final_price_type"
+            ]),
+            ir_attrs: Table::new("InterestRateDerivativeAttributes", &[
+                "id", "reference_rate_id", "interest_rate_1_id", "notional_currency_2", "interest_rate_2_id"
+            ]),
+            fx_attrs: Table::new("FxDerivativeAttributes", &["id", "notional_currency_2", "fx_type"]),
+            basket: Table::new("UnderlyingBasket", &["id"]),
+            basket_isin: Table::new("UnderlyingBasketIsin", &["basket_id", "isin"]),
+            basket_lei: Table::new("UnderlyingBasketIssuerLei", &["basket_id", "issuer_lei"]),
+            underlying_single: Table::new("UnderlyingSingle", &["id", "isin", "index_id", "lei"]),
+            underlying: Table::new("DerivativeUnderlying", &["id", "single_id", "basket_id"]),
+            asset_class_attrs: Table::new("AssetClassSpecificAttributes", &[
+                "id", "commodity_attributes_id", "ir_attributes_id", "fx_attributes_id"
+            ]),
+            derivative_attrs: Table::new("DerivativeAttributes", &[
+                "id", "expiry_date", "price_multiplier", "underlying_id", "option_type", "strike_price_id",
+                "option_exercise_style", "delivery_type", "asset_class_specific_attributes_id"
+            ]),
+            ref_data: Table::new("ReferenceData", &[
+                "id", "isin", "full_name", "cfi", "is_commodities_derivative", "issuer_lei", "fisn",
+                "trading_venue_attrs_id", "notional_currency", "technical_attributes_id",
+                "debt_attributes_id", "derivative_attributes_id", "instrument_class", "latest_record",
+                "valid_from", "valid_to", "source_file_id", "record_type"
+            ]),
+            records: 0,
+            term_ids: HashMap::new(),
+            floating_rate_ids: HashMap::new(),
+            index_ids: HashMap::new(),
+        }
+    }
+
+    /// Load the existing shared rows from the database, so that they are re-used rather than
+    /// duplicated.
+    async fn load_shared_ids(&mut self, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+        let terms: Vec<(i64, i32, String)> = sqlx::query_as("SELECT id, number, unit FROM Term")
+            .fetch_all(&mut **tx).await?;
+        self.term_ids = terms.into_iter().map(|(id, number, unit)| ((number, unit), id)).collect();
+        let rates: Vec<(i64, Option<String>, Option<i64>)> = sqlx::query_as("SELECT id, name, term_id FROM FloatingRate")
+            .fetch_all(&mut **tx).await?;
+        self.floating_rate_ids = rates.into_iter().map(|(id, name, term_id)| ((name, term_id), id)).collect();
+        let indexes: Vec<(i64, Option<String>, i64)> = sqlx::query_as("SELECT id, isin, name_id FROM FirdsIndex")
+            .fetch_all(&mut **tx).await?;
+        self.index_ids = indexes.into_iter().map(|(id, isin, name_id)| ((isin, name_id), id)).collect();
+        Ok(())
+    }
+
+    fn tables(&mut self) -> [&mut Table; 20] {
+        [
+            &mut self.term,
+            &mut self.floating_rate,
+            &mut self.firds_index,
+            &mut self.strike_price,
+            &mut self.trading_venue_attrs,
+            &mut self.publication_period,
+            &mut self.technical_attrs,
+            &mut self.interest_rate,
+            &mut self.debt_attrs,
+            &mut self.commodity_attrs,
+            &mut self.ir_attrs,
+            &mut self.fx_attrs,
+            &mut self.basket,
+            &mut self.basket_isin,
+            &mut self.basket_lei,
+            &mut self.underlying_single,
+            &mut self.underlying,
+            &mut self.asset_class_attrs,
+            &mut self.derivative_attrs,
+            &mut self.ref_data,
+        ]
+    }
+
+    fn term(&mut self, term: &Term) -> i64 {
+        let key = (term.number, term.unit.to_string());
+        if let Some(id) = self.term_ids.get(&key) {
+            return *id
+        }
+        let id = self.term.push(vec![key.0.into(), key.1.as_str().into()]);
+        self.term_ids.insert(key, id);
+        id
+    }
+
+    fn floating_rate(&mut self, rate: &FloatingRate) -> i64 {
+        let term_id = rate.term.as_ref().map(|t| self.term(t));
+        let key = (rate.name.as_ref().map(|n| n.to_string()), term_id);
+        if let Some(id) = self.floating_rate_ids.get(&key) {
+            return *id
+        }
+        let id = self.floating_rate.push(vec![key.0.clone().into(), term_id.into()]);
+        self.floating_rate_ids.insert(key, id);
+        id
+    }
+
+    fn index(&mut self, index: &Index) -> i64 {
+        let name_id = self.floating_rate(&index.name);
+        let key = (index.isin.as_ref().map(|i| i.to_string()), name_id);
+        if let Some(id) = self.index_ids.get(&key) {
+            return *id
+        }
+        let id = self.firds_index.push(vec![key.0.clone().into(), name_id.into()]);
+        self.index_ids.insert(key, id);
+        id
+    }
+
+    fn strike_price(&mut self, price: &StrikePrice) -> i64 {
+        self.strike_price.push(vec![
+            price.price_type.to_string().into(),
+            price.price.into(),
+            price.pending.into(),
+            price.currency.as_ref().map(|c| c.as_str()).into(),
+        ])
+    }
+
+    fn trading_venue_attrs(&mut self, attrs: &TradingVenueAttributes) -> i64 {
+        self.trading_venue_attrs.push(vec![
+            attrs.trading_venue.as_str().into(),
+            attrs.requested_admission.into(),
+            attrs.approval_date.map(|d| d.to_string()).into(),
+            attrs.request_date.map(|d| d.to_string()).into(),
+            attrs.admission_or_first_trade_date.map(|d| d.to_string()).into(),
+            attrs.termination_date.map(|d| d.to_string()).into(),
+        ])
+    }
+
+    fn technical_attrs(&mut self, attrs: &TechnicalAttributes) -> i64 {
+        let period_id = attrs.publication_period.as_ref().map(|p| self.publication_period.push(vec![
+            p.from_date.to_string().into(),
+            p.to_date.map(|d| d.to_string()).into(),
+        ]));
+        self.technical_attrs.push(vec![
+            attrs.relevant_competent_authority.as_deref().into(),
+            period_id.into(),
+            attrs.relevant_trading_venue.as_ref().map(|m| m.as_str()).into(),
+        ])
+    }
+
+    fn interest_rate(&mut self, rate: &InterestRate) -> i64 {
+        let (fixed, floating, spread) = match rate {
+            InterestRate::Fixed(rate) => (Some(*rate), None, None),
+            InterestRate::Floating(rate, spread) => (None, Some(self.floating_rate(rate)), *spread),
+        };
+        self.interest_rate.push(vec![fixed.into(), floating.into(), spread.into()])
+    }
+
+    fn debt_attrs(&mut self, attrs: &DebtAttributes) -> i64 {
+        let ir = self.interest_rate(&attrs.interest_rate);
+        self.debt_attrs.push(vec![
+            attrs.total_issued_amount.into(),
+            attrs.maturity_date.map(|d| d.to_string()).into(),
+            attrs.nominal_currency.as_str().into(),
+            attrs.nominal_value_per_unit.into(),
+            ir.into(),
+            attrs.seniority.map(|s| s.to_string()).into(),
+        ])
+    }
+
+    fn underlying(&mut self, underlying: &DerivativeUnderlying) -> i64 {
+        let (single_id, basket_id) = match underlying {
+            DerivativeUnderlying::Single(single) => {
+                let (isin, index_id, lei) = match single {
+                    UnderlyingSingle::Isin(isin) => (Some(isin.as_str()), None, None),
+                    UnderlyingSingle::Index(index) => (None, Some(self.index(index)), None),
+                    UnderlyingSingle::Lei(lei) => (None, None, Some(lei.as_str())),
+                };
+                (Some(self.underlying_single.push(vec![isin.into(), index_id.into(), lei.into()])), None)
+            },
+            DerivativeUnderlying::Basket(basket) => {
+                let basket_id = self.basket.push(vec![]);
+                for isin in &basket.isin {
+                    self.basket_isin.push_row(vec![basket_id.into(), isin.as_str().into()]);
+                }
+                for lei in &basket.issuer_lei {
+                    self.basket_lei.push_row(vec![basket_id.into(), lei.as_str().into()]);
+                }
+                (None, Some(basket_id))
+            },
+        };
+        self.underlying.push(vec![single_id.into(), basket_id.into()])
+    }
+
+    fn asset_class_attrs(&mut self, attrs: &AssetClassSpecificAttributes) -> i64 {
+        let comm_attrs = attrs.commodity_attributes.as_ref().map(|a| {
+            let (product, subproduct, further_subproduct) = a.product.to_codes();
+            self.commodity_attrs.push(vec![
+                product.into(),
+                subproduct.into(),
+                further_subproduct.into(),
+                a.transaction_type.map(|t| t.to_string()).into(),
+                a.final_price_type.map(|t| t.to_string()).into(),
+            ])
+        });
+        let ir_attrs = attrs.ir_attributes.as_ref().map(|a| {
+            let ref_rate = self.floating_rate(&a.reference_rate);
+            let ir_1 = a.interest_rate_1.as_ref().map(|ir| self.interest_rate(ir));
+            let ir_2 = a.interest_rate_2.as_ref().map(|ir| self.interest_rate(ir));
+            self.ir_attrs.push(vec![
+                ref_rate.into(),
+                ir_1.into(),
+                a.notional_currency_2.as_ref().map(|c| c.as_str()).into(),
+                ir_2.into(),
+            ])
+        });
+        let fx_attrs = attrs.fx_attributes.as_ref().map(|a| self.fx_attrs.push(vec![
+            a.notional_currency_2.as_ref().map(|c| c.as_str()).into(),
+            a.fx_type.map(|t| t.to_string()).into(),
+        ]));
+        self.asset_class_attrs.push(vec![comm_attrs.into(), ir_attrs.into(), fx_attrs.into()])
+    }
+
+    fn derivative_attrs(&mut self, attrs: &DerivativeAttributes) -> i64 {
+        let underlying = attrs.underlying.as_ref().map(|u| self.underlying(u));
+        let strike_price = attrs.strike_price.as_ref().map(|p| self.strike_price(p));
+        let acsa = attrs.asset_class_specific_attributes.as_ref().map(|a| self.asset_class_attrs(a));
+        self.derivative_attrs.push(vec![
+            attrs.expiry_date.map(|d| d.to_string()).into(),
+            attrs.price_multiplier.into(),
+            underlying.into(),
+            attrs.option_type.map(|t| t.to_string()).into(),
+            strike_price.into(),
+            attrs.option_exercise_style.map(|s| s.to_string()).into(),
+            attrs.delivery_type.map(|t| t.to_string()).into(),
+            acsa.into(),
+        ])
+    }
+
+    fn push(&mut self, entry: &RefDataDbEntry) {
+        let ref_data = &entry.ref_data;
+        let tv_attrs = self.trading_venue_attrs(&ref_data.trading_venue_attrs);
+        let tech_attrs = ref_data.technical_attributes.as_ref().map(|a| self.technical_attrs(a));
+        let debt_attrs = ref_data.debt_attributes.as_ref().map(|a| self.debt_attrs(a));
+        let deriv_attrs = ref_data.derivative_attributes.as_ref().map(|a| self.derivative_attrs(a));
+        self.ref_data.push(vec![
+            ref_data.isin.as_str().into(),
+            ref_data.full_name.as_str().into(),
+            ref_data.cfi.as_str().into(),
+            ref_data.is_commodities_derivative.into(),
+            ref_data.issuer_lei.as_str().into(),
+            ref_data.fisn.as_str().into(),
+            tv_attrs.into(),
+            ref_data.notional_currency.as_str().into(),
+            tech_attrs.into(),
+            debt_attrs.into(),
+            deriv_attrs.into(),
+            ref_data.classify().to_string().into(),
+            entry.latest_record.into(),
+            entry.valid_from.to_string().into(),
+            entry.valid_to.map(|d| d.to_string()).into(),
+            entry.source.map(|s| s.source_file_id).into(),
+            entry.source.map(|s| s.record_type.to_string()).into(),
+        ]);
+        self.records += 1;
+    }
+}
+
+/// Loads [`RefDataDbEntry`] records into an SQLite database in batches. See the
+/// [module documentation](self) for details.
+///
+/// Like [`ToDb`](crate::sql::ToDb), the loader writes to a transaction provided by the caller,
+/// which should be committed after [`BulkLoader::finish`] is called. All of the records should be
+/// loaded in the same transaction. [`bulk_load`] does all of this (and sets the PRAGMAs given in
+/// the options).
+pub struct BulkLoader {
+    options: BulkLoadOptions,
+    batch: Batch,
+    /// The `CREATE INDEX` statements of the indexes dropped at the start of the load.
+    deferred_indexes: Vec<String>,
+    stats: LoadStats,
+    started: Instant,
+}
+
+impl BulkLoader {
+    /// Create a new loader, dropping the database's indexes if requested in `options`.
+    pub async fn begin(options: BulkLoadOptions, tx: &mut SqliteTransaction<'_>) -> Result<Self, SqlError> {
+        let started = Instant::now();
+        let mut deferred_indexes = Vec::new();
+        if options.defer_indexes {
+            let indexes = sqlx::query(
+                "SELECT name, sql FROM sqlite_master WHERE type = 'index' AND sql IS NOT NULL"
+            ).fetch_all(&mut **tx).await?;
+            for index in indexes {
+                let name: String = index.get("name");
+                tx.execute(format!("DROP INDEX {name}").as_str()).await?;
+                deferred_indexes.push(index.get("sql"));
+            }
+        }
+        let mut batch = Batch::new();
+        for table in batch.tables() {
+            table.init_id(tx).await?;
+        }
+        batch.load_shared_ids(tx).await?;
+        Ok(Self {
+            options,
+            batch,
+            deferred_indexes,
+            stats: LoadStats::default(),
+            started,
+        })
+    }
+
+    /// Add a record to the current batch, writing the batch to the database if it is full.
+    pub async fn add(&mut self, entry: &RefDataDbEntry, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+        self.batch.push(entry);
+        if self.batch.records >= self.options.batch_size {
+            self.flush(tx).await?;
+        }
+        Ok(())
+    }
+
+    /// Write the current batch to the database.
+    pub async fn flush(&mut self, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+        if self.batch.records == 0 {
+            return Ok(())
+        }
+        for table in self.batch.tables() {
+            self.stats.rows += table.flush(tx).await?;
+        }
+        self.stats.records += self.batch.records as u64;
+        self.stats.batches += 1;
+        self.batch.records = 0;
+        Ok(())
+    }
+
+    /// Write any remaining records and re-create deferred indexes.
+    pub async fn finish(mut self, tx: &mut SqliteTransaction<'_>) -> Result<LoadStats, SqlError> {
+        self.flush(tx).await?;
+        for sql in &self.deferred_indexes {
+            tx.execute(sql.as_str()).await?;
+        }
+        self.stats.elapsed = self.started.elapsed();
+        Ok(self.stats)
+    }
+}
+
+/// Load all of the given records in a single transaction using a [`BulkLoader`]. The PRAGMAs in
+/// `options` are set for the duration of the load, and restored whether or not it succeeds.
+pub async fn bulk_load(
+    conn: &mut SqliteConnection,
+    entries: impl IntoIterator<Item = RefDataDbEntry>,
+    options: BulkLoadOptions
+) -> Result<LoadStats, SqlError> {
+    let started = Instant::now();
+    let prev_pragmas = options.pragmas.apply(conn).await?;
+    let result = load_in_transaction(conn, entries, options).await;
+    // If the load failed, the transaction has been dropped, and is rolled back before the
+    // PRAGMAs are restored.
+    let restored = prev_pragmas.apply(conn).await;
+    let mut stats = result?;
+    restored?;
+    stats.elapsed = started.elapsed();
+    Ok(stats)
+}
+
+async fn load_in_transaction(
+    conn: &mut SqliteConnection,
+    entries: impl IntoIterator<Item = RefDataDbEntry>,
+    options: BulkLoadOptions
+) -> Result<LoadStats, SqlError> {
+    let mut tx = conn.begin().await?;
+    let mut loader = BulkLoader::begin(options, &mut tx).await?;
+    for entry in entries {
+        loader.add(&entry, &mut tx).await?;
+    }
+    let stats = loader.finish(&mut tx).await?;
+    tx.commit().await?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::sql::{bulk_load, init_db, BulkLoadOptions, JournalMode, Pragmas, RecordType, RefDataDbEntry, Synchronous, ToDb};
+    use crate::*;
+    use chrono::NaiveDate;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use sqlx::SqlitePool;
+    use std::env::temp_dir;
+    use std::path::PathBuf;
+
+    const DATE: NaiveDate = NaiveDate::from_ymd_opt(2025, 2, 1).expect("Bad test date");
+
+    async fn new_db(name: &str) -> (SqlitePool, PathBuf) {
+        let path = temp_dir().join(format!("firds-bulk-{name}-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await.unwrap();
+        init_db(&pool).await.unwrap();
+        (pool, path)
+    }
+
+    /// An in-memory database without any tables. The pool has a single connection, as each
+    /// connection to `sqlite::memory:` opens a separate database.
+    pub(crate) async fn empty_memory_pool() -> SqlitePool {
+        SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap()
+    }
+
+    /// An in-memory database with the latest schema.
+    pub(crate) async fn memory_pool() -> SqlitePool {
+        let pool = empty_memory_pool().await;
+        init_db(&pool).await.unwrap();
+        pool
+    }
+
+    fn euribor(months: i32) -> FloatingRate {
+        FloatingRate {
+            name: Some(IndexName::Code(IndexCode::Euribor)),
+            term: Some(Term { number: months, unit: TermUnit::Month }),
+        }
+    }
+
+    /// Generate `n` records, cycling through a floating rate bond, an interest rate swap on an
+    /// index and an option on a basket.
+    pub(crate) fn records(n: usize) -> Vec<RefDataDbEntry> {
+        (0..n).map(|i| {
+            let builder = ReferenceData::builder()
+                .isin(Isin::new_unchecked(format!("XS{i:010}")))
+                .full_name(format!("Instrument {i}"))
+                .issuer_lei(Lei::new_unchecked("5493001KJTIIGC8Y1R12"))
+                .trading_venue(Mic::new_unchecked("XETR"))
+                .notional_currency("EUR".parse().unwrap())
+                .technical_attributes(TechnicalAttributes {
+                    relevant_competent_authority: Some("DE".to_string()),
+                    publication_period: Some(PublicationPeriod { from_date: DATE, to_date: None }),
+                    relevant_trading_venue: None,
+                });
+            let builder = match i % 3 {
+                0 => builder.cfi("DBFTFB".parse().unwrap()).debt_attributes(DebtAttributes {
+                    total_issued_amount: 1_000_000.0,
+                    maturity_date: NaiveDate::from_ymd_opt(2030, 2, 15),
+                    nominal_currency: "EUR".parse().unwrap(),
+                    nominal_value_per_unit: 1000.0,
+                    interest_rate: InterestRate::Floating(euribor(6), Some(50)),
+                    seniority: None,
+                }),
+                1 => builder.cfi("SRCCSP".parse().unwrap()).derivative_attributes(DerivativeAttributes {
+                    expiry_date: NaiveDate::from_ymd_opt(2035, 2, 1),
+                    underlying: Some(DerivativeUnderlying::Single(UnderlyingSingle::Index(Index {
+                        isin: None,
+                        name: euribor(3),
+                    }))),
+                    asset_class_specific_attributes: Some(AssetClassSpecificAttributes {
+                        ir_attributes: Some(InterestRateDerivativeAttributes {
+                            reference_rate: euribor(3),
+                            interest_rate_1: Some(InterestRate::Fixed(2.5)),
+                            notional_currency_2: None,
+                            interest_rate_2: Some(InterestRate::Floating(euribor(3), None)),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                _ => builder.cfi("OCASPS".parse().unwrap()).derivative_attributes(DerivativeAttributes {
+                    expiry_date: NaiveDate::from_ymd_opt(2026, 3, 20),
+                    price_multiplier: Some(100.0),
+                    underlying: Some(DerivativeUnderlying::Basket(UnderlyingBasket {
+                        isin: vec![Isin::new_unchecked("US0378331005"), Isin::new_unchecked("GB0002634946")],
+                        issuer_lei: vec![],
+                    })),
+                    option_type: Some(OptionType::Call),
+                    strike_price: Some(StrikePrice {
+                        price_type: StrikePriceType::MonetaryValue,
+                        price: Some(150.0),
+                        pending: false,
+                        currency: Some("USD".parse().unwrap()),
+                    }),
+                    option_exercise_style: Some(OptionExerciseStyle::European),
+                    delivery_type: Some(DeliveryType::Cash),
+                    ..Default::default()
+                }),
+            };
+            RefDataDbEntry::new(builder.build_unchecked().unwrap(), true, DATE, None)
+        }).collect()
+    }
+
+    /// A denormalised dump of the database which doesn't depend on the IDs allocated to rows.
+    async fn dump(pool: &SqlitePool) -> Vec<(String, String, Option<String>, Option<i64>, Option<f64>, i64)> {
+        sqlx::query_as(
+            r#"
+                SELECT ReferenceData.isin, ReferenceData.instrument_class, FloatingRate.name, Term.number,
+                    StrikePrice.price,
+                    (SELECT COUNT(*) FROM UnderlyingBasketIsin WHERE basket_id = DerivativeUnderlying.basket_id)
+                FROM ReferenceData
+                LEFT JOIN DebtAttributes ON ReferenceData.debt_attributes_id = DebtAttributes.id
+                LEFT JOIN DerivativeAttributes ON ReferenceData.derivative_attributes_id = DerivativeAttributes.id
+                LEFT JOIN AssetClassSpecificAttributes
+                    ON DerivativeAttributes.asset_class_specific_attributes_id = AssetClassSpecificAttributes.id
+                LEFT JOIN InterestRateDerivativeAttributes
+                    ON AssetClassSpecificAttributes.ir_attributes_id = InterestRateDerivativeAttributes.id
+                LEFT JOIN InterestRate ON InterestRate.id = COALESCE(
+                    DebtAttributes.interest_rate_id, InterestRateDerivativeAttributes.interest_rate_2_id
+                )
+                LEFT JOIN FloatingRate ON InterestRate.floating_rate_id = FloatingRate.id
+                LEFT JOIN Term ON FloatingRate.term_id = Term.id
+                LEFT JOIN StrikePrice ON DerivativeAttributes.strike_price_id = StrikePrice.id
+                LEFT JOIN DerivativeUnderlying ON DerivativeAttributes.underlying_id = DerivativeUnderlying.id
+                ORDER BY ReferenceData.isin
+            "#
+        ).fetch_all(pool).await.unwrap()
+    }
+
+    async fn table_counts(pool: &SqlitePool) -> Vec<i64> {
+        let mut counts = vec![];
+        for table in ["Term", "FloatingRate", "FirdsIndex", "InterestRate", "UnderlyingBasketIsin", "ReferenceData"] {
+            counts.push(sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}")).fetch_one(pool).await.unwrap());
+        }
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_bulk_load_matches_to_db() {
+        const N: usize = 3000;
+
+        let (to_db_pool, to_db_path) = new_db("to-db").await;
+        let mut tx = to_db_pool.begin().await.unwrap();
+        for entry in records(N) {
+            entry.to_db(&mut tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        // Load in two parts to check that IDs carry on from the rows already in the database.
+        let (bulk_pool, bulk_path) = new_db("bulk").await;
+        let mut conn = bulk_pool.acquire().await.unwrap();
+        let mut all = records(N);
+        let second = all.split_off(N / 2);
+        let options = BulkLoadOptions { batch_size: 1000, ..BulkLoadOptions::fast_import() };
+        let first_stats = bulk_load(&mut conn, all, options.clone()).await.unwrap();
+        let stats = bulk_load(&mut conn, second, options).await.unwrap();
+        assert_eq!(first_stats.records + stats.records, N as u64);
+        assert_eq!(stats.batches, 2);
+
+        // The PRAGMAs should have been restored and the indexes re-created.
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(journal_mode.parse::<JournalMode>().unwrap(), JournalMode::Delete);
+        let indexes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_refdata_isin'")
+            .fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(indexes, 1);
+        conn.close_on_drop();
+        drop(conn);
+
+        // Both should share a single row for each distinct term, rate and index.
+        assert_eq!(table_counts(&to_db_pool).await[..3], [2, 2, 1]);
+        assert_eq!(table_counts(&bulk_pool).await, table_counts(&to_db_pool).await);
+        assert_eq!(dump(&bulk_pool).await, dump(&to_db_pool).await);
+
+        to_db_pool.close().await;
+        bulk_pool.close().await;
+        std::fs::remove_file(to_db_path).unwrap();
+        std::fs::remove_file(bulk_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bulk_load_failure_restores_pragmas() {
+        let (pool, path) = new_db("failure").await;
+        let mut conn = pool.acquire().await.unwrap();
+        // Records which refer to a source file that doesn't exist violate a foreign key constraint.
+        let entries = records(3).into_iter().map(|e| e.with_source(999, RecordType::Full));
+        let options = BulkLoadOptions {
+            pragmas: Pragmas { journal_mode: Some(JournalMode::Memory), synchronous: Some(Synchronous::Off) },
+            ..BulkLoadOptions::default()
+        };
+        assert!(bulk_load(&mut conn, entries, options).await.is_err());
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(journal_mode.parse::<JournalMode>().unwrap(), JournalMode::Delete);
+        let synchronous: i64 = sqlx::query_scalar("PRAGMA synchronous").fetch_one(&mut *conn).await.unwrap();
+        assert_ne!(synchronous, 0);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ReferenceData").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(count, 0);
+        conn.close_on_drop();
+        drop(conn);
+
+        pool.close().await;
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+//! Checking (and repairing) the consistency of the record history in an SQLite database.
+//!
+//! The records for each ISIN and MIC should form a chain of non-overlapping validity periods, of
+//! which only the last may be open-ended (`valid_to` is `NULL`). The last record is the *latest*
+//! record (`latest_record = true`) unless it has been cancelled, in which case it has a `valid_to`
+//! date and there is no latest record. Bugs in applying delta files can break these invariants, so
+//! [`check_db`] scans the database for violations and [`repair_db`] fixes those that can be fixed
+//! unambiguously.
+
+use crate::sql::error::SqlError;
+use sqlx::SqliteTransaction;
+use std::fmt::{Display, Formatter};
+
+/// A violation of the invariants of the record history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// More than one record for the same ISIN and MIC is marked as the latest record. Contains
+    /// the IDs of the records.
+    MultipleLatest { isin: String, mic: String, ids: Vec<i64> },
+    /// A record ceased to be valid before it became valid.
+    InvertedValidity { id: i64, valid_from: String, valid_to: String },
+    /// A record is marked as the latest record but has a `valid_to` date.
+    ClosedLatest { id: i64 },
+    /// A record has no `valid_to` date but is not marked as the latest record.
+    OpenNotLatest { id: i64 },
+    /// The validity periods of two consecutive records for the same ISIN and MIC overlap, ie, the
+    /// first was still valid when the second became valid.
+    Overlap { isin: String, mic: String, first_id: i64, second_id: i64 },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MultipleLatest { isin, mic, ids } => write!(
+                f,
+                "{isin} on {mic} has {} latest records: {}",
+                ids.len(),
+                ids.iter().map(i64::to_string).collect::<Vec<String>>().join(", ")
+            ),
+            Self::InvertedValidity { id, valid_from, valid_to } => write!(
+                f, "Record {id} is valid from {valid_from} to {valid_to}"
+            ),
+            Self::ClosedLatest { id } => write!(f, "Record {id} is the latest record but has a valid_to date"),
+            Self::OpenNotLatest { id } => write!(f, "Record {id} has no valid_to date but is not the latest record"),
+            Self::Overlap { isin, mic, first_id, second_id } => write!(
+                f, "{isin} on {mic}: record {first_id} overlaps with record {second_id}"
+            ),
+        }
+    }
+}
+
+/// Creates a temporary table of the records for each ISIN and MIC in the order they became valid,
+/// with the start of the validity period of the next record (if any).
+const CREATE_CHAIN: &str = r#"
+    CREATE TEMP TABLE RecordChain AS
+    SELECT ReferenceData.id, ReferenceData.isin, TradingVenueAttributes.trading_venue AS mic,
+        ReferenceData.latest_record, ReferenceData.valid_from, ReferenceData.valid_to,
+        LEAD(ReferenceData.id) OVER chain AS next_id,
+        LEAD(ReferenceData.valid_from) OVER chain AS next_valid_from
+    FROM ReferenceData
+    JOIN TradingVenueAttributes ON ReferenceData.trading_venue_attrs_id = TradingVenueAttributes.id
+    WINDOW chain AS (
+        PARTITION BY ReferenceData.isin, TradingVenueAttributes.trading_venue
+        ORDER BY ReferenceData.valid_from, ReferenceData.id
+    )
+"#;
+
+/// Scan the database for violations of the invariants of the record history, returning all of the
+/// violations found.
+pub async fn check_db(tx: &mut SqliteTransaction<'_>) -> Result<Vec<Violation>, SqlError> {
+    let mut violations = vec![];
+    sqlx::query(CREATE_CHAIN).execute(&mut **tx).await?;
+
+    let multiple_latest: Vec<(String, String, String)> = sqlx::query_as(
+        r#"
+            SELECT isin, mic, group_concat(id) FROM RecordChain
+            WHERE latest_record
+            GROUP BY isin, mic
+            HAVING COUNT(*) > 1
+            ORDER BY isin, mic
+        "#
+    ).fetch_all(&mut **tx).await?;
+    violations.extend(multiple_latest.into_iter().map(|(isin, mic, ids)| Violation::MultipleLatest {
+        isin,
+        mic,
+        ids: ids.split(',').filter_map(|id| id.parse().ok()).collect(),
+    }));
+
+    let inverted: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id, valid_from, valid_to FROM RecordChain WHERE valid_to < valid_from ORDER BY id"
+    ).fetch_all(&mut **tx).await?;
+    violations.extend(inverted.into_iter().map(|(id, valid_from, valid_to)| Violation::InvertedValidity {
+        id,
+        valid_from,
+        valid_to,
+    }));
+
+    let closed_latest: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM RecordChain WHERE latest_record AND valid_to IS NOT NULL ORDER BY id"
+    ).fetch_all(&mut **tx).await?;
+    violations.extend(closed_latest.into_iter().map(|id| Violation::ClosedLatest { id }));
+
+    let open_not_latest: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM RecordChain WHERE NOT latest_record AND valid_to IS NULL ORDER BY id"
+    ).fetch_all(&mut **tx).await?;
+    violations.extend(open_not_latest.into_iter().map(|id| Violation::OpenNotLatest { id }));
+
+    let overlaps: Vec<(String, String, i64, i64)> = sqlx::query_as(
+        r#"
+            SELECT isin, mic, id, next_id FROM RecordChain
+            WHERE next_id IS NOT NULL AND (valid_to IS NULL OR valid_to >= next_valid_from)
+            ORDER BY isin, mic, valid_from, id
+        "#
+    ).fetch_all(&mut **tx).await?;
+    violations.extend(overlaps.into_iter().map(|(isin, mic, first_id, second_id)| Violation::Overlap {
+        isin,
+        mic,
+        first_id,
+        second_id,
+    }));
+
+    sqlx::query("DROP TABLE RecordChain").execute(&mut **tx).await?;
+    Ok(violations)
+}
+
+/// Repair the record history where this can be done unambiguously, returning the number of records
+/// updated.
+///
+/// For each ISIN and MIC, the records are ordered by `valid_from` date. Every record except the
+/// last is marked as not being the latest record, and its `valid_to` date is set to the day before
+/// the next record became valid if it was open-ended or overlapped with the next record. The last
+/// record is marked as the latest record if (and only if) it has no `valid_to` date.
+///
+/// ISINs and MICs which have records with inverted validity periods, or more than one record
+/// becoming valid on the same date, are left unchanged, as the correct order of their records
+/// cannot be determined. These will still be reported by [`check_db`].
+pub async fn repair_db(tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+    sqlx::query(CREATE_CHAIN).execute(&mut **tx).await?;
+    sqlx::query(
+        r#"
+            DELETE FROM RecordChain WHERE (isin, mic) IN (
+                SELECT isin, mic FROM RecordChain
+                WHERE valid_to < valid_from OR valid_from = next_valid_from
+            )
+        "#
+    ).execute(&mut **tx).await?;
+    let mut updated = sqlx::query(
+        r#"
+            UPDATE ReferenceData
+            SET latest_record = false,
+                valid_to = CASE
+                    WHEN RecordChain.valid_to IS NULL OR RecordChain.valid_to >= RecordChain.next_valid_from
+                        THEN date(RecordChain.next_valid_from, '-1 day')
+                    ELSE RecordChain.valid_to
+                END
+            FROM RecordChain
+            WHERE ReferenceData.id = RecordChain.id AND RecordChain.next_id IS NOT NULL
+            AND (
+                RecordChain.latest_record
+                OR RecordChain.valid_to IS NULL
+                OR RecordChain.valid_to >= RecordChain.next_valid_from
+            )
+        "#
+    ).execute(&mut **tx).await?.rows_affected();
+    updated += sqlx::query(
+        r#"
+            UPDATE ReferenceData
+            SET latest_record = (RecordChain.valid_to IS NULL)
+            FROM RecordChain
+            WHERE ReferenceData.id = RecordChain.id AND RecordChain.next_id IS NULL
+            AND RecordChain.latest_record != (RecordChain.valid_to IS NULL)
+        "#
+    ).execute(&mut **tx).await?.rows_affected();
+    sqlx::query("DROP TABLE RecordChain").execute(&mut **tx).await?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::memory_pool;
+    use crate::sql::consistency::{check_db, repair_db, Violation};
+
+    #[tokio::test]
+    async fn test_check_and_repair() {
+        let pool = memory_pool().await;
+        // Records for four instruments:
+        // - A (1, 2, 3): a consistent history ending in a cancellation.
+        // - B (4, 5): the first version was never closed, so both are marked as latest.
+        // - C (6, 7): the first version overlaps the second, which is not marked as latest.
+        // - D (8, 9): two versions valid from the same date, one of which is inverted.
+        sqlx::raw_sql(
+            r#"
+                INSERT INTO TradingVenueAttributes (id, trading_venue, requested_admission) VALUES (1, 'XLON', 0);
+                INSERT INTO ReferenceData (id, isin, full_name, cfi, is_commodities_derivative, issuer_lei, fisn,
+                                           trading_venue_attrs_id, notional_currency, instrument_class,
+                                           latest_record, valid_from, valid_to)
+                VALUES (1, 'A', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-01-01', '2025-01-31'),
+                       (2, 'A', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-02-01', '2025-02-28'),
+                       (3, 'A', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-03-01', '2025-03-31'),
+                       (4, 'B', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 1, '2025-01-01', NULL),
+                       (5, 'B', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 1, '2025-02-01', NULL),
+                       (6, 'C', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-01-01', '2025-02-10'),
+                       (7, 'C', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-02-01', NULL),
+                       (8, 'D', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 1, '2025-01-01', NULL),
+                       (9, 'D', '', 'ESVUFR', 0, '', '', 1, 'GBP', 'EQTY', 0, '2025-01-01', '2024-12-31');
+            "#
+        ).execute(&pool).await.unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        let violations = check_db(&mut tx).await.unwrap();
+        assert_eq!(violations, vec![
+            Violation::MultipleLatest { isin: "B".to_string(), mic: "XLON".to_string(), ids: vec![4, 5] },
+            Violation::InvertedValidity {
+                id: 9,
+                valid_from: "2025-01-01".to_string(),
+                valid_to: "2024-12-31".to_string()
+            },
+            Violation::OpenNotLatest { id: 7 },
+            Violation::Overlap { isin: "B".to_string(), mic: "XLON".to_string(), first_id: 4, second_id: 5 },
+            Violation::Overlap { isin: "C".to_string(), mic: "XLON".to_string(), first_id: 6, second_id: 7 },
+            Violation::Overlap { isin: "D".to_string(), mic: "XLON".to_string(), first_id: 8, second_id: 9 },
+        ]);
+
+        assert_eq!(repair_db(&mut tx).await.unwrap(), 3);
+        let records: Vec<(i64, bool, Option<String>)> = sqlx::query_as(
+            "SELECT id, latest_record, valid_to FROM ReferenceData WHERE id BETWEEN 4 AND 7 ORDER BY id"
+        ).fetch_all(&mut *tx).await.unwrap();
+        assert_eq!(records, vec![
+            (4, false, Some("2025-01-31".to_string())),
+            (5, true, None),
+            (6, false, Some("2025-01-31".to_string())),
+            (7, true, None),
+        ]);
+        // Only D's violations remain, as they couldn't be repaired.
+        let violations = check_db(&mut tx).await.unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(violations[0], Violation::InvertedValidity { id: 9, .. }));
+        assert!(matches!(violations[1], Violation::Overlap { first_id: 8, second_id: 9, .. }));
+    }
+}
@@ -1,13 +1,58 @@
+use std::fmt::{Display, Formatter};
+
 #[derive(Debug)]
 pub enum SqlError {
     /// Received an error from the [`sqlx`] crate.
     Sqlx(sqlx::Error),
     /// [`ReferenceData`] struct is missing `technical_attributes.publication_period` field.
+    ///
+    /// [`ReferenceData`]: crate::ReferenceData
     MissingPublicationPeriod,
+    /// The database's schema version is newer than the latest version supported by this crate
+    /// ([`SCHEMA_VERSION`](crate::sql::SCHEMA_VERSION)).
+    UnsupportedSchemaVersion(i64),
+    /// A value read from the database could not be parsed.
+    BadValue { column: &'static str, value: String },
+    /// Error writing data exported from the database.
+    Io(std::io::Error),
+    /// Full-text search has not been enabled for the database (see
+    /// [`enable_search`](crate::sql::enable_search)).
+    SearchNotEnabled,
+    /// A file name does not follow the naming convention of FIRDS files (see
+    /// [`SourceFile::from_file_name`](crate::sql::SourceFile::from_file_name)).
+    BadFileName(String),
+}
+
+impl Display for SqlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sqlx(e) => write!(f, "Database error: {e}"),
+            Self::MissingPublicationPeriod => write!(f, "Reference data has no publication period"),
+            Self::UnsupportedSchemaVersion(v) => write!(f, "Unsupported database schema version: {v}"),
+            Self::BadValue { column, value } => write!(f, "Invalid value in {column}: {value:?}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::SearchNotEnabled => write!(f, "Full-text search is not enabled for the database"),
+            Self::BadFileName(name) => write!(f, "Not a FIRDS file name: {name}"),
+        }
+    }
 }
 
+impl std::error::Error for SqlError {}
+
 impl From<sqlx::Error> for SqlError {
     fn from(e: sqlx::Error) -> Self {
         SqlError::Sqlx(e)
     }
-}
\ No newline at end of file
+}
+
+impl From<std::io::Error> for SqlError {
+    fn from(e: std::io::Error) -> Self {
+        SqlError::Io(e)
+    }
+}
+
+impl From<csv::Error> for SqlError {
+    fn from(e: csv::Error) -> Self {
+        SqlError::Io(e.into())
+    }
+}
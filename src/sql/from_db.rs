@@ -0,0 +1,449 @@
+//! Reading reference data back from an SQLite database.
+
+use crate::sql::error::SqlError;
+use crate::sql::provenance::{RecordSource, SourceFile};
+use crate::sql::to_db::RefDataDbEntry;
+use crate::*;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use sqlx::SqliteConnection;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// Structs implementing this trait can be read from an SQLite database.
+#[allow(async_fn_in_trait)]
+pub trait FromDb where Self: Sized {
+
+    /// Read the row with the given ID, along with any rows it references, from the database.
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError>;
+}
+
+/// Read the row with the given ID (if any) from the database.
+async fn from_db_option<T: FromDb>(id: Option<i64>, conn: &mut SqliteConnection) -> Result<Option<T>, SqlError> {
+    if let Some(id) = id {
+        Ok(Some(T::from_db(id, conn).await?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse a value stored as text in the given column.
+fn parse<T: FromStr>(column: &'static str, value: &str) -> Result<T, SqlError> where T::Err: Debug {
+    value.parse().map_err(|_| SqlError::BadValue { column, value: value.to_string() })
+}
+
+fn parse_option<T: FromStr>(column: &'static str, value: Option<String>) -> Result<Option<T>, SqlError>
+where T::Err: Debug {
+    value.map(|v| parse(column, &v)).transpose()
+}
+
+fn parse_date(column: &'static str, value: Option<String>) -> Result<Option<NaiveDate>, SqlError> {
+    parse_option(column, value)
+}
+
+/// Parse a date and time stored using the [`Display`](std::fmt::Display) implementation of
+/// [`DateTime<Utc>`], eg, "2025-02-01 00:00:00 UTC".
+fn parse_datetime(column: &'static str, value: Option<String>) -> Result<Option<DateTime<Utc>>, SqlError> {
+    value.map(|v| {
+        NaiveDateTime::parse_from_str(v.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M:%S%.f")
+            .map(|dt| dt.and_utc())
+            .map_err(|_| SqlError::BadValue { column, value: v })
+    }).transpose()
+}
+
+impl FromDb for Term {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (number, unit): (i32, String) = sqlx::query_as("SELECT number, unit FROM Term WHERE id = ?")
+            .bind(id)
+            .fetch_one(conn)
+            .await?;
+        Ok(Self {
+            number,
+            unit: parse("Term.unit", &unit)?,
+        })
+    }
+}
+
+impl FromDb for StrikePrice {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (price_type, price, pending, currency): (String, Option<f64>, bool, Option<String>) = sqlx::query_as(
+            "SELECT price_type, price, pending, currency FROM StrikePrice WHERE id = ?"
+        ).bind(id).fetch_one(conn).await?;
+        Ok(Self {
+            price_type: parse("StrikePrice.price_type", &price_type)?,
+            price,
+            pending,
+            currency: parse_option("StrikePrice.currency", currency)?,
+        })
+    }
+}
+
+impl FromDb for FloatingRate {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (name, term_id): (Option<String>, Option<i64>) = sqlx::query_as(
+            "SELECT name, term_id FROM FloatingRate WHERE id = ?"
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            name: parse_option("FloatingRate.name", name)?,
+            term: from_db_option(term_id, conn).await?,
+        })
+    }
+}
+
+impl FromDb for Index {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (isin, name_id): (Option<String>, i64) = sqlx::query_as(
+            "SELECT isin, name_id FROM FirdsIndex WHERE id = ?"
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            isin: isin.map(Isin::new_unchecked),
+            name: FloatingRate::from_db(name_id, conn).await?,
+        })
+    }
+}
+
+impl FromDb for TradingVenueAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (
+            trading_venue,
+            requested_admission,
+            approval_date,
+            request_date,
+            admission_or_first_trade_date,
+            termination_date
+        ): (String, bool, Option<String>, Option<String>, Option<String>, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT trading_venue, requested_admission, approval_date, request_date,
+                    admission_or_first_trade_date, termination_date
+                FROM TradingVenueAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(conn).await?;
+        Ok(Self {
+            trading_venue: Mic::new_unchecked(trading_venue),
+            requested_admission,
+            approval_date: parse_datetime("TradingVenueAttributes.approval_date", approval_date)?,
+            request_date: parse_datetime("TradingVenueAttributes.request_date", request_date)?,
+            admission_or_first_trade_date: parse_datetime(
+                "TradingVenueAttributes.admission_or_first_trade_date",
+                admission_or_first_trade_date
+            )?,
+            termination_date: parse_datetime("TradingVenueAttributes.termination_date", termination_date)?,
+        })
+    }
+}
+
+impl FromDb for InterestRate {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (fixed, floating_rate_id, spread): (Option<f64>, Option<i64>, Option<i32>) = sqlx::query_as(
+            "SELECT fixed, floating_rate_id, spread FROM InterestRate WHERE id = ?"
+        ).bind(id).fetch_one(&mut *conn).await?;
+        match (fixed, floating_rate_id) {
+            (Some(rate), _) => Ok(Self::Fixed(rate)),
+            (None, Some(rate_id)) => Ok(Self::Floating(FloatingRate::from_db(rate_id, conn).await?, spread)),
+            (None, None) => Err(SqlError::BadValue {
+                column: "InterestRate.floating_rate_id",
+                value: "NULL".to_string()
+            })
+        }
+    }
+}
+
+impl FromDb for PublicationPeriod {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (from_date, to_date): (String, Option<String>) = sqlx::query_as(
+            "SELECT from_date, to_date FROM PublicationPeriod WHERE id = ?"
+        ).bind(id).fetch_one(conn).await?;
+        Ok(Self {
+            from_date: parse("PublicationPeriod.from_date", &from_date)?,
+            to_date: parse_date("PublicationPeriod.to_date", to_date)?,
+        })
+    }
+}
+
+impl FromDb for TechnicalAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (relevant_competent_authority, publication_period_id, relevant_trading_venue):
+            (Option<String>, Option<i64>, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT relevant_competent_authority, publication_period_id, relevant_trading_venue
+                FROM TechnicalAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            relevant_competent_authority,
+            publication_period: from_db_option(publication_period_id, conn).await?,
+            relevant_trading_venue: relevant_trading_venue.map(Mic::new_unchecked),
+        })
+    }
+}
+
+impl FromDb for DebtAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (
+            total_issued_amount,
+            maturity_date,
+            nominal_currency,
+            nominal_value_per_unit,
+            interest_rate_id,
+            seniority
+        ): (f64, Option<String>, String, f64, i64, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT total_issued_amount, maturity_date, nominal_currency, nominal_value_per_unit,
+                    interest_rate_id, seniority
+                FROM DebtAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            total_issued_amount,
+            maturity_date: parse_date("DebtAttributes.maturity_date", maturity_date)?,
+            nominal_currency: parse("DebtAttributes.nominal_currency", &nominal_currency)?,
+            nominal_value_per_unit,
+            interest_rate: InterestRate::from_db(interest_rate_id, conn).await?,
+            seniority: parse_option("DebtAttributes.seniority", seniority)?,
+        })
+    }
+}
+
+impl FromDb for CommodityDerivativeAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (product, subproduct, further_subproduct, transaction_type, final_price_type):
+            (String, Option<String>, Option<String>, Option<String>, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT product, subproduct, further_subproduct, transaction_type, final_price_type
+                FROM CommodityDerivativeAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(conn).await?;
+        let product = BaseProduct::try_from_codes(&product, subproduct.as_deref(), further_subproduct.as_deref())
+            .map_err(|_| SqlError::BadValue {
+                column: "CommodityDerivativeAttributes.product",
+                value: [Some(product), subproduct, further_subproduct].into_iter().flatten()
+                    .collect::<Vec<String>>()
+                    .join("/")
+            })?;
+        Ok(Self {
+            product,
+            transaction_type: parse_option("CommodityDerivativeAttributes.transaction_type", transaction_type)?,
+            final_price_type: parse_option("CommodityDerivativeAttributes.final_price_type", final_price_type)?,
+        })
+    }
+}
+
+impl FromDb for InterestRateDerivativeAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (reference_rate_id, interest_rate_1_id, notional_currency_2, interest_rate_2_id):
+            (i64, Option<i64>, Option<String>, Option<i64>) = sqlx::query_as(
+            r#"
+                SELECT reference_rate_id, interest_rate_1_id, notional_currency_2, interest_rate_2_id
+                FROM InterestRateDerivativeAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            reference_rate: FloatingRate::from_db(reference_rate_id, conn).await?,
+            interest_rate_1: from_db_option(interest_rate_1_id, conn).await?,
+            notional_currency_2: parse_option(
+                "InterestRateDerivativeAttributes.notional_currency_2",
+                notional_currency_2
+            )?,
+            interest_rate_2: from_db_option(interest_rate_2_id, conn).await?,
+        })
+    }
+}
+
+impl FromDb for FxDerivativeAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (notional_currency_2, fx_type): (Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT notional_currency_2, fx_type FROM FxDerivativeAttributes WHERE id = ?"
+        ).bind(id).fetch_one(conn).await?;
+        Ok(Self {
+            notional_currency_2: parse_option("FxDerivativeAttributes.notional_currency_2", notional_currency_2)?,
+            fx_type: parse_option("FxDerivativeAttributes.fx_type", fx_type)?,
+        })
+    }
+}
+
+impl FromDb for UnderlyingSingle {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (isin, index_id, lei): (Option<String>, Option<i64>, Option<String>) = sqlx::query_as(
+            "SELECT isin, index_id, lei FROM UnderlyingSingle WHERE id = ?"
+        ).bind(id).fetch_one(&mut *conn).await?;
+        match (isin, index_id, lei) {
+            (Some(isin), _, _) => Ok(Self::Isin(Isin::new_unchecked(isin))),
+            (None, Some(index_id), _) => Ok(Self::Index(Index::from_db(index_id, conn).await?)),
+            (None, None, Some(lei)) => Ok(Self::Lei(Lei::new_unchecked(lei))),
+            (None, None, None) => Err(SqlError::BadValue {
+                column: "UnderlyingSingle.isin",
+                value: "NULL".to_string()
+            })
+        }
+    }
+}
+
+impl FromDb for UnderlyingBasket {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let isin: Vec<String> = sqlx::query_scalar(
+            "SELECT isin FROM UnderlyingBasketIsin WHERE basket_id = ? ORDER BY rowid"
+        ).bind(id).fetch_all(&mut *conn).await?;
+        let issuer_lei: Vec<String> = sqlx::query_scalar(
+            "SELECT issuer_lei FROM UnderlyingBasketIssuerLei WHERE basket_id = ? ORDER BY rowid"
+        ).bind(id).fetch_all(conn).await?;
+        Ok(Self {
+            isin: isin.into_iter().map(Isin::new_unchecked).collect(),
+            issuer_lei: issuer_lei.into_iter().map(Lei::new_unchecked).collect(),
+        })
+    }
+}
+
+impl FromDb for DerivativeUnderlying {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (single_id, basket_id): (Option<i64>, Option<i64>) = sqlx::query_as(
+            "SELECT single_id, basket_id FROM DerivativeUnderlying WHERE id = ?"
+        ).bind(id).fetch_one(&mut *conn).await?;
+        match (single_id, basket_id) {
+            (Some(single_id), _) => Ok(Self::Single(UnderlyingSingle::from_db(single_id, conn).await?)),
+            (None, Some(basket_id)) => Ok(Self::Basket(UnderlyingBasket::from_db(basket_id, conn).await?)),
+            (None, None) => Err(SqlError::BadValue {
+                column: "DerivativeUnderlying.single_id",
+                value: "NULL".to_string()
+            })
+        }
+    }
+}
+
+impl FromDb for AssetClassSpecificAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        let (commodity_attributes_id, ir_attributes_id, fx_attributes_id):
+            (Option<i64>, Option<i64>, Option<i64>) = sqlx::query_as(
+            r#"
+                SELECT commodity_attributes_id, ir_attributes_id, fx_attributes_id
+                FROM AssetClassSpecificAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            commodity_attributes: from_db_option(commodity_attributes_id, conn).await?,
+            ir_attributes: from_db_option(ir_attributes_id, conn).await?,
+            fx_attributes: from_db_option(fx_attributes_id, conn).await?,
+        })
+    }
+}
+
+impl FromDb for DerivativeAttributes {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (
+            expiry_date,
+            price_multiplier,
+            underlying_id,
+            option_type,
+            strike_price_id,
+            option_exercise_style,
+            delivery_type,
+            asset_class_specific_attributes_id
+        ): (
+            Option<String>, Option<f64>, Option<i64>, Option<String>, Option<i64>, Option<String>,
+            Option<String>, Option<i64>
+        ) = sqlx::query_as(
+            r#"
+                SELECT expiry_date, price_multiplier, underlying_id, option_type, strike_price_id,
+                    option_exercise_style, delivery_type, asset_class_specific_attributes_id
+                FROM DerivativeAttributes WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        Ok(Self {
+            expiry_date: parse_date("DerivativeAttributes.expiry_date", expiry_date)?,
+            price_multiplier,
+            underlying: from_db_option(underlying_id, conn).await?,
+            option_type: parse_option("DerivativeAttributes.option_type", option_type)?,
+            strike_price: from_db_option(strike_price_id, conn).await?,
+            option_exercise_style: parse_option("DerivativeAttributes.option_exercise_style", option_exercise_style)?,
+            delivery_type: parse_option("DerivativeAttributes.delivery_type", delivery_type)?,
+            asset_class_specific_attributes: from_db_option(asset_class_specific_attributes_id, conn).await?,
+        })
+    }
+}
+
+impl FromDb for SourceFile {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (source, file_type, file_name, file_id, download_link, timestamp, checksum): (
+            String, String, String, Option<String>, Option<String>, Option<String>, Option<String>
+        ) = sqlx::query_as(
+            r#"
+                SELECT source, file_type, file_name, file_id, download_link, publication_timestamp,
+                    checksum
+                FROM SourceFile WHERE id = ?
+            "#
+        ).bind(id).fetch_one(conn).await?;
+        Ok(Self {
+            source,
+            file_type,
+            file_name,
+            file_id,
+            download_link,
+            timestamp: parse_datetime("SourceFile.publication_timestamp", timestamp)?,
+            checksum,
+        })
+    }
+}
+
+impl FromDb for RefDataDbEntry {
+    async fn from_db(id: i64, conn: &mut SqliteConnection) -> Result<Self, SqlError> {
+        #[allow(clippy::type_complexity)]
+        let (
+            isin,
+            full_name,
+            cfi,
+            is_commodities_derivative,
+            issuer_lei,
+            fisn,
+            trading_venue_attrs_id,
+            notional_currency,
+            technical_attributes_id,
+            debt_attributes_id,
+            derivative_attributes_id,
+            latest_record,
+            valid_from,
+            valid_to,
+            source_file_id,
+            record_type
+        ): (
+            String, String, String, bool, String, String, i64, String, Option<i64>, Option<i64>,
+            Option<i64>, bool, String, Option<String>, Option<i64>, Option<String>
+        ) = sqlx::query_as(
+            r#"
+                SELECT isin, full_name, cfi, is_commodities_derivative, issuer_lei, fisn,
+                    trading_venue_attrs_id, notional_currency, technical_attributes_id,
+                    debt_attributes_id, derivative_attributes_id, latest_record, valid_from, valid_to,
+                    source_file_id, record_type
+                FROM ReferenceData WHERE id = ?
+            "#
+        ).bind(id).fetch_one(&mut *conn).await?;
+        let ref_data = ReferenceData {
+            isin: Isin::new_unchecked(isin),
+            full_name,
+            cfi: parse("ReferenceData.cfi", &cfi)?,
+            is_commodities_derivative,
+            issuer_lei: Lei::new_unchecked(issuer_lei),
+            fisn: Fisn::new(fisn),
+            trading_venue_attrs: TradingVenueAttributes::from_db(trading_venue_attrs_id, conn).await?,
+            notional_currency: parse("ReferenceData.notional_currency", &notional_currency)?,
+            technical_attributes: from_db_option(technical_attributes_id, conn).await?,
+            debt_attributes: from_db_option(debt_attributes_id, conn).await?,
+            derivative_attributes: from_db_option(derivative_attributes_id, conn).await?,
+        };
+        Ok(Self {
+            ref_data,
+            latest_record,
+            valid_from: parse("ReferenceData.valid_from", &valid_from)?,
+            valid_to: parse_date("ReferenceData.valid_to", valid_to)?,
+            source: match (source_file_id, record_type) {
+                (Some(source_file_id), Some(record_type)) => Some(RecordSource {
+                    source_file_id,
+                    record_type: parse("ReferenceData.record_type", &record_type)?,
+                }),
+                _ => None
+            },
+        })
+    }
+}
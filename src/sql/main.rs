@@ -0,0 +1,326 @@
+#![cfg(feature = "sql-cli")]
+
+use chrono::{Days, NaiveDate, Utc};
+use clap::{Parser, Subcommand};
+use std::fs::File;
+use std::io::{stdout, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{SqlitePool, SqliteTransaction};
+use firds::sql::{
+    bulk_load,
+    check_db,
+    compact_db,
+    dedupe_shared_rows,
+    enable_search,
+    export_snapshot_csv,
+    export_snapshot_db,
+    prune_history,
+    repair_db,
+    search_instruments,
+    upgrade_db,
+    BulkLoadOptions,
+    Discrepancy,
+    RecordType,
+    Reconciler,
+    RefDataDbEntry,
+    SourceFile,
+    SqlError,
+    ToDb,
+    SCHEMA_VERSION
+};
+use firds::xml::IterRefData;
+use firds::ReferenceData;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// Path to the SQLite database.
+    db_path: PathBuf,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create the database if it doesn't exist, or upgrade its schema to the latest version.
+    Upgrade,
+    /// Load a set of FULINS files into the database (creating it if it doesn't exist) using the
+    /// bulk loader, and print statistics about the load.
+    Load {
+        /// Disable journaling and syncing during the load. The database may be corrupted if the
+        /// load is interrupted, so only use this when the database can be rebuilt from the files.
+        #[clap(long, action)]
+        fast: bool,
+        /// The number of records to buffer before writing them to the database.
+        #[clap(long)]
+        batch_size: Option<usize>,
+        /// The publisher of the files, recorded as the source of the records.
+        #[clap(long, default_value = "ESMA", value_parser = ["ESMA", "FCA"])]
+        source: String,
+        /// The FULINS files to load.
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Merge duplicate Term, FloatingRate and FirdsIndex rows.
+    Dedupe,
+    /// Check the record history for inconsistencies, such as more than one latest record for the
+    /// same ISIN and MIC or overlapping validity periods. Exits with status 1 if any are found.
+    Check {
+        /// Repair the inconsistencies that can be repaired unambiguously before checking.
+        #[clap(long, action)]
+        repair: bool,
+    },
+    /// Delete records which ceased to be valid before a given date, along with any rows which are
+    /// no longer referenced as a result.
+    Prune {
+        /// Delete records which ceased to be valid before this date.
+        #[clap(long, required_unless_present = "older_than_days", conflicts_with = "older_than_days")]
+        before: Option<NaiveDate>,
+        /// Delete records which ceased to be valid more than this many days ago.
+        #[clap(long)]
+        older_than_days: Option<u64>,
+        /// Compact the database afterwards.
+        #[clap(long, action)]
+        vacuum: bool,
+    },
+    /// Compact the database to reclaim the space left by deleted rows.
+    Vacuum,
+    /// Export the records that were valid on a given date, either to a new SQLite database or as
+    /// CSV.
+    Snapshot {
+        /// The date as of which to export the records.
+        date: NaiveDate,
+        /// Path of a new SQLite database to copy the records to.
+        #[clap(long, required_unless_present = "csv", conflicts_with = "csv")]
+        to_db: Option<PathBuf>,
+        /// Path of a CSV file to write the records to, or "-" to write to stdout.
+        #[clap(long)]
+        csv: Option<PathBuf>,
+    },
+    /// Compare the latest records in the database with a set of FULINS files, printing the
+    /// instruments which are missing, extra or different. Exits with status 1 if any differences
+    /// are found and not repaired.
+    Reconcile {
+        /// The publication date of the FULINS files.
+        #[clap(long)]
+        as_of: NaiveDate,
+        /// Repair the database to match the FULINS files.
+        #[clap(long, action)]
+        repair: bool,
+        /// The publisher of the files, recorded as the source of the repaired records.
+        #[clap(long, default_value = "ESMA", value_parser = ["ESMA", "FCA"])]
+        source: String,
+        /// The FULINS files to compare with. All of the files for each CFI category should be
+        /// given.
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Create (or rebuild) the full-text search index over instrument names, FISNs and issuer
+    /// LEIs.
+    EnableSearch,
+    /// Search the latest records by instrument name, FISN or issuer LEI, printing the ISIN, MIC
+    /// and name of each match.
+    Search {
+        /// The terms to search for, eg, "Siemens 2030".
+        #[clap(required = true)]
+        query: Vec<String>,
+        /// The maximum number of matches to print.
+        #[clap(long, default_value_t = 20)]
+        limit: u32,
+    },
+}
+
+async fn connect(path: &Path, create: bool) -> Result<SqlitePool, SqlError> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(create);
+    Ok(SqlitePoolOptions::new().max_connections(1).connect_with(options).await?)
+}
+
+/// Iterate over the records in a FULINS file, exiting if the file can't be opened or parsed.
+fn read_fulins(path: &Path) -> impl Iterator<Item = ReferenceData> + '_ {
+    let iter = IterRefData::new(path).unwrap_or_else(|e| {
+        eprintln!("Could not open {}: {e:?}", path.display());
+        exit(1);
+    });
+    iter.map(move |ref_data| ref_data.unwrap_or_else(|e| {
+        eprintln!("Could not parse record in {}: {e:?}", path.display());
+        exit(1);
+    }))
+}
+
+/// Store a [`SourceFile`] for the FULINS file at `path`, published by `source`, and return its ID.
+/// Exits if the file is not named as a FULINS file.
+async fn store_fulins_source(path: &Path, source: &str, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let file = SourceFile::from_file_name(source, file_name).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        exit(1);
+    });
+    if file.file_type != "FULINS" {
+        eprintln!("{} is not a FULINS file.", path.display());
+        exit(1);
+    }
+    file.to_db(tx).await
+}
+
+async fn run(args: &Args) -> Result<(), SqlError> {
+    let create = matches!(args.command, Command::Upgrade | Command::Load { .. });
+    let pool = connect(&args.db_path, create).await?;
+    match &args.command {
+        Command::Upgrade => {
+            let from_version = upgrade_db(&pool).await?;
+            if from_version == SCHEMA_VERSION {
+                eprintln!("Database schema is up to date (version {SCHEMA_VERSION}).");
+            } else {
+                eprintln!("Upgraded database schema from version {from_version} to {SCHEMA_VERSION}.");
+            }
+        },
+        Command::Load { fast, batch_size, source, files } => {
+            upgrade_db(&pool).await?;
+            let mut options = if *fast { BulkLoadOptions::fast_import() } else { BulkLoadOptions::default() };
+            if let Some(batch_size) = batch_size {
+                options.batch_size = *batch_size;
+            }
+            let mut tx = pool.begin().await?;
+            let mut source_file_ids = vec![];
+            for path in files {
+                source_file_ids.push(store_fulins_source(path, source, &mut tx).await?);
+            }
+            tx.commit().await?;
+            let entries = files.iter()
+                .zip(source_file_ids)
+                .flat_map(|(path, id)| read_fulins(path).map(move |ref_data| (ref_data, id)))
+                .map(|(ref_data, id)| {
+                    RefDataDbEntry::new_latest(ref_data).unwrap_or_else(|e| {
+                        eprintln!("{e}");
+                        exit(1);
+                    }).with_source(id, RecordType::Full)
+                });
+            let mut conn = pool.acquire().await?;
+            let stats = bulk_load(&mut conn, entries, options).await?;
+            drop(conn);
+            eprintln!("{stats}.");
+        },
+        Command::Dedupe => {
+            let mut tx = pool.begin().await?;
+            let deleted = dedupe_shared_rows(&mut tx).await?;
+            tx.commit().await?;
+            eprintln!("Deleted {deleted} duplicate rows.");
+        },
+        Command::Check { repair } => {
+            let mut tx = pool.begin().await?;
+            if *repair {
+                let updated = repair_db(&mut tx).await?;
+                eprintln!("Repaired {updated} records.");
+            }
+            let violations = check_db(&mut tx).await?;
+            tx.commit().await?;
+            for violation in &violations {
+                println!("{violation}");
+            }
+            eprintln!("Found {} inconsistencies.", violations.len());
+            if !violations.is_empty() {
+                pool.close().await;
+                exit(1);
+            }
+        },
+        Command::Prune { before, older_than_days, vacuum } => {
+            let before = match (before, older_than_days) {
+                (Some(d), _) => *d,
+                (None, Some(days)) => Utc::now().date_naive() - Days::new(*days),
+                (None, None) => unreachable!("clap requires one of --before and --older-than-days")
+            };
+            let mut tx = pool.begin().await?;
+            let stats = prune_history(before, &mut tx).await?;
+            tx.commit().await?;
+            eprintln!(
+                "Deleted {} records which ceased to be valid before {before} and {} other rows.",
+                stats.records,
+                stats.child_rows
+            );
+            if *vacuum {
+                compact_db(&pool).await?;
+                eprintln!("Compacted database.");
+            }
+        },
+        Command::Vacuum => {
+            compact_db(&pool).await?;
+            eprintln!("Compacted database.");
+        },
+        Command::Snapshot { date, to_db, csv } => {
+            if let Some(path) = to_db {
+                if path.exists() {
+                    eprintln!("{} already exists.", path.display());
+                    exit(2);
+                }
+                let dest = connect(path, true).await?;
+                let stats = export_snapshot_db(*date, &pool, &dest, BulkLoadOptions::default()).await?;
+                dest.close().await;
+                eprintln!("Exported {} records valid on {date} to {}.", stats.records, path.display());
+            } else if let Some(path) = csv {
+                let count = if path.as_os_str() == "-" {
+                    export_snapshot_csv(*date, &pool, stdout().lock()).await?
+                } else {
+                    export_snapshot_csv(*date, &pool, BufWriter::new(File::create(path)?)).await?
+                };
+                eprintln!("Exported {count} records valid on {date}.");
+            }
+        },
+        Command::Reconcile { as_of, repair, source, files } => {
+            let mut tx = pool.begin().await?;
+            let mut reconciler = Reconciler::new(*as_of, *repair);
+            for path in files {
+                if *repair {
+                    reconciler = reconciler.with_source_file(store_fulins_source(path, source, &mut tx).await?);
+                }
+                for ref_data in read_fulins(path) {
+                    if let Some(discrepancy) = reconciler.add(ref_data, &mut tx).await? {
+                        println!("{discrepancy}");
+                    }
+                }
+            }
+            let report = reconciler.finish(&mut tx).await?;
+            tx.commit().await?;
+            let extra = report.discrepancies.iter().skip_while(|d| !matches!(d, Discrepancy::Extra { .. }));
+            for discrepancy in extra {
+                println!("{discrepancy}");
+            }
+            eprintln!(
+                "Checked {} records: {} matched, {} discrepancies, {} repaired.",
+                report.checked,
+                report.matched,
+                report.discrepancies.len(),
+                report.repaired
+            );
+            if !*repair && !report.discrepancies.is_empty() {
+                pool.close().await;
+                exit(1);
+            }
+        },
+        Command::EnableSearch => {
+            enable_search(&pool).await?;
+            eprintln!("Enabled full-text search.");
+        },
+        Command::Search { query, limit } => {
+            let mut conn = pool.acquire().await?;
+            let results = search_instruments(&query.join(" "), *limit, &mut conn).await?;
+            for ref_data in &results {
+                println!("{}\t{}\t{}", ref_data.isin, ref_data.venue(), ref_data.full_name);
+            }
+            eprintln!("Found {} matches.", results.len());
+        }
+    }
+    pool.close().await;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if let Err(e) = run(&args).await {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
@@ -0,0 +1,355 @@
+//! Maintenance operations on an existing SQLite database.
+
+use crate::sql::error::SqlError;
+use chrono::NaiveDate;
+use sqlx::{Executor, SqlitePool, SqliteTransaction};
+
+/// A table whose rows are shared between records, along with the columns that reference it.
+struct SharedTable {
+    name: &'static str,
+    /// The columns which together identify a row's value.
+    value_columns: &'static str,
+    /// `(table, column)` pairs which reference this table's `id` column.
+    references: &'static [(&'static str, &'static str)],
+}
+
+/// The shared tables, in the order they must be de-duplicated (a table must be de-duplicated after
+/// any of the tables its value columns reference).
+const SHARED_TABLES: [SharedTable; 3] = [
+    SharedTable {
+        name: "Term",
+        value_columns: "number, unit",
+        references: &[("FloatingRate", "term_id")],
+    },
+    SharedTable {
+        name: "FloatingRate",
+        value_columns: "name, term_id",
+        references: &[
+            ("FirdsIndex", "name_id"),
+            ("InterestRate", "floating_rate_id"),
+            ("InterestRateDerivativeAttributes", "reference_rate_id"),
+        ],
+    },
+    SharedTable {
+        name: "FirdsIndex",
+        value_columns: "isin, name_id",
+        references: &[("UnderlyingSingle", "index_id")],
+    },
+];
+
+/// Merge duplicate `Term`, `FloatingRate` and `FirdsIndex` rows, which were created for every
+/// record by older versions of this crate. References to duplicates are updated to point to the
+/// row with the lowest ID, and the duplicates are deleted. Returns the number of rows deleted.
+pub async fn dedupe_shared_rows(tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+    let mut deleted = 0;
+    for table in SHARED_TABLES {
+        // The primary key lets each referencing row find its canonical row without scanning the
+        // table, which would make the merge quadratic in the number of duplicates.
+        sqlx::query("CREATE TEMP TABLE DuplicateRow (id INTEGER PRIMARY KEY, canonical_id INTEGER NOT NULL)")
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query(&format!(
+            r#"
+                INSERT INTO DuplicateRow (id, canonical_id)
+                SELECT id, canonical_id FROM (
+                    SELECT id, MIN(id) OVER (PARTITION BY {}) AS canonical_id FROM {}
+                )
+                WHERE id != canonical_id
+            "#,
+            table.value_columns, table.name
+        )).execute(&mut **tx).await?;
+        for (ref_table, ref_column) in table.references {
+            sqlx::query(&format!(
+                r#"
+                    UPDATE {ref_table}
+                    SET {ref_column} = DuplicateRow.canonical_id
+                    FROM DuplicateRow
+                    WHERE {ref_table}.{ref_column} = DuplicateRow.id
+                "#
+            )).execute(&mut **tx).await?;
+        }
+        deleted += sqlx::query(&format!("DELETE FROM {} WHERE id IN (SELECT id FROM DuplicateRow)", table.name))
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+        sqlx::query("DROP TABLE DuplicateRow").execute(&mut **tx).await?;
+    }
+    Ok(deleted)
+}
+
+/// A table whose rows are only kept while they are referenced by another row.
+struct ChildTable {
+    name: &'static str,
+    /// The column by which rows are referenced (normally `id`).
+    key: &'static str,
+    /// `(table, column)` pairs which reference this table's key column.
+    references: &'static [(&'static str, &'static str)],
+}
+
+/// The child tables, in the order they must be cleaned up (a table must come after all of the
+/// tables that reference it).
+const CHILD_TABLES: [ChildTable; 19] = [
+    ChildTable {
+        name: "TradingVenueAttributes",
+        key: "id",
+        references: &[("ReferenceData", "trading_venue_attrs_id")],
+    },
+    ChildTable {
+        name: "TechnicalAttributes",
+        key: "id",
+        references: &[("ReferenceData", "technical_attributes_id")],
+    },
+    ChildTable {
+        name: "DebtAttributes",
+        key: "id",
+        references: &[("ReferenceData", "debt_attributes_id")],
+    },
+    ChildTable {
+        name: "DerivativeAttributes",
+        key: "id",
+        references: &[("ReferenceData", "derivative_attributes_id")],
+    },
+    ChildTable {
+        name: "PublicationPeriod",
+        key: "id",
+        references: &[("TechnicalAttributes", "publication_period_id")],
+    },
+    ChildTable {
+        name: "StrikePrice",
+        key: "id",
+        references: &[("DerivativeAttributes", "strike_price_id")],
+    },
+    ChildTable {
+        name: "AssetClassSpecificAttributes",
+        key: "id",
+        references: &[("DerivativeAttributes", "asset_class_specific_attributes_id")],
+    },
+    ChildTable {
+        name: "DerivativeUnderlying",
+        key: "id",
+        references: &[("DerivativeAttributes", "underlying_id")],
+    },
+    ChildTable {
+        name: "CommodityDerivativeAttributes",
+        key: "id",
+        references: &[("AssetClassSpecificAttributes", "commodity_attributes_id")],
+    },
+    ChildTable {
+        name: "InterestRateDerivativeAttributes",
+        key: "id",
+        references: &[("AssetClassSpecificAttributes", "ir_attributes_id")],
+    },
+    ChildTable {
+        name: "FxDerivativeAttributes",
+        key: "id",
+        references: &[("AssetClassSpecificAttributes", "fx_attributes_id")],
+    },
+    ChildTable {
+        name: "InterestRate",
+        key: "id",
+        references: &[
+            ("DebtAttributes", "interest_rate_id"),
+            ("InterestRateDerivativeAttributes", "interest_rate_1_id"),
+            ("InterestRateDerivativeAttributes", "interest_rate_2_id"),
+        ],
+    },
+    ChildTable {
+        name: "UnderlyingSingle",
+        key: "id",
+        references: &[("DerivativeUnderlying", "single_id")],
+    },
+    ChildTable {
+        name: "UnderlyingBasket",
+        key: "id",
+        references: &[("DerivativeUnderlying", "basket_id")],
+    },
+    ChildTable {
+        name: "UnderlyingBasketIsin",
+        key: "basket_id",
+        references: &[("UnderlyingBasket", "id")],
+    },
+    ChildTable {
+        name: "UnderlyingBasketIssuerLei",
+        key: "basket_id",
+        references: &[("UnderlyingBasket", "id")],
+    },
+    ChildTable {
+        name: "FirdsIndex",
+        key: "id",
+        references: &[("UnderlyingSingle", "index_id")],
+    },
+    ChildTable {
+        name: "FloatingRate",
+        key: "id",
+        references: &[
+            ("FirdsIndex", "name_id"),
+            ("InterestRate", "floating_rate_id"),
+            ("InterestRateDerivativeAttributes", "reference_rate_id"),
+        ],
+    },
+    ChildTable {
+        name: "Term",
+        key: "id",
+        references: &[("FloatingRate", "term_id")],
+    },
+];
+
+/// The number of rows deleted by [`prune_history`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PruneStats {
+    /// The number of `ReferenceData` rows deleted.
+    pub records: u64,
+    /// The number of rows deleted from other tables because they were no longer referenced.
+    pub child_rows: u64,
+}
+
+/// Delete all rows in the attribute and other child tables which are not referenced by any
+/// `ReferenceData` row (directly or indirectly). Returns the number of rows deleted.
+pub async fn delete_orphaned_rows(tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+    let mut deleted = 0;
+    for table in CHILD_TABLES {
+        let conditions = table.references.iter()
+            .map(|(ref_table, ref_column)| format!(
+                "{} NOT IN (SELECT {ref_column} FROM {ref_table} WHERE {ref_column} IS NOT NULL)",
+                table.key
+            ))
+            .collect::<Vec<String>>()
+            .join(" AND ");
+        deleted += sqlx::query(&format!("DELETE FROM {} WHERE {conditions}", table.name))
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+    }
+    Ok(deleted)
+}
+
+/// Delete all records which ceased to be valid before `before` (ie, whose `valid_to` date is
+/// earlier than `before`), along with the rows in other tables which are no longer referenced as a
+/// result. Records which are still valid on or after `before`, including all latest records, are
+/// kept.
+///
+/// Note that this deletes the history of cancelled instruments, as well as old versions of
+/// modified instruments. The space freed is only returned to the operating system once the
+/// database has been compacted with [`compact_db`].
+pub async fn prune_history(before: NaiveDate, tx: &mut SqliteTransaction<'_>) -> Result<PruneStats, SqlError> {
+    let records = sqlx::query("DELETE FROM ReferenceData WHERE valid_to < ?")
+        .bind(before.to_string())
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+    let child_rows = delete_orphaned_rows(tx).await?;
+    Ok(PruneStats { records, child_rows })
+}
+
+/// Rebuild the database file to reclaim the space left by deleted rows, and update the statistics
+/// used by the query planner. This cannot be run inside a transaction.
+pub async fn compact_db(pool: &SqlitePool) -> Result<(), SqlError> {
+    pool.execute("VACUUM").await?;
+    pool.execute("PRAGMA optimize").await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::memory_pool;
+    use crate::sql::maintenance::{compact_db, dedupe_shared_rows, prune_history, PruneStats};
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_dedupe_shared_rows() {
+        let pool = memory_pool().await;
+        // Two identical EURIBOR 3M rates (with their own terms), one EURIBOR 6M and two indexes on
+        // the duplicated 3M rate, as older versions would have inserted them.
+        sqlx::raw_sql(
+            r#"
+                INSERT INTO Term (id, number, unit) VALUES (1, 3, 'MNTH'), (2, 3, 'MNTH'), (3, 6, 'MNTH');
+                INSERT INTO FloatingRate (id, name, term_id) VALUES (1, 'EURO', 1), (2, 'EURO', 2), (3, 'EURO', 3);
+                INSERT INTO FirdsIndex (id, isin, name_id) VALUES (1, NULL, 1), (2, NULL, 2);
+                INSERT INTO UnderlyingSingle (id, index_id) VALUES (1, 1), (2, 2);
+                INSERT INTO InterestRate (id, floating_rate_id) VALUES (1, 2), (2, 3);
+            "#
+        ).execute(&pool).await.unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        assert_eq!(dedupe_shared_rows(&mut tx).await.unwrap(), 3);
+        tx.commit().await.unwrap();
+
+        let rates: Vec<(i64, i64)> = sqlx::query_as("SELECT id, term_id FROM FloatingRate ORDER BY id")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(rates, vec![(1, 1), (3, 3)]);
+        let underlying: Vec<i64> = sqlx::query_scalar("SELECT index_id FROM UnderlyingSingle ORDER BY id")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(underlying, vec![1, 1]);
+        let ir: Vec<i64> = sqlx::query_scalar("SELECT floating_rate_id FROM InterestRate ORDER BY id")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(ir, vec![1, 3]);
+
+        // Running it again should be a no-op.
+        let mut tx = pool.begin().await.unwrap();
+        assert_eq!(dedupe_shared_rows(&mut tx).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_history() {
+        let pool = memory_pool().await;
+        // A bond whose first version (1) was superseded by a second (2) at the start of 2025, and a
+        // cancelled swap on an index (3) which ceased to be valid in June 2025. The bonds share a
+        // floating rate.
+        sqlx::raw_sql(
+            r#"
+                INSERT INTO Term (id, number, unit) VALUES (1, 3, 'MNTH');
+                INSERT INTO FloatingRate (id, name, term_id) VALUES (1, 'EURI', 1);
+                INSERT INTO FirdsIndex (id, isin, name_id) VALUES (1, NULL, 1);
+                INSERT INTO InterestRate (id, floating_rate_id, spread) VALUES (1, 1, 50), (2, 1, 60);
+                INSERT INTO DebtAttributes (id, total_issued_amount, nominal_currency, nominal_value_per_unit,
+                                            interest_rate_id)
+                VALUES (1, 1000000, 'EUR', 1000, 1), (2, 1000000, 'EUR', 1000, 2);
+                INSERT INTO UnderlyingSingle (id, index_id) VALUES (1, 1);
+                INSERT INTO DerivativeUnderlying (id, single_id) VALUES (1, 1);
+                INSERT INTO DerivativeAttributes (id, underlying_id) VALUES (1, 1);
+                INSERT INTO TradingVenueAttributes (id, trading_venue, requested_admission)
+                VALUES (1, 'XLON', 0), (2, 'XLON', 0), (3, 'XLON', 0);
+                INSERT INTO ReferenceData (id, isin, full_name, cfi, is_commodities_derivative, issuer_lei, fisn,
+                                           trading_venue_attrs_id, notional_currency, debt_attributes_id,
+                                           derivative_attributes_id, instrument_class, latest_record,
+                                           valid_from, valid_to)
+                VALUES (1, 'XS0000000001', 'Bond', 'DBFTFB', 0, '5493001KJTIIGC8Y1R12', 'ISSUER/FRN', 1, 'EUR', 1,
+                        NULL, 'BOND', 0, '2024-01-01', '2024-12-31'),
+                       (2, 'XS0000000001', 'Bond', 'DBFTFB', 0, '5493001KJTIIGC8Y1R12', 'ISSUER/FRN', 2, 'EUR', 2,
+                        NULL, 'BOND', 1, '2025-01-01', NULL),
+                       (3, 'XS0000000002', 'Swap', 'SRCCSP', 0, '5493001KJTIIGC8Y1R12', 'ISSUER/SWAP', 3, 'EUR', NULL,
+                        1, 'SWAP', 0, '2024-01-01', '2025-06-30');
+            "#
+        ).execute(&pool).await.unwrap();
+
+        let count = |table: &'static str| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {table}")).fetch_one(&pool).await.unwrap()
+            }
+        };
+
+        // Only the first version of the bond is pruned. Its interest rate goes with it but the
+        // shared floating rate is kept.
+        let mut tx = pool.begin().await.unwrap();
+        let stats = prune_history(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), &mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(stats, PruneStats { records: 1, child_rows: 3 });
+        assert_eq!(count("InterestRate").await, 1);
+        assert_eq!(count("FloatingRate").await, 1);
+
+        // Now the swap is pruned too, along with everything only it referenced.
+        let mut tx = pool.begin().await.unwrap();
+        let stats = prune_history(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(), &mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(stats, PruneStats { records: 1, child_rows: 5 });
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM ReferenceData").fetch_all(&pool).await.unwrap();
+        assert_eq!(ids, vec![2]);
+        assert_eq!(count("DerivativeAttributes").await, 0);
+        assert_eq!(count("FirdsIndex").await, 0);
+        assert_eq!(count("FloatingRate").await, 1);
+        assert_eq!(count("Term").await, 1);
+
+        compact_db(&pool).await.unwrap();
+    }
+}
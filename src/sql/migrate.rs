@@ -0,0 +1,241 @@
+//! Versioning and migration of the SQLite schema.
+//!
+//! The version of a database's schema is recorded in the `SchemaVersion` table. A new database is
+//! created from `sql/schema.sql`, which always describes the latest version. Existing databases are
+//! brought up to date by applying the migrations in `sql/migrations` that are newer than their
+//! recorded version, so they don't need to be rebuilt from FULINS files when the schema changes.
+//!
+//! Databases that were created before versioning was introduced have no `SchemaVersion` table and
+//! are treated as version 1.
+//!
+//! When changing the schema, update `sql/schema.sql`, add a migration that makes the same change to
+//! an existing database and increment [`SCHEMA_VERSION`].
+
+use crate::sql::error::SqlError;
+use sqlx::{Executor, SqlitePool, SqliteTransaction};
+
+const SQL_SCHEMA: &str = include_str!("../../sql/schema.sql");
+
+/// The version of the schema described by `sql/schema.sql`.
+pub const SCHEMA_VERSION: i64 = 6;
+
+struct Migration {
+    /// The schema version after the migration is applied.
+    version: i64,
+    description: &'static str,
+    sql: &'static str,
+}
+
+/// All migrations, in order of version.
+const MIGRATIONS: [Migration; 5] = [
+    Migration {
+        version: 2,
+        description: "Add instrument_class column to ReferenceData",
+        sql: include_str!("../../sql/migrations/0002_instrument_class.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Add missing commodity sub-product codes",
+        sql: include_str!("../../sql/migrations/0003_sub_product_codes.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "Index shared Term, FloatingRate and FirdsIndex rows",
+        sql: include_str!("../../sql/migrations/0004_shared_row_indexes.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "Add SourceFile table and record provenance columns to ReferenceData",
+        sql: include_str!("../../sql/migrations/0005_source_files.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "Add InstrumentClass table and check ReferenceData.instrument_class against it",
+        sql: include_str!("../../sql/migrations/0006_instrument_class_table.sql"),
+    },
+];
+
+const CREATE_VERSION_TABLE: &str = r#"
+    CREATE TABLE IF NOT EXISTS SchemaVersion (
+        version INTEGER PRIMARY KEY,
+        description TEXT NOT NULL,
+        applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+    )
+"#;
+
+/// Determine the schema version of the database: 0 if it has not been initialised, 1 if it was
+/// created before versioning was introduced, or otherwise the latest version recorded.
+async fn current_version(tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
+    let has_table = |name: &'static str| sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)"
+    ).bind(name);
+    if has_table("SchemaVersion").fetch_one(&mut **tx).await? {
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM SchemaVersion")
+            .fetch_one(&mut **tx)
+            .await?;
+        if let Some(version) = version {
+            return Ok(version)
+        }
+    }
+    if has_table("ReferenceData").fetch_one(&mut **tx).await? {
+        Ok(1)
+    } else {
+        Ok(0)
+    }
+}
+
+async fn record_version(
+    version: i64,
+    description: &str,
+    tx: &mut SqliteTransaction<'_>
+) -> Result<(), SqlError> {
+    sqlx::query("INSERT INTO SchemaVersion (version, description) VALUES (?, ?)")
+        .bind(version)
+        .bind(description)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Return the schema version of the database (0 if it has not been initialised).
+pub async fn schema_version(pool: &SqlitePool) -> Result<i64, SqlError> {
+    let mut tx = pool.begin().await?;
+    current_version(&mut tx).await
+}
+
+/// Bring the database's schema up to [`SCHEMA_VERSION`], creating the tables if the database has
+/// not been initialised and otherwise applying any outstanding migrations. The upgrade is done in a
+/// single transaction, so if any migration fails the database is left unchanged.
+///
+/// Returns the schema version of the database before the upgrade. Returns an error if the database
+/// was created by a newer version of this crate.
+pub async fn upgrade_db(pool: &SqlitePool) -> Result<i64, SqlError> {
+    let mut tx = pool.begin().await?;
+    let from_version = current_version(&mut tx).await?;
+    if from_version > SCHEMA_VERSION {
+        return Err(SqlError::UnsupportedSchemaVersion(from_version))
+    }
+    tx.execute(CREATE_VERSION_TABLE).await?;
+    if from_version == 0 {
+        sqlx::raw_sql(SQL_SCHEMA).execute(&mut *tx).await?;
+        record_version(SCHEMA_VERSION, "Create schema", &mut tx).await?;
+    } else {
+        for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+            sqlx::raw_sql(migration.sql).execute(&mut *tx).await?;
+            record_version(migration.version, migration.description, &mut tx).await?;
+        }
+    }
+    tx.commit().await?;
+    Ok(from_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::empty_memory_pool;
+    use crate::sql::migrate::{schema_version, upgrade_db, MIGRATIONS, SCHEMA_VERSION};
+    use crate::sql::{enable_search, SqlError};
+    use sqlx::SqlitePool;
+
+    const BASELINE_SCHEMA: &str = include_str!("../../sql/migrations/0001_baseline.sql");
+
+    /// Return the definitions of all tables, indexes and triggers in the database, except
+    /// `SchemaVersion`.
+    async fn schema(pool: &SqlitePool) -> Vec<(String, String)> {
+        let mut schema: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+                SELECT type, name, tbl_name FROM sqlite_master
+                WHERE name NOT LIKE 'sqlite_%' AND tbl_name != 'SchemaVersion'
+                ORDER BY type, name
+            "#
+        ).fetch_all(pool).await.unwrap();
+        let mut columns = vec![];
+        for (kind, name, table) in schema.drain(..) {
+            if kind == "table" {
+                let cols: Vec<(String, String, bool)> = sqlx::query_as(
+                    "SELECT name, type, \"notnull\" FROM pragma_table_info(?) ORDER BY name"
+                ).bind(&table).fetch_all(pool).await.unwrap();
+                for (col, ty, not_null) in cols {
+                    columns.push((name.clone(), format!("{col} {ty} {not_null}")));
+                }
+                let foreign_keys: Vec<(String, String, String)> = sqlx::query_as(
+                    "SELECT \"from\", \"table\", \"to\" FROM pragma_foreign_key_list(?) ORDER BY \"from\""
+                ).bind(&table).fetch_all(pool).await.unwrap();
+                for (col, ref_table, ref_col) in foreign_keys {
+                    columns.push((name.clone(), format!("{col} REFERENCES {ref_table}({ref_col})")));
+                }
+            } else {
+                columns.push((kind, name));
+            }
+        }
+        columns
+    }
+
+    #[tokio::test]
+    async fn test_new_db() {
+        let pool = empty_memory_pool().await;
+        assert_eq!(schema_version(&pool).await.unwrap(), 0);
+        assert_eq!(upgrade_db(&pool).await.unwrap(), 0);
+        assert_eq!(schema_version(&pool).await.unwrap(), SCHEMA_VERSION);
+        // Upgrading an up-to-date database is a no-op.
+        assert_eq!(upgrade_db(&pool).await.unwrap(), SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_legacy_db() {
+        assert_eq!(MIGRATIONS.last().unwrap().version, SCHEMA_VERSION);
+
+        let legacy = empty_memory_pool().await;
+        sqlx::raw_sql(BASELINE_SCHEMA).execute(&legacy).await.unwrap();
+        sqlx::raw_sql(
+            r#"
+                INSERT INTO TradingVenueAttributes (id, trading_venue, requested_admission) VALUES (1, 'XLON', 0);
+                INSERT INTO ReferenceData (isin, full_name, cfi, is_commodities_derivative, issuer_lei, fisn,
+                                           trading_venue_attrs_id, notional_currency, latest_record, valid_from)
+                VALUES ('GB00B03MLX29', 'Shell', 'ESVUFR', 0, '21380068P1DRHMJ8KU70', 'SHELL/SHS', 1, 'GBP', 1, '2025-01-01'),
+                       ('DE000F1KXNY9', 'Wheat future', 'FCECSX', 0, '529900UT4DG0LG5R9O07', 'EUREX/F WHEAT', 1, 'EUR', 1, '2025-01-01');
+            "#
+        ).execute(&legacy).await.unwrap();
+        assert_eq!(schema_version(&legacy).await.unwrap(), 1);
+        enable_search(&legacy).await.unwrap();
+        assert_eq!(upgrade_db(&legacy).await.unwrap(), 1);
+        assert_eq!(schema_version(&legacy).await.unwrap(), SCHEMA_VERSION);
+
+        let classes: Vec<String> = sqlx::query_scalar("SELECT instrument_class FROM ReferenceData ORDER BY id")
+            .fetch_all(&legacy).await.unwrap();
+        assert_eq!(classes, vec!["EQTY", "CMDT"]);
+        assert!(sqlx::query("UPDATE ReferenceData SET instrument_class = 'XXXX' WHERE isin = 'GB00B03MLX29'")
+            .execute(&legacy).await.is_err());
+
+        // Search is still enabled, and kept in sync with ReferenceData, after the upgrade.
+        sqlx::query("UPDATE ReferenceData SET full_name = 'Shell plc' WHERE isin = 'GB00B03MLX29'")
+            .execute(&legacy).await.unwrap();
+        let matches: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM InstrumentSearch WHERE InstrumentSearch MATCH 'plc'")
+            .fetch_one(&legacy).await.unwrap();
+        assert_eq!(matches, 1);
+
+        // A migrated database should have the same tables, columns, foreign keys, indexes and
+        // triggers as a new one.
+        let new = empty_memory_pool().await;
+        upgrade_db(&new).await.unwrap();
+        enable_search(&new).await.unwrap();
+        assert_eq!(schema(&legacy).await, schema(&new).await);
+        let sub_products: Vec<String> = sqlx::query_scalar("SELECT code FROM SubProduct ORDER BY code")
+            .fetch_all(&legacy).await.unwrap();
+        let new_sub_products: Vec<String> = sqlx::query_scalar("SELECT code FROM SubProduct ORDER BY code")
+            .fetch_all(&new).await.unwrap();
+        assert_eq!(sub_products, new_sub_products);
+    }
+
+    #[tokio::test]
+    async fn test_newer_db() {
+        let pool = empty_memory_pool().await;
+        upgrade_db(&pool).await.unwrap();
+        sqlx::query("INSERT INTO SchemaVersion (version, description) VALUES (?, 'From the future')")
+            .bind(SCHEMA_VERSION + 1)
+            .execute(&pool).await.unwrap();
+        assert!(matches!(
+            upgrade_db(&pool).await,
+            Err(SqlError::UnsupportedSchemaVersion(v)) if v == SCHEMA_VERSION + 1
+        ));
+    }
+}
@@ -1,14 +1,48 @@
 //! Code for serialising FIRDS data to and from an SQL database.
+//!
+//! SQLite is supported with the `sql` feature, and PostgreSQL with the `postgres` feature (see the
+//! [`postgres`] module). Serialisation is done through the [`ToDb`] trait, which is generic over
+//! the [`sqlx`] database backend, so the same loading code can target either database. Records can
+//! be read back from an SQLite database with the [`FromDb`] trait, or as a point-in-time
+//! [`Snapshot`], and compared with a fresh FULINS snapshot using a [`Reconciler`]. Instruments can
+//! be found by name with [`search_instruments`] once full-text search has been enabled.
+//!
+//! Each record can also store the [`SourceFile`] and [`RecordType`] from which it was loaded, so
+//! any value in the database can be traced back to the file in which it was published.
 
+mod bulk;
+mod consistency;
 mod error;
+mod from_db;
+mod maintenance;
+mod migrate;
+mod provenance;
+mod reconcile;
+mod search;
+mod snapshot;
 mod to_db;
 
-use sqlx::{Executor, SqlitePool};
+#[cfg(feature = "postgres")]
+pub mod postgres;
 
-const SQL_SCHEMA: &str = include_str!("../../sql/schema.sql");
+pub use bulk::{bulk_load, BulkLoadOptions, BulkLoader, JournalMode, LoadStats, Pragmas, Synchronous};
+pub use consistency::{check_db, repair_db, Violation};
+pub use error::SqlError;
+pub use from_db::FromDb;
+pub use maintenance::{compact_db, dedupe_shared_rows, delete_orphaned_rows, prune_history, PruneStats};
+pub use migrate::{schema_version, upgrade_db, SCHEMA_VERSION};
+pub use provenance::{RecordSource, RecordType, SourceFile};
+pub use reconcile::{reconcile, Discrepancy, FieldDiff, ReconcileReport, Reconciler};
+pub use search::{disable_search, enable_search, search_enabled, search_instruments};
+pub use snapshot::{export_snapshot_csv, export_snapshot_db, Snapshot};
+pub use to_db::{Backend, RefDataDbEntry, ToDb, ToDbOption};
 
+use sqlx::SqlitePool;
+
+/// Create the tables and populate the enum tables of an SQLite database, or upgrade the schema of an
+/// existing database to the latest version (see [`upgrade_db`]).
 pub async fn init_db(pool: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
-    pool.execute(SQL_SCHEMA).await?;
+    upgrade_db(pool).await?;
     Ok(())
 }
 
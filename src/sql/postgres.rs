@@ -0,0 +1,886 @@
+//! PostgreSQL backend, enabled with the `postgres` feature.
+//!
+//! The schema (`sql/schema_postgres.sql`) mirrors the SQLite schema, except that IDs are identity
+//! columns and dates and times are stored using native types. All of the [`ToDb`] impls in this
+//! module are implemented for [`Postgres`], so the same loading code used for SQLite can be used
+//! here by passing a [`PgTransaction`] instead of a [`SqliteTransaction`](sqlx::SqliteTransaction).
+//!
+//! Unlike the SQLite impls, the queries here are not checked at compile time, so building with
+//! this feature does not require a running PostgreSQL server.
+
+use crate::sql::error::SqlError;
+use crate::sql::provenance::SourceFile;
+use crate::sql::to_db::{Backend, RefDataDbEntry, ToDb, ToDbOption};
+use crate::*;
+use chrono::NaiveDate;
+use sqlx::{Executor, PgPool, PgTransaction, Postgres};
+
+const PG_SCHEMA: &str = include_str!("../../sql/schema_postgres.sql");
+
+/// Create the tables (if they don't already exist) and populate the enum tables of a PostgreSQL
+/// database. A database created by an earlier version of this crate is brought up to date,
+/// merging any duplicate shared rows before their unique constraints are added.
+pub async fn init_db(pool: &PgPool) -> Result<(), SqlError> {
+    pool.execute(PG_SCHEMA).await?;
+    Ok(())
+}
+
+impl Backend for Postgres {
+    async fn mark_prev_record(isin: &str, mic: &str, valid_to: NaiveDate, tx: &mut PgTransaction<'_>) -> Result<u64, SqlError> {
+        let query = sqlx::query(
+            r#"
+                UPDATE ReferenceData
+                SET valid_to = $1,
+                    latest_record = false
+                FROM TradingVenueAttributes
+                WHERE ReferenceData.trading_venue_attrs_id = TradingVenueAttributes.id
+                AND ReferenceData.isin = $2 AND TradingVenueAttributes.trading_venue = $3
+                AND ReferenceData.valid_to IS NULL
+            "#)
+            .bind(valid_to)
+            .bind(isin)
+            .bind(mic);
+        Ok(query
+            .execute(&mut **tx)
+            .await?
+            .rows_affected())
+    }
+}
+
+/// [`Term`], [`FloatingRate`] and [`Index`] rows are shared between records. `RETURNING` returns
+/// nothing when the row already exists, in which case the ID of the existing row is selected. This
+/// avoids writing a new version of the existing row, as a no-op `DO UPDATE` would.
+impl ToDb<Postgres> for Term {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let unit = self.unit.to_string();
+        let inserted = sqlx::query_scalar(
+            r#"
+                INSERT INTO Term (number, unit) VALUES ($1, $2)
+                ON CONFLICT (number, unit) DO NOTHING
+                RETURNING id
+            "#)
+            .bind(self.number)
+            .bind(&unit)
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(id) = inserted {
+            return Ok(id)
+        }
+        Ok(sqlx::query_scalar("SELECT id FROM Term WHERE number = $1 AND unit = $2")
+            .bind(self.number)
+            .bind(&unit)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for StrikePrice {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO StrikePrice (price_type, price, pending, currency)
+                VALUES ($1, $2, $3, $4) RETURNING id
+            "#)
+            .bind(self.price_type.to_string())
+            .bind(self.price)
+            .bind(self.pending)
+            .bind(self.currency.as_ref().map(|c| c.as_str()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for FloatingRate {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let term_id = self.term.to_db_option(tx).await?;
+        let name = self.name.as_ref().map(|n| n.to_string());
+        let inserted = sqlx::query_scalar(
+            r#"
+                INSERT INTO FloatingRate (name, term_id) VALUES ($1, $2)
+                ON CONFLICT (name, term_id) DO NOTHING
+                RETURNING id
+            "#)
+            .bind(&name)
+            .bind(term_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(id) = inserted {
+            return Ok(id)
+        }
+        Ok(sqlx::query_scalar(
+            r#"
+                SELECT id FROM FloatingRate
+                WHERE name IS NOT DISTINCT FROM $1 AND term_id IS NOT DISTINCT FROM $2
+            "#)
+            .bind(&name)
+            .bind(term_id)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for Index {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let name_id = self.name.to_db(tx).await?;
+        let isin = self.isin.as_ref().map(Isin::as_str);
+        let inserted = sqlx::query_scalar(
+            r#"
+                INSERT INTO FirdsIndex (isin, name_id) VALUES ($1, $2)
+                ON CONFLICT (isin, name_id) DO NOTHING
+                RETURNING id
+            "#)
+            .bind(isin)
+            .bind(name_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(id) = inserted {
+            return Ok(id)
+        }
+        Ok(sqlx::query_scalar("SELECT id FROM FirdsIndex WHERE isin IS NOT DISTINCT FROM $1 AND name_id = $2")
+            .bind(isin)
+            .bind(name_id)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for TradingVenueAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO TradingVenueAttributes (
+                    trading_venue,
+                    requested_admission,
+                    approval_date,
+                    request_date,
+                    admission_or_first_trade_date,
+                    termination_date
+                ) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id
+            "#)
+            .bind(self.trading_venue.as_str())
+            .bind(self.requested_admission)
+            .bind(self.approval_date)
+            .bind(self.request_date)
+            .bind(self.admission_or_first_trade_date)
+            .bind(self.termination_date)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for InterestRate {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let (fixed, floating, spread) = match self {
+            InterestRate::Fixed(rate) => (Some(*rate), None, None),
+            InterestRate::Floating(rate, spread) =>
+                (None, Some(rate.to_db(tx).await?), *spread),
+        };
+        Ok(sqlx::query_scalar(
+            "INSERT INTO InterestRate (fixed, floating_rate_id, spread) VALUES ($1, $2, $3) RETURNING id"
+            )
+            .bind(fixed)
+            .bind(floating)
+            .bind(spread)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for PublicationPeriod {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        Ok(sqlx::query_scalar(
+            "INSERT INTO PublicationPeriod (from_date, to_date) VALUES ($1, $2) RETURNING id"
+            )
+            .bind(self.from_date)
+            .bind(self.to_date)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for TechnicalAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let publication_period_id = self.publication_period.to_db_option(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO TechnicalAttributes (
+                    relevant_competent_authority,
+                    publication_period_id,
+                    relevant_trading_venue
+                ) VALUES ($1, $2, $3) RETURNING id
+            "#)
+            .bind(&self.relevant_competent_authority)
+            .bind(publication_period_id)
+            .bind(self.relevant_trading_venue.as_ref().map(|m| m.as_str()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for DebtAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let ir = self.interest_rate.to_db(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO DebtAttributes (
+                    total_issued_amount,
+                    maturity_date,
+                    nominal_currency,
+                    nominal_value_per_unit,
+                    interest_rate_id,
+                    seniority
+                ) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id
+            "#)
+            .bind(self.total_issued_amount)
+            .bind(self.maturity_date)
+            .bind(self.nominal_currency.as_str())
+            .bind(self.nominal_value_per_unit)
+            .bind(ir)
+            .bind(self.seniority.map(|s| s.to_string()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for CommodityDerivativeAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let (product, subproduct, further_subproduct) = self.product.to_codes();
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO CommodityDerivativeAttributes (
+                   product,
+                   subproduct,
+                   further_subproduct,
+                   transaction_type,
+                   final_price_type
+                ) VALUES ($1, $2, $3, $4, $5) RETURNING id
+            "#)
+            .bind(product)
+            .bind(subproduct)
+            .bind(further_subproduct)
+            .bind(self.transaction_type.map(|t| t.to_string()))
+            .bind(self.final_price_type.map(|t| t.to_string()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for InterestRateDerivativeAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let ref_rate = self.reference_rate.to_db(tx).await?;
+        let interest_rate_1_id = self.interest_rate_1.to_db_option(tx).await?;
+        let interest_rate_2_id = self.interest_rate_2.to_db_option(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO InterestRateDerivativeAttributes (
+                  reference_rate_id,
+                  interest_rate_1_id,
+                  notional_currency_2,
+                  interest_rate_2_id
+                ) VALUES ($1, $2, $3, $4) RETURNING id
+            "#)
+            .bind(ref_rate)
+            .bind(interest_rate_1_id)
+            .bind(self.notional_currency_2.as_ref().map(|c| c.as_str()))
+            .bind(interest_rate_2_id)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for FxDerivativeAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        Ok(sqlx::query_scalar(
+            "INSERT INTO FxDerivativeAttributes (notional_currency_2, fx_type) VALUES ($1, $2) RETURNING id"
+            )
+            .bind(self.notional_currency_2.as_ref().map(|c| c.as_str()))
+            .bind(self.fx_type.map(|t| t.to_string()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for UnderlyingSingle {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let (isin, index_id, lei) = match &self {
+            UnderlyingSingle::Isin(isin) => (Some(isin.as_str()), None, None),
+            UnderlyingSingle::Index(index) => (None, Some(index.to_db(tx).await?), None),
+            UnderlyingSingle::Lei(lei) => (None, None, Some(lei.as_str())),
+        };
+        Ok(sqlx::query_scalar(
+            "INSERT INTO UnderlyingSingle (isin, index_id, lei) VALUES ($1, $2, $3) RETURNING id"
+            )
+            .bind(isin)
+            .bind(index_id)
+            .bind(lei)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for UnderlyingBasket {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let basket_id: i64 = sqlx::query_scalar("INSERT INTO UnderlyingBasket DEFAULT VALUES RETURNING id")
+            .fetch_one(&mut **tx)
+            .await?;
+        for isin in &self.isin {
+            sqlx::query("INSERT INTO UnderlyingBasketIsin VALUES ($1, $2)")
+                .bind(basket_id)
+                .bind(isin.as_str())
+                .execute(&mut **tx)
+                .await?;
+        }
+        for lei in &self.issuer_lei {
+            sqlx::query("INSERT INTO UnderlyingBasketIssuerLei VALUES ($1, $2)")
+                .bind(basket_id)
+                .bind(lei.as_str())
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(basket_id)
+    }
+}
+
+impl ToDb<Postgres> for DerivativeUnderlying {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let (single_id, basket_id) = match &self {
+            DerivativeUnderlying::Single(s) => (Some(s.to_db(tx).await?), None),
+            DerivativeUnderlying::Basket(b) => (None, Some(b.to_db(tx).await?)),
+        };
+        Ok(sqlx::query_scalar(
+            "INSERT INTO DerivativeUnderlying (single_id, basket_id) VALUES ($1, $2) RETURNING id"
+            )
+            .bind(single_id)
+            .bind(basket_id)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for AssetClassSpecificAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let comm_attrs = self.commodity_attributes.to_db_option(tx).await?;
+        let ir_attrs = self.ir_attributes.to_db_option(tx).await?;
+        let fx_attrs = self.fx_attributes.to_db_option(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO AssetClassSpecificAttributes (
+                  commodity_attributes_id,
+                  ir_attributes_id,
+                  fx_attributes_id
+                ) VALUES ($1, $2, $3) RETURNING id
+            "#)
+            .bind(comm_attrs)
+            .bind(ir_attrs)
+            .bind(fx_attrs)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for DerivativeAttributes {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let underlying = self.underlying.to_db_option(tx).await?;
+        let strike_price = self.strike_price.to_db_option(tx).await?;
+        let acsa = self.asset_class_specific_attributes.to_db_option(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO DerivativeAttributes (
+                  expiry_date,
+                  price_multiplier,
+                  underlying_id,
+                  option_type,
+                  strike_price_id,
+                  option_exercise_style,
+                  delivery_type,
+                  asset_class_specific_attributes_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id
+            "#)
+            .bind(self.expiry_date)
+            .bind(self.price_multiplier)
+            .bind(underlying)
+            .bind(self.option_type.map(|t| t.to_string()))
+            .bind(strike_price)
+            .bind(self.option_exercise_style.map(|s| s.to_string()))
+            .bind(self.delivery_type.map(|t| t.to_string()))
+            .bind(acsa)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for SourceFile {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let inserted = sqlx::query_scalar(
+            r#"
+                INSERT INTO SourceFile (
+                    source,
+                    file_type,
+                    file_name,
+                    file_id,
+                    download_link,
+                    publication_timestamp,
+                    checksum
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (source, file_name) DO NOTHING
+                RETURNING id
+            "#)
+            .bind(&self.source)
+            .bind(&self.file_type)
+            .bind(&self.file_name)
+            .bind(&self.file_id)
+            .bind(&self.download_link)
+            .bind(self.timestamp)
+            .bind(&self.checksum)
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(id) = inserted {
+            return Ok(id)
+        }
+        Ok(sqlx::query_scalar("SELECT id FROM SourceFile WHERE source = $1 AND file_name = $2")
+            .bind(&self.source)
+            .bind(&self.file_name)
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+impl ToDb<Postgres> for RefDataDbEntry {
+    async fn to_db(&self, tx: &mut PgTransaction<'_>) -> Result<i64, SqlError> {
+        let tv_attrs = self.ref_data.trading_venue_attrs.to_db(tx).await?;
+        let tech_attrs = self.ref_data.technical_attributes.to_db_option(tx).await?;
+        let debt_attrs = self.ref_data.debt_attributes.to_db_option(tx).await?;
+        let deriv_attrs = self.ref_data.derivative_attributes.to_db_option(tx).await?;
+        Ok(sqlx::query_scalar(
+            r#"
+                INSERT INTO ReferenceData (
+                   isin,
+                   full_name,
+                   cfi,
+                   is_commodities_derivative,
+                   issuer_lei,
+                   fisn,
+                   trading_venue_attrs_id,
+                   notional_currency,
+                   technical_attributes_id,
+                   debt_attributes_id,
+                   derivative_attributes_id,
+                   instrument_class,
+                   latest_record,
+                   valid_from,
+                   valid_to,
+                   source_file_id,
+                   record_type
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                RETURNING id
+            "#)
+            .bind(self.ref_data.isin.as_str())
+            .bind(&self.ref_data.full_name)
+            .bind(self.ref_data.cfi.as_str())
+            .bind(self.ref_data.is_commodities_derivative)
+            .bind(self.ref_data.issuer_lei.as_str())
+            .bind(self.ref_data.fisn.as_str())
+            .bind(tv_attrs)
+            .bind(self.ref_data.notional_currency.as_str())
+            .bind(tech_attrs)
+            .bind(debt_attrs)
+            .bind(deriv_attrs)
+            .bind(self.ref_data.classify().to_string())
+            .bind(self.latest_record)
+            .bind(self.valid_from)
+            .bind(self.valid_to)
+            .bind(self.source.map(|s| s.source_file_id))
+            .bind(self.source.map(|s| s.record_type.to_string()))
+            .fetch_one(&mut **tx)
+            .await?)
+    }
+}
+
+/// The integration tests start a throwaway PostgreSQL server using the `initdb` and `pg_ctl`
+/// binaries on the `PATH` (`initdb` refuses to run as root). Alternatively, set
+/// `FIRDS_TEST_POSTGRES_URL` to the URL of an existing server, in which each test creates its own
+/// database. The tests fail if no server is available, unless `FIRDS_SKIP_POSTGRES_TESTS` is set.
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::records;
+    use crate::sql::postgres::init_db;
+    use crate::sql::{RecordType, RefDataDbEntry, SourceFile, ToDb};
+    use crate::*;
+    use chrono::NaiveDate;
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use sqlx::{Connection, Executor, PgConnection, PgPool};
+    use std::env::temp_dir;
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    const NO_SERVER: &str = "Could not start a PostgreSQL server for testing. Put initdb and pg_ctl \
+        on the PATH and run the tests as a user other than root, or set FIRDS_TEST_POSTGRES_URL to \
+        the URL of an existing server. Set FIRDS_SKIP_POSTGRES_TESTS to skip these tests.";
+
+    /// A PostgreSQL database for the duration of a test.
+    struct TestServer {
+        data_dir: Option<PathBuf>,
+        options: PgConnectOptions,
+    }
+
+    impl TestServer {
+        /// Start a server, or create a database in the server given by `FIRDS_TEST_POSTGRES_URL`,
+        /// for the named test. Returns `None` if the PostgreSQL tests are to be skipped, and panics
+        /// if no server is available.
+        async fn start(name: &str) -> Option<Self> {
+            if std::env::var_os("FIRDS_SKIP_POSTGRES_TESTS").is_some() {
+                eprintln!("Skipping PostgreSQL test {name}: FIRDS_SKIP_POSTGRES_TESTS is set");
+                return None
+            }
+            if let Ok(url) = std::env::var("FIRDS_TEST_POSTGRES_URL") {
+                let options: PgConnectOptions = url.parse().expect("Invalid FIRDS_TEST_POSTGRES_URL");
+                let database = format!("firds_test_{}", name.replace('-', "_"));
+                let mut conn = PgConnection::connect_with(&options).await.expect(NO_SERVER);
+                conn.execute(format!("DROP DATABASE IF EXISTS {database}").as_str()).await.unwrap();
+                conn.execute(format!("CREATE DATABASE {database}").as_str()).await.unwrap();
+                return Some(Self { data_dir: None, options: options.database(&database) })
+            }
+            let data_dir = temp_dir().join(format!("firds-pg-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&data_dir);
+            let initdb = Command::new("initdb")
+                .arg("-D").arg(&data_dir)
+                .args(["-A", "trust", "-U", "firds", "--no-sync"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            if !initdb.is_ok_and(|s| s.success()) {
+                let _ = std::fs::remove_dir_all(&data_dir);
+                panic!("{NO_SERVER}")
+            }
+            let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+            let server = Self {
+                data_dir: Some(data_dir.clone()),
+                options: PgConnectOptions::new()
+                    .host("127.0.0.1")
+                    .port(port)
+                    .username("firds")
+                    .database("postgres"),
+            };
+            let started = Command::new("pg_ctl")
+                .arg("-D").arg(&data_dir)
+                .arg("-l").arg(data_dir.join("server.log"))
+                .arg("-o").arg(format!("-p {port} -k {} -c listen_addresses=127.0.0.1", data_dir.display()))
+                .args(["-w", "start"])
+                .stdout(Stdio::null())
+                .status();
+            assert!(started.is_ok_and(|s| s.success()), "{NO_SERVER}");
+            Some(server)
+        }
+
+        /// Connect to the test database and create the tables.
+        async fn pool(&self) -> PgPool {
+            let pool = PgPoolOptions::new()
+                .max_connections(2)
+                .connect_with(self.options.clone())
+                .await
+                .expect("Could not connect to test PostgreSQL server");
+            init_db(&pool).await.expect("Could not initialise database");
+            pool
+        }
+    }
+
+    impl Drop for TestServer {
+        fn drop(&mut self) {
+            if let Some(data_dir) = &self.data_dir {
+                let _ = Command::new("pg_ctl")
+                    .arg("-D").arg(data_dir)
+                    .args(["-m", "immediate", "stop"])
+                    .stdout(Stdio::null())
+                    .status();
+                let _ = std::fs::remove_dir_all(data_dir);
+            }
+        }
+    }
+
+    fn bond(valid_from: NaiveDate) -> RefDataDbEntry {
+        let ref_data = ReferenceData::builder()
+            .isin(Isin::new_unchecked("DE0001102390"))
+            .full_name("Test bond")
+            .cfi("DBFTFB".parse().unwrap())
+            .issuer_lei(Lei::new_unchecked("5493001KJTIIGC8Y1R12"))
+            .trading_venue(Mic::new_unchecked("XETR"))
+            .notional_currency("EUR".parse().unwrap())
+            .technical_attributes(TechnicalAttributes {
+                relevant_competent_authority: Some("DE".to_string()),
+                publication_period: Some(PublicationPeriod { from_date: valid_from, to_date: None }),
+                relevant_trading_venue: Some(Mic::new_unchecked("XETR")),
+            })
+            .debt_attributes(DebtAttributes {
+                total_issued_amount: 1_000_000.0,
+                maturity_date: NaiveDate::from_ymd_opt(2030, 2, 15),
+                nominal_currency: "EUR".parse().unwrap(),
+                nominal_value_per_unit: 1000.0,
+                interest_rate: InterestRate::Floating(
+                    FloatingRate {
+                        name: Some(IndexName::Code(IndexCode::Euribor)),
+                        term: Some(Term { number: 6, unit: TermUnit::Month }),
+                    },
+                    Some(50)
+                ),
+                seniority: None,
+            })
+            .build()
+            .unwrap();
+        RefDataDbEntry::new_latest(ref_data).unwrap()
+    }
+
+    /// A wheat future, with commodity derivative attributes.
+    fn wheat_future() -> RefDataDbEntry {
+        let ref_data = ReferenceData::builder()
+            .isin(Isin::new_unchecked("DE000F1KXNY9"))
+            .full_name("Wheat future")
+            .cfi("FCECSX".parse().unwrap())
+            .commodities_derivative(true)
+            .issuer_lei(Lei::new_unchecked("529900UT4DG0LG5R9O07"))
+            .trading_venue(Mic::new_unchecked("XEUR"))
+            .notional_currency("EUR".parse().unwrap())
+            .derivative_attributes(DerivativeAttributes {
+                expiry_date: NaiveDate::from_ymd_opt(2026, 9, 10),
+                price_multiplier: Some(50.0),
+                delivery_type: Some(DeliveryType::Physical),
+                asset_class_specific_attributes: Some(AssetClassSpecificAttributes {
+                    commodity_attributes: Some(CommodityDerivativeAttributes {
+                        product: BaseProduct::try_from_codes("AGRI", Some("GROS"), Some("FWHT")).unwrap(),
+                        transaction_type: Some(TransactionType::Futures),
+                        final_price_type: Some(FinalPriceType::Exchange),
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .build_unchecked()
+            .unwrap();
+        RefDataDbEntry::new(ref_data, true, NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(), None)
+    }
+
+    async fn store(pool: &PgPool, entries: &[RefDataDbEntry]) -> Vec<i64> {
+        let mut tx = pool.begin().await.unwrap();
+        let mut ids = vec![];
+        for entry in entries {
+            ids.push(entry.to_db(&mut tx).await.expect("Could not serialise to DB"));
+        }
+        tx.commit().await.unwrap();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_postgres_to_db() {
+        let Some(server) = TestServer::start("to-db").await else { return };
+        let pool = server.pool().await;
+        // Initialising an existing database should be a no-op.
+        init_db(&pool).await.expect("Could not re-initialise database");
+
+        let first = bond(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap());
+        let second = bond(NaiveDate::from_ymd_opt(2025, 2, 4).unwrap());
+        let mut tx = pool.begin().await.unwrap();
+        first.to_db(&mut tx).await.expect("Could not serialise to DB");
+        assert_eq!(second.mark_prev_record(&mut tx).await.unwrap(), 1);
+        let id = second.to_db(&mut tx).await.expect("Could not serialise to DB");
+        tx.commit().await.unwrap();
+
+        let rows: Vec<(i64, bool, NaiveDate, Option<NaiveDate>)> = sqlx::query_as(
+            "SELECT id, latest_record, valid_from, valid_to FROM ReferenceData ORDER BY id"
+        ).fetch_all(&pool).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(!rows[0].1);
+        assert_eq!(rows[0].3, NaiveDate::from_ymd_opt(2025, 2, 3));
+        assert_eq!(rows[1], (id, true, second.valid_from, None));
+
+        let rate: (String, i32, String) = sqlx::query_as(
+            r#"
+                SELECT FloatingRate.name, Term.number, Term.unit FROM ReferenceData
+                JOIN DebtAttributes ON ReferenceData.debt_attributes_id = DebtAttributes.id
+                JOIN InterestRate ON DebtAttributes.interest_rate_id = InterestRate.id
+                JOIN FloatingRate ON InterestRate.floating_rate_id = FloatingRate.id
+                JOIN Term ON FloatingRate.term_id = Term.id
+                WHERE ReferenceData.id = $1
+            "#
+        ).bind(id).fetch_one(&pool).await.unwrap();
+        assert_eq!(rate, ("EURO".to_string(), 6, "MNTH".to_string()));
+        // Both records should share the same rate.
+        let rate_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM FloatingRate").fetch_one(&pool).await.unwrap();
+        assert_eq!(rate_count, 1);
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_postgres_derivatives() {
+        let Some(server) = TestServer::start("derivatives").await else { return };
+        let pool = server.pool().await;
+        // A bond, an interest rate swap on an index and an option on a basket, then a commodity
+        // future.
+        let mut entries = records(3);
+        entries.push(wheat_future());
+        store(&pool, &entries).await;
+
+        let classes: Vec<(String, String)> = sqlx::query_as(
+            "SELECT isin, instrument_class FROM ReferenceData ORDER BY id"
+        ).fetch_all(&pool).await.unwrap();
+        assert_eq!(
+            classes.iter().map(|(_, c)| c.as_str()).collect::<Vec<_>>(),
+            vec!["BOND", "SWAP", "OPTN", "CMDT"]
+        );
+
+        // The swap's underlying index, reference rate and second leg share one EURIBOR 3M row,
+        // separate from the bond's EURIBOR 6M.
+        let rates: Vec<(String, i32)> = sqlx::query_as(
+            r#"
+                SELECT FloatingRate.name, Term.number FROM FloatingRate
+                JOIN Term ON FloatingRate.term_id = Term.id
+                ORDER BY Term.number
+            "#
+        ).fetch_all(&pool).await.unwrap();
+        assert_eq!(rates, vec![("EURO".to_string(), 3), ("EURO".to_string(), 6)]);
+        let swap: (Option<f64>, String, i32) = sqlx::query_as(
+            r#"
+                SELECT InterestRate.fixed, FloatingRate.name, Term.number FROM ReferenceData
+                JOIN DerivativeAttributes ON ReferenceData.derivative_attributes_id = DerivativeAttributes.id
+                JOIN AssetClassSpecificAttributes
+                    ON DerivativeAttributes.asset_class_specific_attributes_id = AssetClassSpecificAttributes.id
+                JOIN InterestRateDerivativeAttributes
+                    ON AssetClassSpecificAttributes.ir_attributes_id = InterestRateDerivativeAttributes.id
+                JOIN InterestRate ON InterestRateDerivativeAttributes.interest_rate_1_id = InterestRate.id
+                JOIN FloatingRate ON InterestRateDerivativeAttributes.reference_rate_id = FloatingRate.id
+                JOIN Term ON FloatingRate.term_id = Term.id
+                WHERE ReferenceData.isin = $1
+            "#
+        ).bind(entries[1].ref_data.isin.as_str()).fetch_one(&pool).await.unwrap();
+        assert_eq!(swap, (Some(2.5), "EURO".to_string(), 3));
+
+        // The option's basket and strike price.
+        let basket: Vec<String> = sqlx::query_scalar(
+            r#"
+                SELECT UnderlyingBasketIsin.isin FROM ReferenceData
+                JOIN DerivativeAttributes ON ReferenceData.derivative_attributes_id = DerivativeAttributes.id
+                JOIN DerivativeUnderlying ON DerivativeAttributes.underlying_id = DerivativeUnderlying.id
+                JOIN UnderlyingBasketIsin ON DerivativeUnderlying.basket_id = UnderlyingBasketIsin.basket_id
+                WHERE ReferenceData.isin = $1
+                ORDER BY UnderlyingBasketIsin.isin
+            "#
+        ).bind(entries[2].ref_data.isin.as_str()).fetch_all(&pool).await.unwrap();
+        assert_eq!(basket, vec!["GB0002634946", "US0378331005"]);
+        let strike: (String, Option<f64>, Option<String>, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT StrikePrice.price_type, StrikePrice.price, StrikePrice.currency, DerivativeAttributes.option_type
+                FROM ReferenceData
+                JOIN DerivativeAttributes ON ReferenceData.derivative_attributes_id = DerivativeAttributes.id
+                JOIN StrikePrice ON DerivativeAttributes.strike_price_id = StrikePrice.id
+                WHERE ReferenceData.isin = $1
+            "#
+        ).bind(entries[2].ref_data.isin.as_str()).fetch_one(&pool).await.unwrap();
+        assert_eq!(strike, ("MONETARY_VALUE".to_string(), Some(150.0), Some("USD".to_string()), Some("CALL".to_string())));
+
+        // The future's commodity attributes.
+        let commodity: (String, Option<String>, Option<String>, Option<String>, Option<String>) = sqlx::query_as(
+            r#"
+                SELECT product, subproduct, further_subproduct, transaction_type, final_price_type
+                FROM ReferenceData
+                JOIN DerivativeAttributes ON ReferenceData.derivative_attributes_id = DerivativeAttributes.id
+                JOIN AssetClassSpecificAttributes
+                    ON DerivativeAttributes.asset_class_specific_attributes_id = AssetClassSpecificAttributes.id
+                JOIN CommodityDerivativeAttributes
+                    ON AssetClassSpecificAttributes.commodity_attributes_id = CommodityDerivativeAttributes.id
+                WHERE ReferenceData.isin = 'DE000F1KXNY9'
+            "#
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(commodity, (
+            "AGRI".to_string(),
+            Some("GROS".to_string()),
+            Some("FWHT".to_string()),
+            Some("FUTR".to_string()),
+            Some("EXOF".to_string())
+        ));
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_postgres_cancellation() {
+        let Some(server) = TestServer::start("cancellation").await else { return };
+        let pool = server.pool().await;
+        let entry = bond(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap());
+        store(&pool, std::slice::from_ref(&entry)).await;
+
+        let cancelled = CancelledRecord::new(entry.ref_data.isin.clone(), Mic::new_unchecked("XETR"));
+        let mut tx = pool.begin().await.unwrap();
+        assert_eq!(cancelled.mark_prev_record(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), &mut tx).await.unwrap(), 1);
+        // Only the latest record is affected, so cancelling again does nothing.
+        assert_eq!(cancelled.mark_prev_record(NaiveDate::from_ymd_opt(2025, 3, 2).unwrap(), &mut tx).await.unwrap(), 0);
+        // Nor does cancelling the instrument on another venue.
+        let other_venue = CancelledRecord::new(entry.ref_data.isin.clone(), Mic::new_unchecked("XLON"));
+        assert_eq!(other_venue.mark_prev_record(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), &mut tx).await.unwrap(), 0);
+        tx.commit().await.unwrap();
+
+        let row: (bool, NaiveDate, Option<NaiveDate>) = sqlx::query_as(
+            "SELECT latest_record, valid_from, valid_to FROM ReferenceData"
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(row, (false, entry.valid_from, NaiveDate::from_ymd_opt(2025, 3, 1)));
+        pool.close().await;
+    }
+
+    async fn shared_row_counts(pool: &PgPool) -> Vec<i64> {
+        let mut counts = vec![];
+        for table in ["Term", "FloatingRate", "FirdsIndex"] {
+            counts.push(sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}")).fetch_one(pool).await.unwrap());
+        }
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_postgres_upgrade() {
+        let Some(server) = TestServer::start("upgrade").await else { return };
+        let pool = server.pool().await;
+        store(&pool, &records(3)).await;
+        assert_eq!(shared_row_counts(&pool).await, [2, 2, 1]);
+
+        // Make the database look like one created before the shared rows were made unique and
+        // provenance was recorded, with a duplicate EURIBOR 3M rate (and term and index) used by
+        // the swap.
+        sqlx::raw_sql(
+            r#"
+                ALTER TABLE Term DROP CONSTRAINT term_number_unit_key;
+                ALTER TABLE FloatingRate DROP CONSTRAINT floatingrate_name_term_id_key;
+                ALTER TABLE FirdsIndex DROP CONSTRAINT firdsindex_isin_name_id_key;
+                ALTER TABLE ReferenceData DROP COLUMN source_file_id, DROP COLUMN record_type;
+                INSERT INTO Term (number, unit) VALUES (3, 'MNTH');
+                INSERT INTO FloatingRate (name, term_id) SELECT 'EURO', MAX(id) FROM Term;
+                UPDATE InterestRateDerivativeAttributes SET reference_rate_id = (SELECT MAX(id) FROM FloatingRate);
+                INSERT INTO FirdsIndex (isin, name_id) SELECT NULL, MAX(id) FROM FloatingRate;
+                UPDATE UnderlyingSingle SET index_id = (SELECT MAX(id) FROM FirdsIndex);
+            "#
+        ).execute(&pool).await.unwrap();
+        assert_eq!(shared_row_counts(&pool).await, [3, 3, 2]);
+
+        init_db(&pool).await.expect("Could not upgrade database");
+        assert_eq!(shared_row_counts(&pool).await, [2, 2, 1]);
+        let reference_rate: (String, i32) = sqlx::query_as(
+            r#"
+                SELECT FloatingRate.name, Term.number FROM InterestRateDerivativeAttributes
+                JOIN FloatingRate ON InterestRateDerivativeAttributes.reference_rate_id = FloatingRate.id
+                JOIN Term ON FloatingRate.term_id = Term.id
+            "#
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(reference_rate, ("EURO".to_string(), 3));
+
+        // The constraints are back, so storing the same records again shares the existing rows,
+        // and the provenance columns can be written.
+        let mut tx = pool.begin().await.unwrap();
+        let source_file_id = SourceFile::new("ESMA", "FULINS", "FULINS_D_20250201_01of01.zip")
+            .to_db(&mut tx)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        let entries: Vec<_> = records(3)
+            .into_iter()
+            .map(|e| e.with_source(source_file_id, RecordType::Full))
+            .collect();
+        store(&pool, &entries).await;
+        assert_eq!(shared_row_counts(&pool).await, [2, 2, 1]);
+        pool.close().await;
+    }
+}
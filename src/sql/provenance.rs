@@ -0,0 +1,208 @@
+//! The provenance of the records in a database, ie, the published FIRDS file and type of record
+//! from which each `ReferenceData` row was loaded.
+//!
+//! To record the provenance of the records being loaded, store the [`SourceFile`] with
+//! [`ToDb`](crate::sql::ToDb) to get its ID, and attach it to each
+//! [`RefDataDbEntry`](crate::sql::RefDataDbEntry) with
+//! [`with_source`](crate::sql::RefDataDbEntry::with_source) before storing the entry. Storing the
+//! same file again returns the ID of the existing row. With the `download` feature, a
+//! [`SourceFile`] can be created from the [`FirdsDoc`](crate::download::FirdsDoc) that the file
+//! was downloaded from.
+//!
+//! When records are read back with [`FromDb`](crate::sql::FromDb), the entry's `source` holds the
+//! ID of the file, which can itself be read back with [`FromDb`](crate::sql::FromDb).
+
+use crate::sql::error::SqlError;
+use chrono::{DateTime, Utc};
+use strum_macros::{Display, EnumString};
+
+/// The type of record in a FIRDS file from which a database row was created.
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq, Eq)]
+pub enum RecordType {
+    /// A record in a full (FULINS) file.
+    #[strum(serialize = "FULL")]
+    Full,
+    /// A new record (`NewRcrd`) in a delta (DLTINS) file.
+    #[strum(serialize = "NEWR")]
+    New,
+    /// A modified record (`ModfdRcrd`) in a delta file.
+    #[strum(serialize = "MODF")]
+    Modified,
+    /// A terminated record (`TermntdRcrd`) in a delta file.
+    #[strum(serialize = "TERM")]
+    Terminated,
+}
+
+/// The types of FIRDS file, with which their names begin.
+const FILE_TYPES: [&str; 3] = ["FULINS", "DLTINS", "FULCAN"];
+
+/// A published FIRDS file from which records were loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+    /// The publisher of the file ("ESMA" or "FCA").
+    pub source: String,
+    /// The type of the file ("FULINS", "DLTINS" or "FULCAN").
+    pub file_type: String,
+    /// The name of the file as published. Together with `source`, this identifies the file.
+    pub file_name: String,
+    /// The publisher's ID for the file, if known.
+    pub file_id: Option<String>,
+    /// The URL the file was downloaded from, if known.
+    pub download_link: Option<String>,
+    /// The publication timestamp of the file, if known.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The MD5 checksum of the file published by ESMA, if known.
+    pub checksum: Option<String>,
+}
+
+impl SourceFile {
+    /// Create a new source file with the given publisher, file type and file name, and no other
+    /// details.
+    pub fn new(source: impl Into<String>, file_type: impl Into<String>, file_name: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            file_type: file_type.into(),
+            file_name: file_name.into(),
+            file_id: None,
+            download_link: None,
+            timestamp: None,
+            checksum: None,
+        }
+    }
+
+    /// Create a new source file from the name of a file published by `source`, eg,
+    /// `FULINS_D_20250201_01of02.zip`, taking the file type from the name. ESMA and the FCA name
+    /// their files the same way, so the publisher can't be determined from the name.
+    ///
+    /// Files are published as ZIP archives, so the name of an extracted `.xml` file is recorded
+    /// with a `.zip` extension, to identify the same file as one recorded when it was downloaded.
+    pub fn from_file_name(source: impl Into<String>, file_name: &str) -> Result<Self, SqlError> {
+        let file_type = FILE_TYPES.into_iter()
+            .find(|t| file_name.strip_prefix(t).is_some_and(|rest| rest.starts_with('_')))
+            .ok_or_else(|| SqlError::BadFileName(file_name.to_owned()))?;
+        let file_name = match file_name.strip_suffix(".xml") {
+            Some(stem) => format!("{stem}.zip"),
+            None => file_name.to_owned()
+        };
+        Ok(Self::new(source, file_type, file_name))
+    }
+}
+
+#[cfg(feature = "download")]
+impl From<&crate::download::FirdsDoc> for SourceFile {
+    fn from(doc: &crate::download::FirdsDoc) -> Self {
+        Self {
+            source: doc.source.to_string(),
+            file_type: doc.file_type.to_string(),
+            file_name: doc.file_name.clone(),
+            file_id: Some(doc.file_id.clone()),
+            download_link: Some(doc.download_link.clone()),
+            timestamp: Some(doc.timestamp.to_utc()),
+            checksum: doc.checksum.clone(),
+        }
+    }
+}
+
+/// The file and type of record from which a database row was loaded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RecordSource {
+    /// The ID of the [`SourceFile`] row.
+    pub source_file_id: i64,
+    /// The type of record in the file.
+    pub record_type: RecordType,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::{memory_pool, records};
+    use crate::sql::{
+        bulk_load,
+        export_snapshot_db,
+        BulkLoadOptions,
+        FromDb,
+        RecordSource,
+        RecordType,
+        RefDataDbEntry,
+        SourceFile,
+        SqlError,
+        ToDb
+    };
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use sqlx::SqlitePool;
+
+    async fn sources(pool: &SqlitePool) -> Vec<Option<RecordSource>> {
+        let mut conn = pool.acquire().await.unwrap();
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM ReferenceData ORDER BY isin, id")
+            .fetch_all(&mut *conn).await.unwrap();
+        let mut sources = vec![];
+        for id in ids {
+            sources.push(RefDataDbEntry::from_db(id, &mut conn).await.unwrap().source);
+        }
+        sources
+    }
+
+    #[tokio::test]
+    async fn test_provenance() {
+        let pool = memory_pool().await;
+        let fulins = SourceFile {
+            file_id: Some("12345".to_string()),
+            timestamp: Some(Utc.with_ymd_and_hms(2025, 2, 1, 7, 30, 0).unwrap()),
+            checksum: Some("0123456789abcdef0123456789abcdef".to_string()),
+            ..SourceFile::new("ESMA", "FULINS", "FULINS_D_20250201_01of01.zip")
+        };
+        let dltins = SourceFile::new("ESMA", "DLTINS", "DLTINS_20250301_01of01.zip");
+
+        // Storing the same file again returns the existing row.
+        let mut tx = pool.begin().await.unwrap();
+        let fulins_id = fulins.to_db(&mut tx).await.unwrap();
+        assert_eq!(fulins.to_db(&mut tx).await.unwrap(), fulins_id);
+        let dltins_id = dltins.to_db(&mut tx).await.unwrap();
+        assert_ne!(dltins_id, fulins_id);
+        tx.commit().await.unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        bulk_load(
+            &mut conn,
+            records(2).into_iter().map(|e| e.with_source(fulins_id, RecordType::Full)),
+            BulkLoadOptions::default()
+        ).await.unwrap();
+        drop(conn);
+        let mut tx = pool.begin().await.unwrap();
+        let mut modified = records(1).pop().unwrap().with_source(dltins_id, RecordType::Modified);
+        modified.valid_from = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        modified.mark_prev_record(&mut tx).await.unwrap();
+        modified.to_db(&mut tx).await.unwrap();
+        // Records loaded without a source have none.
+        records(3).pop().unwrap().to_db(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let full = Some(RecordSource { source_file_id: fulins_id, record_type: RecordType::Full });
+        let modified = Some(RecordSource { source_file_id: dltins_id, record_type: RecordType::Modified });
+        assert_eq!(sources(&pool).await, vec![full, modified, full, None]);
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(SourceFile::from_db(fulins_id, &mut conn).await.unwrap(), fulins);
+        assert_eq!(SourceFile::from_db(dltins_id, &mut conn).await.unwrap(), dltins);
+        drop(conn);
+
+        // Snapshots keep the provenance of the records.
+        let dest = memory_pool().await;
+        export_snapshot_db(NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(), &pool, &dest, BulkLoadOptions::default())
+            .await.unwrap();
+        assert_eq!(sources(&dest).await, vec![full, full, None]);
+        let mut conn = dest.acquire().await.unwrap();
+        assert_eq!(SourceFile::from_db(fulins_id, &mut conn).await.unwrap(), fulins);
+    }
+
+    #[test]
+    fn test_source_file_from_file_name() {
+        let file = SourceFile::from_file_name("FCA", "FULINS_D_20250201_01of02.zip").unwrap();
+        assert_eq!(file, SourceFile::new("FCA", "FULINS", "FULINS_D_20250201_01of02.zip"));
+        let file = SourceFile::from_file_name("ESMA", "DLTINS_20250203_01of01.xml").unwrap();
+        assert_eq!(file, SourceFile::new("ESMA", "DLTINS", "DLTINS_20250203_01of01.zip"));
+        assert!(matches!(
+            SourceFile::from_file_name("ESMA", "FULINSX_D_20250201_01of02.zip"),
+            Err(SqlError::BadFileName(_))
+        ));
+        assert!(SourceFile::from_file_name("ESMA", "instruments.xml").is_err());
+    }
+}
@@ -0,0 +1,534 @@
+//! Reconciling an SQLite database against a full (FULINS) snapshot of the FIRDS data.
+//!
+//! A database that is kept up to date by applying delta (DLTINS) files can drift from the FIRDS
+//! data, for example if a delta file is missed or applied twice. A [`Reconciler`] compares the
+//! records in a fresh set of FULINS files with the latest records in the database, one record at a
+//! time, and reports the instruments which are missing from the database, which are in the
+//! database but not in the FULINS files, or whose records differ (with the differing fields).
+//! It can optionally repair the database to match the FULINS files as it goes.
+//!
+//! FULINS files are published per CFI category, so the instruments in the database are only
+//! reported as extra if their CFI category appeared in the records reconciled. Reconciling only
+//! the debt (`FULINS_D`) files, for example, does not report every equity in the database as
+//! extra. Within each category, the complete set of files must be reconciled.
+
+use crate::sql::error::SqlError;
+use crate::sql::from_db::FromDb;
+use crate::sql::maintenance::delete_orphaned_rows;
+use crate::sql::provenance::RecordType;
+use crate::sql::snapshot::{describe_floating_rate, describe_interest_rate, describe_strike_price};
+use crate::sql::to_db::{RefDataDbEntry, ToDb};
+use crate::*;
+use chrono::{Duration, NaiveDate};
+use sqlx::SqliteTransaction;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// A field whose value differs between the database and the FULINS files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// The path of the field within [`ReferenceData`], eg, `debt_attributes.maturity_date`.
+    pub field: String,
+    /// The value in the database, or `None` if the field is not populated.
+    pub db_value: Option<String>,
+    /// The value in the FULINS files, or `None` if the field is not populated.
+    pub fulins_value: Option<String>,
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn opt(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or("(none)")
+        }
+        write!(f, "{}: {} -> {}", self.field, opt(&self.db_value), opt(&self.fulins_value))
+    }
+}
+
+/// A difference between the database and the FULINS files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The instrument is in the FULINS files but has no latest record in the database.
+    Missing { isin: String, mic: String },
+    /// The instrument has a latest record in the database but is not in the FULINS files. Contains
+    /// the ID of the record.
+    Extra { isin: String, mic: String, id: i64 },
+    /// The latest record of the instrument in the database differs from the FULINS files. Contains
+    /// the ID of the record and the fields which differ.
+    Different { isin: String, mic: String, id: i64, diffs: Vec<FieldDiff> },
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { isin, mic } => write!(f, "{isin} on {mic} is missing from the database"),
+            Self::Extra { isin, mic, id } => write!(f, "{isin} on {mic} (record {id}) is not in the FULINS files"),
+            Self::Different { isin, mic, id, diffs } => {
+                write!(f, "{isin} on {mic} (record {id}) differs:")?;
+                for diff in diffs {
+                    write!(f, "\n    {diff}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The outcome of reconciling a database against a set of FULINS files.
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    /// The number of FULINS records compared with the database.
+    pub checked: u64,
+    /// The number of FULINS records which matched the latest record in the database.
+    pub matched: u64,
+    /// The differences found, in the order they were found. Extra instruments are found last.
+    pub discrepancies: Vec<Discrepancy>,
+    /// The number of discrepancies repaired (zero unless repair was requested).
+    pub repaired: u64,
+}
+
+/// Flatten a record into its field paths and (populated) values, for comparison. Nested attributes
+/// which the CSV export summarises are described the same way here.
+fn fields(rd: &ReferenceData) -> BTreeMap<&'static str, String> {
+    let mut fields = BTreeMap::new();
+    let mut push = |field: &'static str, value: Option<String>| {
+        if let Some(value) = value {
+            fields.insert(field, value);
+        }
+    };
+    fn opt(value: Option<impl ToString>) -> Option<String> {
+        value.map(|v| v.to_string())
+    }
+    push("full_name", Some(rd.full_name.clone()));
+    push("cfi", Some(rd.cfi.to_string()));
+    push("is_commodities_derivative", Some(rd.is_commodities_derivative.to_string()));
+    push("issuer_lei", Some(rd.issuer_lei.to_string()));
+    push("fisn", Some(rd.fisn.to_string()));
+    push("notional_currency", Some(rd.notional_currency.to_string()));
+
+    let tv = &rd.trading_venue_attrs;
+    push("trading_venue_attrs.requested_admission", Some(tv.requested_admission.to_string()));
+    push("trading_venue_attrs.approval_date", opt(tv.approval_date));
+    push("trading_venue_attrs.request_date", opt(tv.request_date));
+    push("trading_venue_attrs.admission_or_first_trade_date", opt(tv.admission_or_first_trade_date));
+    push("trading_venue_attrs.termination_date", opt(tv.termination_date));
+
+    if let Some(tech) = &rd.technical_attributes {
+        push("technical_attributes.relevant_competent_authority", tech.relevant_competent_authority.clone());
+        let period = tech.publication_period.as_ref();
+        push("technical_attributes.publication_period.from_date", opt(period.map(|p| p.from_date)));
+        push("technical_attributes.publication_period.to_date", opt(period.and_then(|p| p.to_date)));
+        push("technical_attributes.relevant_trading_venue", opt(tech.relevant_trading_venue.as_ref()));
+    }
+
+    if let Some(debt) = &rd.debt_attributes {
+        push("debt_attributes.total_issued_amount", Some(debt.total_issued_amount.to_string()));
+        push("debt_attributes.maturity_date", opt(debt.maturity_date));
+        push("debt_attributes.nominal_currency", Some(debt.nominal_currency.to_string()));
+        push("debt_attributes.nominal_value_per_unit", Some(debt.nominal_value_per_unit.to_string()));
+        push("debt_attributes.interest_rate", Some(describe_interest_rate(&debt.interest_rate)));
+        push("debt_attributes.seniority", opt(debt.seniority));
+    }
+
+    if let Some(deriv) = &rd.derivative_attributes {
+        push("derivative_attributes.expiry_date", opt(deriv.expiry_date));
+        push("derivative_attributes.price_multiplier", opt(deriv.price_multiplier));
+        push("derivative_attributes.underlying", deriv.underlying.as_ref().map(describe_underlying));
+        push("derivative_attributes.option_type", opt(deriv.option_type));
+        push("derivative_attributes.strike_price", deriv.strike_price.as_ref().map(describe_strike_price));
+        push("derivative_attributes.option_exercise_style", opt(deriv.option_exercise_style));
+        push("derivative_attributes.delivery_type", opt(deriv.delivery_type));
+        let ac_attrs = deriv.asset_class_specific_attributes.as_ref();
+        if let Some(comm) = ac_attrs.and_then(|a| a.commodity_attributes.as_ref()) {
+            push("derivative_attributes.commodity_attributes.product", Some(describe_product(&comm.product)));
+            push("derivative_attributes.commodity_attributes.transaction_type", opt(comm.transaction_type));
+            push("derivative_attributes.commodity_attributes.final_price_type", opt(comm.final_price_type));
+        }
+        if let Some(ir) = ac_attrs.and_then(|a| a.ir_attributes.as_ref()) {
+            push("derivative_attributes.ir_attributes.reference_rate", Some(describe_floating_rate(&ir.reference_rate)));
+            push("derivative_attributes.ir_attributes.interest_rate_1", ir.interest_rate_1.as_ref().map(describe_interest_rate));
+            push("derivative_attributes.ir_attributes.notional_currency_2", opt(ir.notional_currency_2));
+            push("derivative_attributes.ir_attributes.interest_rate_2", ir.interest_rate_2.as_ref().map(describe_interest_rate));
+        }
+        if let Some(fx) = ac_attrs.and_then(|a| a.fx_attributes.as_ref()) {
+            push("derivative_attributes.fx_attributes.notional_currency_2", opt(fx.notional_currency_2));
+            push("derivative_attributes.fx_attributes.fx_type", opt(fx.fx_type));
+        }
+    }
+    fields
+}
+
+/// The ESMA codes of a commodity product, eg `AGRI/GROS/FWHT`.
+fn describe_product(product: &BaseProduct) -> String {
+    let (product, subproduct, further_subproduct) = product.to_codes();
+    [Some(product), subproduct, further_subproduct].into_iter().flatten()
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+fn describe_underlying(underlying: &DerivativeUnderlying) -> String {
+    match underlying {
+        DerivativeUnderlying::Single(UnderlyingSingle::Isin(isin)) => isin.to_string(),
+        DerivativeUnderlying::Single(UnderlyingSingle::Lei(lei)) => lei.to_string(),
+        DerivativeUnderlying::Single(UnderlyingSingle::Index(index)) => match &index.isin {
+            Some(isin) => format!("{isin} {}", describe_floating_rate(&index.name)),
+            None => describe_floating_rate(&index.name)
+        },
+        DerivativeUnderlying::Basket(basket) => basket.isin.iter().map(Isin::to_string)
+            .chain(basket.issuer_lei.iter().map(Lei::to_string))
+            .collect::<Vec<String>>()
+            .join(";")
+    }
+}
+
+/// Compare two records field by field, returning the fields which differ.
+fn diff(db: &ReferenceData, fulins: &ReferenceData) -> Vec<FieldDiff> {
+    let mut db_fields = fields(db);
+    let mut diffs = vec![];
+    for (field, fulins_value) in fields(fulins) {
+        let db_value = db_fields.remove(field);
+        if db_value.as_ref() != Some(&fulins_value) {
+            diffs.push(FieldDiff { field: field.to_string(), db_value, fulins_value: Some(fulins_value) });
+        }
+    }
+    for (field, db_value) in db_fields {
+        diffs.push(FieldDiff { field: field.to_string(), db_value: Some(db_value), fulins_value: None });
+    }
+    diffs.sort_by(|a, b| a.field.cmp(&b.field));
+    diffs
+}
+
+/// Compares FULINS records with the latest records in an SQLite database. See the
+/// [module documentation](self) for details.
+///
+/// Like [`BulkLoader`](crate::sql::BulkLoader), the reconciler works in a transaction provided by
+/// the caller, which should be committed after [`Reconciler::finish`] is called if the database
+/// was repaired.
+///
+/// When repairing, the FULINS files are taken to be correct as of the `as_of` date (normally their
+/// publication date). Missing instruments and differing records are stored as new latest records
+/// valid from that date, closing any previous record the day before, and extra instruments are
+/// closed the day before as if they had been cancelled. A differing or extra record which itself
+/// only became valid on the `as_of` date is deleted instead of being closed, as it was never valid
+/// as far as the FULINS files are concerned.
+///
+/// The IDs of the latest records which matched (or replaced) a FULINS record are kept in a
+/// temporary table, `ReconciledRecord`, rather than in memory, so that the extra records can be
+/// found with a single query however large the database is.
+pub struct Reconciler {
+    as_of: NaiveDate,
+    repair: bool,
+    /// The ID of the [`SourceFile`](crate::sql::SourceFile) to record as the source of repaired
+    /// records, if any.
+    source_file_id: Option<i64>,
+    /// Whether the `ReconciledRecord` table has been created (and emptied) for this reconciler.
+    started: bool,
+    /// Whether any records were deleted when repairing, leaving rows to be cleaned up.
+    deleted: bool,
+    /// The CFI categories of the FULINS records reconciled.
+    categories: HashSet<char>,
+    report: ReconcileReport,
+}
+
+/// The number of extra records fetched at a time by [`Reconciler::finish`].
+const EXTRA_PAGE_SIZE: i64 = 1000;
+
+/// Record that the latest record with the given ID matched (or replaced) a FULINS record.
+async fn mark_reconciled(id: i64, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+    sqlx::query("INSERT OR IGNORE INTO temp.ReconciledRecord (id) VALUES (?)")
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+async fn delete_record(id: i64, tx: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+    sqlx::query("DELETE FROM ReferenceData WHERE id = ?")
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+impl Reconciler {
+    /// Create a new reconciler for FULINS files published on `as_of`, which repairs the database
+    /// if `repair` is true.
+    pub fn new(as_of: NaiveDate, repair: bool) -> Self {
+        Self {
+            as_of,
+            repair,
+            source_file_id: None,
+            started: false,
+            deleted: false,
+            categories: HashSet::new(),
+            report: ReconcileReport::default(),
+        }
+    }
+
+    /// Record the records stored when repairing as loaded from the
+    /// [`SourceFile`](crate::sql::SourceFile) with the given ID. When reconciling several files,
+    /// this can be called again before adding the records of each one.
+    pub fn with_source_file(mut self, source_file_id: i64) -> Self {
+        self.source_file_id = Some(source_file_id);
+        self
+    }
+
+    /// Compare a FULINS record with the latest record of the same ISIN and MIC in the database,
+    /// returning the discrepancy found, if any.
+    pub async fn add(
+        &mut self,
+        ref_data: ReferenceData,
+        tx: &mut SqliteTransaction<'_>
+    ) -> Result<Option<Discrepancy>, SqlError> {
+        if !self.started {
+            sqlx::query("CREATE TEMP TABLE IF NOT EXISTS ReconciledRecord (id INTEGER PRIMARY KEY)")
+                .execute(&mut **tx)
+                .await?;
+            sqlx::query("DELETE FROM temp.ReconciledRecord").execute(&mut **tx).await?;
+            self.started = true;
+        }
+        self.report.checked += 1;
+        self.categories.insert(ref_data.cfi.category().code());
+        let isin = ref_data.isin.to_string();
+        let mic = ref_data.venue().to_string();
+        let id: Option<i64> = sqlx::query_scalar(
+            r#"
+                SELECT ReferenceData.id FROM ReferenceData
+                JOIN TradingVenueAttributes ON ReferenceData.trading_venue_attrs_id = TradingVenueAttributes.id
+                WHERE ReferenceData.isin = ? AND TradingVenueAttributes.trading_venue = ?
+                    AND ReferenceData.latest_record
+                ORDER BY ReferenceData.id DESC
+                LIMIT 1
+            "#
+        )
+            .bind(&isin)
+            .bind(&mic)
+            .fetch_optional(&mut **tx)
+            .await?;
+        // The ID of a differing record which became valid on the `as_of` date, and so is replaced
+        // rather than closed when repairing.
+        let mut replaced = None;
+        let discrepancy = match id {
+            Some(id) => {
+                mark_reconciled(id, tx).await?;
+                let db_entry = RefDataDbEntry::from_db(id, tx).await?;
+                let diffs = diff(&db_entry.ref_data, &ref_data);
+                if diffs.is_empty() {
+                    self.report.matched += 1;
+                    return Ok(None);
+                }
+                if db_entry.valid_from == self.as_of {
+                    replaced = Some(id);
+                }
+                Discrepancy::Different { isin, mic, id, diffs }
+            },
+            None => Discrepancy::Missing { isin, mic }
+        };
+        if self.repair {
+            let mut entry = RefDataDbEntry::new(ref_data, true, self.as_of, None);
+            if let Some(source_file_id) = self.source_file_id {
+                entry = entry.with_source(source_file_id, RecordType::Full);
+            }
+            if let Some(id) = replaced {
+                delete_record(id, tx).await?;
+                self.deleted = true;
+            }
+            entry.mark_prev_record(tx).await?;
+            let new_id = entry.to_db(tx).await?;
+            mark_reconciled(new_id, tx).await?;
+            self.report.repaired += 1;
+        }
+        self.report.discrepancies.push(discrepancy.clone());
+        Ok(Some(discrepancy))
+    }
+
+    /// Find the instruments in the database which were not in the FULINS records (within the CFI
+    /// categories reconciled), closing them if repairing, and return the final report.
+    pub async fn finish(mut self, tx: &mut SqliteTransaction<'_>) -> Result<ReconcileReport, SqlError> {
+        if !self.started {
+            return Ok(self.report)
+        }
+        let categories: String = self.categories.iter().collect();
+        let valid_to = self.as_of - Duration::days(1);
+        let mut last_id = 0;
+        loop {
+            // Only the latest records which weren't reconciled are read, a page at a time.
+            let extra: Vec<(i64, String, String, bool)> = sqlx::query_as(
+                r#"
+                    SELECT ReferenceData.id, ReferenceData.isin, TradingVenueAttributes.trading_venue,
+                        ReferenceData.valid_from = ?
+                    FROM ReferenceData
+                    JOIN TradingVenueAttributes ON ReferenceData.trading_venue_attrs_id = TradingVenueAttributes.id
+                    WHERE ReferenceData.latest_record AND ReferenceData.id > ?
+                        AND instr(?, substr(ReferenceData.cfi, 1, 1)) > 0
+                        AND ReferenceData.id NOT IN (SELECT id FROM temp.ReconciledRecord)
+                    ORDER BY ReferenceData.id
+                    LIMIT ?
+                "#
+            )
+                .bind(self.as_of.to_string())
+                .bind(last_id)
+                .bind(&categories)
+                .bind(EXTRA_PAGE_SIZE)
+                .fetch_all(&mut **tx)
+                .await?;
+            let Some((id, ..)) = extra.last() else { break };
+            last_id = *id;
+            for (id, isin, mic, valid_from_as_of) in extra {
+                if self.repair {
+                    if valid_from_as_of {
+                        delete_record(id, tx).await?;
+                        self.deleted = true;
+                    } else {
+                        CancelledRecord::new(Isin::new_unchecked(&isin), Mic::new_unchecked(&mic))
+                            .mark_prev_record(valid_to, tx)
+                            .await?;
+                    }
+                    self.report.repaired += 1;
+                }
+                self.report.discrepancies.push(Discrepancy::Extra { isin, mic, id });
+            }
+        }
+        if self.deleted {
+            delete_orphaned_rows(tx).await?;
+        }
+        sqlx::query("DROP TABLE temp.ReconciledRecord").execute(&mut **tx).await?;
+        Ok(self.report)
+    }
+}
+
+/// Reconcile the latest records in the database with the given FULINS records, using a
+/// [`Reconciler`], and return the report.
+pub async fn reconcile(
+    records: impl IntoIterator<Item = ReferenceData>,
+    as_of: NaiveDate,
+    repair: bool,
+    tx: &mut SqliteTransaction<'_>
+) -> Result<ReconcileReport, SqlError> {
+    let mut reconciler = Reconciler::new(as_of, repair);
+    for ref_data in records {
+        reconciler.add(ref_data, tx).await?;
+    }
+    reconciler.finish(tx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::{memory_pool, records};
+    use crate::sql::reconcile::describe_product;
+    use crate::sql::{check_db, reconcile, Discrepancy, FieldDiff, Snapshot, ToDb};
+    use crate::BaseProduct;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_describe_product() {
+        let product = BaseProduct::try_from_codes("AGRI", Some("GROS"), Some("FWHT")).unwrap();
+        assert_eq!(describe_product(&product), "AGRI/GROS/FWHT");
+        let product = BaseProduct::try_from_codes("OTHR", None, None).unwrap();
+        assert_eq!(describe_product(&product), "OTHR");
+    }
+
+    #[tokio::test]
+    async fn test_reconcile() {
+        let pool = memory_pool().await;
+        // The database has instruments 0-4; the FULINS files have 1-5 (of which 5 is new), with
+        // instrument 2 renamed and 4 matured early.
+        let mut tx = pool.begin().await.unwrap();
+        for entry in records(5) {
+            entry.to_db(&mut tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+        let fulins = || records(6).into_iter().skip(1).map(|entry| {
+            let mut rd = entry.ref_data;
+            if rd.full_name == "Instrument 2" {
+                rd.full_name.push_str(" (renamed)");
+            }
+            if rd.full_name == "Instrument 4" {
+                rd.derivative_attributes.as_mut().unwrap().expiry_date = NaiveDate::from_ymd_opt(2030, 1, 1);
+            }
+            rd
+        });
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        let report = reconcile(fulins(), as_of, false, &mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!((report.checked, report.matched, report.repaired), (5, 2, 0));
+        let isin = |i: usize| format!("XS{i:010}");
+        let mic = "XETR".to_string();
+        assert_eq!(report.discrepancies, vec![
+            Discrepancy::Different { isin: isin(2), mic: mic.clone(), id: 3, diffs: vec![FieldDiff {
+                field: "full_name".to_string(),
+                db_value: Some("Instrument 2".to_string()),
+                fulins_value: Some("Instrument 2 (renamed)".to_string()),
+            }]},
+            Discrepancy::Different { isin: isin(4), mic: mic.clone(), id: 5, diffs: vec![FieldDiff {
+                field: "derivative_attributes.expiry_date".to_string(),
+                db_value: Some("2035-02-01".to_string()),
+                fulins_value: Some("2030-01-01".to_string()),
+            }]},
+            Discrepancy::Missing { isin: isin(5), mic: mic.clone() },
+            Discrepancy::Extra { isin: isin(0), mic: mic.clone(), id: 1 },
+        ]);
+
+        let mut tx = pool.begin().await.unwrap();
+        let report = reconcile(fulins(), as_of, true, &mut tx).await.unwrap();
+        assert_eq!(report.repaired, 4);
+        assert!(check_db(&mut tx).await.unwrap().is_empty());
+        let report = reconcile(fulins(), as_of, false, &mut tx).await.unwrap();
+        assert_eq!((report.checked, report.matched), (5, 5));
+        assert!(report.discrepancies.is_empty());
+        tx.commit().await.unwrap();
+
+        // Before the FULINS date, the database is as it was.
+        let mut conn = pool.acquire().await.unwrap();
+        let mut snapshot = Snapshot::new(as_of.pred_opt().unwrap());
+        let mut names = vec![];
+        while let Some(entry) = snapshot.next(&mut conn).await.unwrap() {
+            names.push(entry.ref_data.full_name);
+        }
+        assert_eq!(names, (0..5).map(|i| format!("Instrument {i}")).collect::<Vec<String>>());
+    }
+
+    #[tokio::test]
+    async fn test_repair_as_of_valid_from() {
+        let pool = memory_pool().await;
+        // The database has instruments 0-3, valid from the FULINS date; the FULINS files have 0
+        // (renamed), 1 and 2.
+        let mut tx = pool.begin().await.unwrap();
+        for entry in records(4) {
+            entry.to_db(&mut tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+        let as_of = records(1)[0].valid_from;
+        let fulins = || records(3).into_iter().map(|entry| {
+            let mut rd = entry.ref_data;
+            if rd.full_name == "Instrument 0" {
+                rd.full_name.push_str(" (renamed)");
+            }
+            rd
+        });
+
+        // The records which became valid on the FULINS date are replaced rather than closed the
+        // day before they became valid.
+        let mut tx = pool.begin().await.unwrap();
+        let report = reconcile(fulins(), as_of, true, &mut tx).await.unwrap();
+        assert_eq!((report.checked, report.matched, report.repaired), (3, 2, 2));
+        assert!(check_db(&mut tx).await.unwrap().is_empty());
+        let report = reconcile(fulins(), as_of, false, &mut tx).await.unwrap();
+        assert!(report.discrepancies.is_empty());
+        tx.commit().await.unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let mut snapshot = Snapshot::new(as_of);
+        let mut names = vec![];
+        while let Some(entry) = snapshot.next(&mut conn).await.unwrap() {
+            names.push(entry.ref_data.full_name);
+        }
+        assert_eq!(names, vec!["Instrument 1", "Instrument 2", "Instrument 0 (renamed)"]);
+        let records: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ReferenceData").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(records, 3);
+        // The attributes of the deleted bonds are deleted too.
+        let debt_attrs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM DebtAttributes").fetch_one(&mut *conn).await.unwrap();
+        assert_eq!(debt_attrs, 1);
+    }
+}
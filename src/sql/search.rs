@@ -0,0 +1,192 @@
+//! Full-text search over the names, FISNs and issuer LEIs of the instruments in an SQLite
+//! database.
+//!
+//! Search is optional, as the index adds to the size of the database and slows down loading. It is
+//! enabled with [`enable_search`], which creates an FTS5 index (the `InstrumentSearch` table) and
+//! triggers which keep it in sync with the `ReferenceData` table, so records loaded afterwards with
+//! [`ToDb`](crate::sql::ToDb) or a [`BulkLoader`](crate::sql::BulkLoader), or removed by
+//! [`prune_history`](crate::sql::prune_history), are indexed (or unindexed) automatically.
+//!
+//! [`search_instruments`] takes a query as a user would type it, such as `Siemens 2030` or
+//! `EURIBOR swap`, and matches the latest records containing words beginning with each of the
+//! terms, ranked by relevance.
+
+use crate::sql::error::SqlError;
+use crate::sql::from_db::FromDb;
+use crate::sql::to_db::RefDataDbEntry;
+use crate::ReferenceData;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const SEARCH_SCHEMA: &str = include_str!("../../sql/search.sql");
+
+const DROP_SEARCH: &str = r#"
+    DROP TRIGGER IF EXISTS InstrumentSearch_insert;
+    DROP TRIGGER IF EXISTS InstrumentSearch_delete;
+    DROP TRIGGER IF EXISTS InstrumentSearch_update;
+    DROP TABLE IF EXISTS InstrumentSearch;
+"#;
+
+/// Create the full-text search index and index the existing records. If search is already
+/// enabled, the index is rebuilt.
+pub async fn enable_search(pool: &SqlitePool) -> Result<(), SqlError> {
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(SEARCH_SCHEMA).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Drop the full-text search index, if it exists.
+pub async fn disable_search(pool: &SqlitePool) -> Result<(), SqlError> {
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(DROP_SEARCH).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Whether full-text search has been enabled for the database.
+pub async fn search_enabled(conn: &mut SqliteConnection) -> Result<bool, SqlError> {
+    Ok(sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'InstrumentSearch')"
+    ).fetch_one(conn).await?)
+}
+
+/// Convert a query as typed by a user into an FTS5 query which matches records containing words
+/// beginning with each of the terms. Each term is quoted, so characters which have a meaning in the
+/// FTS5 query syntax (such as `-`, `*` or `:`) are matched literally.
+fn fts_query(query: &str) -> String {
+    query.split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Search the latest records for instruments whose full name, FISN or issuer LEI match `query`,
+/// returning up to `limit` records, best matches first.
+///
+/// The query is split into terms on whitespace, and a record matches if it contains a word
+/// beginning with each term (ignoring case and diacritics). Returns
+/// [`SqlError::SearchNotEnabled`] if [`enable_search`] has not been called for the database.
+pub async fn search_instruments(
+    query: &str,
+    limit: u32,
+    conn: &mut SqliteConnection
+) -> Result<Vec<ReferenceData>, SqlError> {
+    if !search_enabled(conn).await? {
+        return Err(SqlError::SearchNotEnabled);
+    }
+    let query = fts_query(query);
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    let ids: Vec<i64> = sqlx::query_scalar(
+        r#"
+            SELECT ReferenceData.id FROM InstrumentSearch
+            JOIN ReferenceData ON ReferenceData.id = InstrumentSearch.rowid
+            WHERE InstrumentSearch MATCH ? AND ReferenceData.latest_record
+            ORDER BY InstrumentSearch.rank, ReferenceData.id
+            LIMIT ?
+        "#
+    )
+        .bind(query)
+        .bind(limit)
+        .fetch_all(&mut *conn)
+        .await?;
+    let mut results = Vec::with_capacity(ids.len());
+    for id in ids {
+        results.push(RefDataDbEntry::from_db(id, conn).await?.ref_data);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::{memory_pool, records};
+    use crate::sql::search::fts_query;
+    use crate::sql::{
+        bulk_load,
+        disable_search,
+        enable_search,
+        prune_history,
+        search_instruments,
+        BulkLoadOptions,
+        SqlError,
+        ToDb
+    };
+    use crate::Fisn;
+    use chrono::NaiveDate;
+    use sqlx::SqlitePool;
+
+    async fn names(pool: &SqlitePool, query: &str) -> Vec<String> {
+        let mut conn = pool.acquire().await.unwrap();
+        search_instruments(query, 10, &mut conn).await.unwrap()
+            .into_iter()
+            .map(|rd| rd.full_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("Siemens 2030"), r#""Siemens"* "2030"*"#);
+        assert_eq!(fts_query("  EUR-\"swap\" "), r#""EUR-""swap"""*"#);
+        assert_eq!(fts_query(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_search_instruments() {
+        let pool = memory_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(matches!(
+            search_instruments("Siemens", 10, &mut conn).await,
+            Err(SqlError::SearchNotEnabled)
+        ));
+        drop(conn);
+
+        // Records loaded before search is enabled are indexed when it is enabled; those loaded
+        // afterwards are indexed by the triggers.
+        let mut entries = records(4);
+        entries[0].ref_data.full_name = "Siemens AG 2.5% 2030".to_string();
+        entries[0].ref_data.fisn = Fisn::new("SIEMENS AG/2.5 BD 20300315");
+        entries[1].ref_data.full_name = "EURIBOR 3M swap".to_string();
+        entries[2].ref_data.full_name = "Siemens Energy call option".to_string();
+        let mut tx = pool.begin().await.unwrap();
+        entries[0].to_db(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        enable_search(&pool).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        bulk_load(&mut conn, entries.drain(1..), BulkLoadOptions::default()).await.unwrap();
+        drop(conn);
+
+        assert_eq!(names(&pool, "siemens").await, vec!["Siemens AG 2.5% 2030", "Siemens Energy call option"]);
+        assert_eq!(names(&pool, "Siemens 2030").await, vec!["Siemens AG 2.5% 2030"]);
+        assert_eq!(names(&pool, "euribor SWAP").await, vec!["EURIBOR 3M swap"]);
+        // FISNs and issuer LEIs are searched too.
+        assert_eq!(names(&pool, "siemens bd").await, vec!["Siemens AG 2.5% 2030"]);
+        assert_eq!(names(&pool, "5493001KJTIIGC8Y1R12").await.len(), 4);
+        assert!(names(&pool, "Siemens swap").await.is_empty());
+        assert!(names(&pool, " ").await.is_empty());
+
+        // Updated and deleted records are kept in sync.
+        sqlx::query("UPDATE ReferenceData SET full_name = 'Siemens Healthineers' WHERE full_name = 'EURIBOR 3M swap'")
+            .execute(&pool).await.unwrap();
+        assert!(names(&pool, "euribor").await.is_empty());
+        assert_eq!(names(&pool, "healthineers").await, vec!["Siemens Healthineers"]);
+        sqlx::query("UPDATE ReferenceData SET latest_record = 0, valid_to = '2025-02-28' WHERE full_name LIKE 'Siemens AG%'")
+            .execute(&pool).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        prune_history(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(), &mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert!(names(&pool, "2030").await.is_empty());
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM InstrumentSearch WHERE InstrumentSearch MATCH 'siemens'")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(count, 2);
+        sqlx::query("INSERT INTO InstrumentSearch (InstrumentSearch) VALUES ('integrity-check')")
+            .execute(&pool).await.unwrap();
+
+        disable_search(&pool).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(matches!(
+            search_instruments("Siemens", 10, &mut conn).await,
+            Err(SqlError::SearchNotEnabled)
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+//! Point-in-time snapshots of the records in an SQLite database.
+//!
+//! Each record in the database is valid from its `valid_from` date until its `valid_to` date
+//! (inclusive), or indefinitely if it is still the latest record for its ISIN and MIC. A snapshot
+//! as of a given date therefore consists of the records that were valid on that date, ie, the
+//! FIRDS reference data as it was known on that date.
+//!
+//! A snapshot can be read record by record with [`Snapshot`], or exported to a new SQLite
+//! database with [`export_snapshot_db`] or to a CSV file with [`export_snapshot_csv`]. (Export to
+//! FIRDS XML is not supported, as this crate does not write XML.)
+
+use crate::sql::bulk::{BulkLoadOptions, BulkLoader, LoadStats};
+use crate::sql::error::SqlError;
+use crate::sql::from_db::FromDb;
+use crate::sql::migrate::upgrade_db;
+use crate::sql::provenance::SourceFile;
+use crate::sql::to_db::RefDataDbEntry;
+use crate::*;
+use chrono::NaiveDate;
+use sqlx::{SqliteConnection, SqlitePool, SqliteTransaction};
+use std::collections::VecDeque;
+use std::io::Write;
+
+/// The number of record IDs fetched at a time by [`Snapshot`].
+const PAGE_SIZE: i64 = 1000;
+
+/// Reads the records that were valid on a given date, in the order they were added to the
+/// database.
+///
+/// Records are read in pages, so the snapshot can be read from a large database without loading
+/// every record into memory. Each page is read with a separate query, so if the database may be
+/// written to while the snapshot is being read, `conn` should be a transaction (eg, `&mut *tx`)
+/// which is kept open until the last record has been read. All of the pages are then read from
+/// the same version of the database.
+pub struct Snapshot {
+    date: String,
+    /// The ID of the last record read.
+    last_id: i64,
+    /// The IDs of the records in the current page which have not yet been read.
+    page: VecDeque<i64>,
+}
+
+impl Snapshot {
+    /// Create a snapshot of the records that were valid on `date`.
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date: date.to_string(),
+            last_id: 0,
+            page: VecDeque::new(),
+        }
+    }
+
+    /// Read the next record in the snapshot, returning `None` when all records have been read.
+    pub async fn next(&mut self, conn: &mut SqliteConnection) -> Result<Option<RefDataDbEntry>, SqlError> {
+        if self.page.is_empty() {
+            let ids: Vec<i64> = sqlx::query_scalar(
+                r#"
+                    SELECT id FROM ReferenceData
+                    WHERE id > ? AND valid_from <= ? AND (valid_to IS NULL OR valid_to >= ?)
+                    ORDER BY id
+                    LIMIT ?
+                "#
+            )
+                .bind(self.last_id)
+                .bind(&self.date)
+                .bind(&self.date)
+                .bind(PAGE_SIZE)
+                .fetch_all(&mut *conn)
+                .await?;
+            self.page = ids.into();
+        }
+        match self.page.pop_front() {
+            Some(id) => {
+                self.last_id = id;
+                Ok(Some(RefDataDbEntry::from_db(id, conn).await?))
+            },
+            None => Ok(None)
+        }
+    }
+}
+
+/// Copy all of the [`SourceFile`] rows from `src` to `dest`, keeping their IDs so that the
+/// records copied afterwards still refer to the right files.
+async fn copy_source_files(src: &mut SqliteConnection, dest: &mut SqliteTransaction<'_>) -> Result<(), SqlError> {
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM SourceFile ORDER BY id")
+        .fetch_all(&mut *src)
+        .await?;
+    for id in ids {
+        let file = SourceFile::from_db(id, src).await?;
+        sqlx::query(
+            r#"
+                INSERT INTO SourceFile (
+                    id, source, file_type, file_name, file_id, download_link, publication_timestamp,
+                    checksum
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+            .bind(id)
+            .bind(file.source)
+            .bind(file.file_type)
+            .bind(file.file_name)
+            .bind(file.file_id)
+            .bind(file.download_link)
+            .bind(file.timestamp.map(|t| t.to_string()))
+            .bind(file.checksum)
+            .execute(&mut **dest)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Copy the records that were valid on `date` from the database `src` to the database `dest`,
+/// which is initialised if necessary. The records keep their `valid_from` and `valid_to` dates, so
+/// the new database records when each one became (and, if applicable, ceased to be) valid. The
+/// source files from which the records were loaded are copied too.
+///
+/// `dest` should be empty, as existing records are not superseded (see [`BulkLoader`]). `src` is
+/// read in a single transaction, so the export is consistent even if `src` is written to during
+/// the export.
+pub async fn export_snapshot_db(
+    date: NaiveDate,
+    src: &SqlitePool,
+    dest: &SqlitePool,
+    options: BulkLoadOptions
+) -> Result<LoadStats, SqlError> {
+    upgrade_db(dest).await?;
+    let mut src_tx = src.begin().await?;
+    let mut tx = dest.begin().await?;
+    copy_source_files(&mut src_tx, &mut tx).await?;
+    let mut loader = BulkLoader::begin(options, &mut tx).await?;
+    let mut snapshot = Snapshot::new(date);
+    while let Some(entry) = snapshot.next(&mut src_tx).await? {
+        loader.add(&entry, &mut tx).await?;
+    }
+    let stats = loader.finish(&mut tx).await?;
+    tx.commit().await?;
+    src_tx.commit().await?;
+    Ok(stats)
+}
+
+/// The columns written by [`export_snapshot_csv`].
+const CSV_HEADER: [&str; 29] = [
+    "isin", "trading_venue", "full_name", "cfi", "instrument_class", "fisn", "issuer_lei",
+    "notional_currency", "is_commodities_derivative", "requested_admission", "approval_date",
+    "request_date", "admission_or_first_trade_date", "termination_date",
+    "relevant_competent_authority", "maturity_date", "total_issued_amount", "nominal_currency",
+    "nominal_value_per_unit", "interest_rate", "seniority", "expiry_date", "price_multiplier",
+    "option_type", "strike_price", "underlying", "reference_rate", "valid_from", "valid_to",
+];
+
+pub(super) fn describe_floating_rate(rate: &FloatingRate) -> String {
+    match (&rate.name, &rate.term) {
+        (Some(name), Some(term)) => format!("{name} {}{}", term.number, term.unit),
+        (Some(name), None) => name.to_string(),
+        (None, Some(term)) => format!("{}{}", term.number, term.unit),
+        (None, None) => String::new()
+    }
+}
+
+pub(super) fn describe_interest_rate(rate: &InterestRate) -> String {
+    match rate {
+        InterestRate::Fixed(r) => r.to_string(),
+        InterestRate::Floating(r, Some(spread)) => format!("{} {spread:+}bp", describe_floating_rate(r)),
+        InterestRate::Floating(r, None) => describe_floating_rate(r),
+    }
+}
+
+pub(super) fn describe_strike_price(price: &StrikePrice) -> String {
+    let mut s = price.price_type.to_string();
+    if let Some(p) = price.price {
+        s.push_str(&format!(" {p}"));
+    }
+    if let Some(c) = &price.currency {
+        s.push_str(&format!(" {c}"));
+    }
+    if price.pending {
+        s.push_str(" (pending)");
+    }
+    s
+}
+
+/// Flatten a record into the columns in [`CSV_HEADER`].
+fn csv_record(entry: &RefDataDbEntry) -> Vec<String> {
+    fn opt(value: Option<impl ToString>) -> String {
+        value.map(|v| v.to_string()).unwrap_or_default()
+    }
+    let rd = &entry.ref_data;
+    let tv = &rd.trading_venue_attrs;
+    let debt = rd.debt_attributes.as_ref();
+    let deriv = rd.derivative_attributes.as_ref();
+    let underlying = rd.underlying_isins().into_iter().map(Isin::to_string)
+        .chain(rd.underlying_leis().into_iter().map(Lei::to_string))
+        .collect::<Vec<String>>()
+        .join(";");
+    vec![
+        rd.isin.to_string(),
+        tv.trading_venue.to_string(),
+        rd.full_name.clone(),
+        rd.cfi.to_string(),
+        rd.classify().to_string(),
+        rd.fisn.to_string(),
+        rd.issuer_lei.to_string(),
+        rd.notional_currency.to_string(),
+        rd.is_commodities_derivative.to_string(),
+        tv.requested_admission.to_string(),
+        opt(tv.approval_date),
+        opt(tv.request_date),
+        opt(tv.admission_or_first_trade_date),
+        opt(tv.termination_date),
+        opt(rd.technical_attributes.as_ref().and_then(|t| t.relevant_competent_authority.as_ref())),
+        opt(rd.maturity()),
+        opt(debt.map(|d| d.total_issued_amount)),
+        opt(debt.map(|d| d.nominal_currency)),
+        opt(debt.map(|d| d.nominal_value_per_unit)),
+        opt(debt.map(|d| describe_interest_rate(&d.interest_rate))),
+        opt(debt.and_then(|d| d.seniority)),
+        opt(rd.expiry()),
+        opt(deriv.and_then(|d| d.price_multiplier)),
+        opt(deriv.and_then(|d| d.option_type)),
+        opt(deriv.and_then(|d| d.strike_price.as_ref()).map(describe_strike_price)),
+        underlying,
+        opt(rd.reference_rate().map(describe_floating_rate)),
+        entry.valid_from.to_string(),
+        opt(entry.valid_to),
+    ]
+}
+
+/// Write the records that were valid on `date` to `writer` as CSV, one row per record. Returns
+/// the number of records written.
+///
+/// The CSV has a fixed set of columns covering the most commonly used fields, with nested
+/// attributes (such as interest rates and underlyings) summarised as text. It is intended for
+/// inspection and for loading into other tools; for a complete copy of the records, use
+/// [`export_snapshot_db`]. Like [`export_snapshot_db`], the records are read in a single
+/// transaction.
+pub async fn export_snapshot_csv(
+    date: NaiveDate,
+    pool: &SqlitePool,
+    writer: impl Write
+) -> Result<u64, SqlError> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer.write_record(CSV_HEADER)?;
+    let mut tx = pool.begin().await?;
+    let mut snapshot = Snapshot::new(date);
+    let mut count = 0;
+    while let Some(entry) = snapshot.next(&mut tx).await? {
+        csv_writer.write_record(csv_record(&entry))?;
+        count += 1;
+    }
+    tx.commit().await?;
+    csv_writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sql::bulk::tests::{memory_pool, records};
+    use crate::sql::{export_snapshot_csv, export_snapshot_db, init_db, BulkLoadOptions, RefDataDbEntry, Snapshot, ToDb};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+    use sqlx::SqlitePool;
+    use std::env::temp_dir;
+
+    /// The records used in the tests: three instruments first published in February 2025, which
+    /// are renamed on 1 March.
+    fn versions() -> (Vec<RefDataDbEntry>, Vec<RefDataDbEntry>) {
+        let mut v1 = records(3);
+        for entry in &mut v1 {
+            entry.ref_data.trading_venue_attrs.admission_or_first_trade_date =
+                Some(Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap());
+        }
+        let mut v2 = records(3);
+        for entry in &mut v2 {
+            entry.ref_data.full_name.push_str(" (renamed)");
+            entry.valid_from = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        }
+        (v1, v2)
+    }
+
+    async fn read_snapshot(pool: &SqlitePool, date: NaiveDate) -> Vec<RefDataDbEntry> {
+        let mut conn = pool.acquire().await.unwrap();
+        let mut snapshot = Snapshot::new(date);
+        let mut entries = vec![];
+        while let Some(entry) = snapshot.next(&mut conn).await.unwrap() {
+            entries.push(entry);
+        }
+        entries
+    }
+
+    fn debug_all(entries: &[RefDataDbEntry]) -> Vec<String> {
+        entries.iter().map(|e| format!("{:?}", e.ref_data)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let pool = memory_pool().await;
+        let (v1, v2) = versions();
+        let mut tx = pool.begin().await.unwrap();
+        for entry in v1.iter().chain(&v2) {
+            entry.mark_prev_record(&mut tx).await.unwrap();
+            entry.to_db(&mut tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        assert!(read_snapshot(&pool, NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()).await.is_empty());
+        // Records are read back exactly as they were written.
+        let feb = read_snapshot(&pool, NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()).await;
+        assert_eq!(debug_all(&feb), debug_all(&v1));
+        assert!(feb.iter().all(|e| e.valid_to == NaiveDate::from_ymd_opt(2025, 2, 28)));
+        let mar = read_snapshot(&pool, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()).await;
+        assert_eq!(debug_all(&mar), debug_all(&v2));
+        assert!(mar.iter().all(|e| e.latest_record && e.valid_to.is_none()));
+
+        let date = NaiveDate::from_ymd_opt(2025, 2, 15).unwrap();
+        let dest = memory_pool().await;
+        let stats = export_snapshot_db(date, &pool, &dest, BulkLoadOptions::default()).await.unwrap();
+        assert_eq!(stats.records, 3);
+        assert_eq!(debug_all(&read_snapshot(&dest, date).await), debug_all(&v1));
+
+        let mut csv = vec![];
+        assert_eq!(export_snapshot_csv(date, &pool, &mut csv).await.unwrap(), 3);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("isin,trading_venue,full_name,cfi,instrument_class,"));
+        assert!(lines[1].starts_with("XS0000000000,XETR,Instrument 0,DBFTFB,BOND,"));
+        assert!(lines[1].contains(",EURO 6MNTH +50bp,"));
+        assert!(lines[2].contains(",EURO 3MNTH,"));
+        assert!(lines[3].contains(",US0378331005;GB0002634946,"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_unknown_cfi() {
+        // Rows whose CFI code has a category unknown to this crate are read back rather than
+        // aborting the snapshot.
+        let pool = memory_pool().await;
+        let mut tx = pool.begin().await.unwrap();
+        records(1).pop().unwrap().to_db(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        sqlx::query("UPDATE ReferenceData SET cfi = 'ZZXXXX'").execute(&pool).await.unwrap();
+        let entries = read_snapshot(&pool, NaiveDate::from_ymd_opt(2025, 2, 15).unwrap()).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].ref_data.cfi.as_str(), "ZZXXXX");
+        assert!(!entries[0].ref_data.cfi.is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_in_transaction() {
+        // A database in WAL mode, so that one connection can write while another reads.
+        let path = temp_dir().join(format!("firds-snapshot-tx-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let pool = SqlitePoolOptions::new().max_connections(2).connect_with(options).await.unwrap();
+        init_db(&pool).await.unwrap();
+        let (v1, _) = versions();
+        let mut tx = pool.begin().await.unwrap();
+        for entry in &v1[..2] {
+            entry.to_db(&mut tx).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        // A record added while the snapshot is being read, after the page containing the
+        // existing records has been read, is not included.
+        let date = NaiveDate::from_ymd_opt(2025, 2, 15).unwrap();
+        let mut read_tx = pool.begin().await.unwrap();
+        let mut snapshot = Snapshot::new(date);
+        assert!(snapshot.next(&mut read_tx).await.unwrap().is_some());
+        assert!(snapshot.next(&mut read_tx).await.unwrap().is_some());
+        let mut tx = pool.begin().await.unwrap();
+        v1[2].to_db(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+        assert!(snapshot.next(&mut read_tx).await.unwrap().is_none());
+        read_tx.commit().await.unwrap();
+        assert_eq!(read_snapshot(&pool, date).await.len(), 3);
+
+        pool.close().await;
+        std::fs::remove_file(&path).unwrap();
+        let _ = std::fs::remove_file(path.with_extension("db-wal"));
+        let _ = std::fs::remove_file(path.with_extension("db-shm"));
+    }
+}
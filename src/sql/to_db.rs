@@ -1,7 +1,8 @@
 use crate::sql::error::SqlError;
+use crate::sql::provenance::{RecordSource, RecordType, SourceFile};
 use crate::*;
 use chrono::{Duration, NaiveDate};
-use sqlx::{Executor, SqliteTransaction};
+use sqlx::{Database, Sqlite, SqliteTransaction, Transaction};
 
 /// A wrapper around a [`ReferenceData`] object which contains some additional data necessary for
 /// storing modifications to the data.
@@ -14,6 +15,9 @@ pub struct RefDataDbEntry {
     pub valid_from: NaiveDate,
     /// The date, if any, to which this entry was valid.
     pub valid_to: Option<NaiveDate>,
+    /// The file and type of record from which this entry was loaded, if known (see
+    /// [`RefDataDbEntry::with_source`]).
+    pub source: Option<RecordSource>,
 }
 
 impl RefDataDbEntry {
@@ -27,7 +31,8 @@ impl RefDataDbEntry {
             ref_data,
             latest_record,
             valid_from,
-            valid_to
+            valid_to,
+            source: None
         }
     }
 
@@ -44,18 +49,29 @@ impl RefDataDbEntry {
             ref_data,
             latest_record: true,
             valid_from,
-            valid_to: None
+            valid_to: None,
+            source: None
         })
     }
 
-    pub async fn mark_prev_record(&self, tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+    /// Record that this entry was loaded from a record of the given type in the [`SourceFile`]
+    /// with the given ID.
+    pub fn with_source(mut self, source_file_id: i64, record_type: RecordType) -> Self {
+        self.source = Some(RecordSource { source_file_id, record_type });
+        self
+    }
+
+    /// Mark the previous latest record of the instrument (if any) as valid until the day before
+    /// this entry's `valid_from` date.
+    pub async fn mark_prev_record<DB: Backend>(&self, tx: &mut Transaction<'_, DB>) -> Result<u64, SqlError> {
         let prev_valid_to = self.valid_from - Duration::days(1);
         IsinMic::from(&self.ref_data).mark_prev_record(prev_valid_to, tx).await
     }
 }
 
 /// The ISIN and trading venue MIC for an instrument. At any one time there should be only one
-/// *latest* record (ie, `latest_record = true`) with the same ISIN and MIC in the database.
+/// *latest* record (ie, `latest_record = true`) with the same ISIN and MIC in the database. This and
+/// the other invariants of the record history can be checked with [`check_db`](crate::sql::check_db).
 struct IsinMic<'a> {
     isin: &'a str,
     mic: &'a str,
@@ -64,8 +80,8 @@ struct IsinMic<'a> {
 impl<'a> From<&'a ReferenceData> for IsinMic<'a> {
     fn from(value: &'a ReferenceData) -> Self {
         Self {
-            isin: &value.isin,
-            mic: &value.trading_venue_attrs.trading_venue
+            isin: value.isin.as_str(),
+            mic: value.trading_venue_attrs.trading_venue.as_str()
         }
     }
 }
@@ -73,14 +89,28 @@ impl<'a> From<&'a ReferenceData> for IsinMic<'a> {
 impl<'a> From<&'a CancelledRecord> for IsinMic<'a> {
     fn from(value: &'a CancelledRecord) -> Self {
         Self {
-            isin: &value.isin,
-            mic: &value.trading_venue
+            isin: value.isin.as_str(),
+            mic: value.trading_venue.as_str()
         }
     }
 }
 
-impl<'a> IsinMic<'a> {
-    async fn mark_prev_record(&self, valid_to: NaiveDate, tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+impl IsinMic<'_> {
+    async fn mark_prev_record<DB: Backend>(&self, valid_to: NaiveDate, tx: &mut Transaction<'_, DB>) -> Result<u64, SqlError> {
+        DB::mark_prev_record(self.isin, self.mic, valid_to, tx).await
+    }
+}
+
+/// Operations whose SQL differs between the supported database backends.
+#[allow(async_fn_in_trait)]
+pub trait Backend: Database {
+    /// Set `valid_to` on the latest record(s) with the given ISIN and MIC, and mark them as no
+    /// longer being the latest record. Returns the number of records updated.
+    async fn mark_prev_record(isin: &str, mic: &str, valid_to: NaiveDate, tx: &mut Transaction<'_, Self>) -> Result<u64, SqlError>;
+}
+
+impl Backend for Sqlite {
+    async fn mark_prev_record(isin: &str, mic: &str, valid_to: NaiveDate, tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
         let valid_to_str = valid_to.to_string();
         let query = sqlx::query!(
             r#"
@@ -93,8 +123,8 @@ impl<'a> IsinMic<'a> {
                 AND ReferenceData.valid_to IS NULL
             "#,
             valid_to_str,
-            self.isin,
-            self.mic
+            isin,
+            mic
         );
         Ok(query
             .execute(&mut **tx)
@@ -104,24 +134,28 @@ impl<'a> IsinMic<'a> {
 }
 
 impl CancelledRecord {
-    async fn mark_prev_record(&self, valid_to_date: NaiveDate, tx: &mut SqliteTransaction<'_>) -> Result<u64, SqlError> {
+    /// Mark the latest record of the cancelled instrument as valid until `valid_to_date`.
+    pub async fn mark_prev_record<DB: Backend>(&self, valid_to_date: NaiveDate, tx: &mut Transaction<'_, DB>) -> Result<u64, SqlError> {
         IsinMic::from(self).mark_prev_record(valid_to_date, tx).await
     }
 }
 
-/// Structs implementing this trait can be serialised to a database.
-pub trait ToDb where Self: Sized {
+/// Structs implementing this trait can be serialised to a database. The type parameter is the
+/// [`sqlx`] database backend, which defaults to SQLite.
+#[allow(async_fn_in_trait)]
+pub trait ToDb<DB: Database = Sqlite> where Self: Sized {
 
-    /// Serialise to a database.
-    async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError>;
+    /// Serialise to a database, returning the ID of the inserted row.
+    async fn to_db(&self, tx: &mut Transaction<'_, DB>) -> Result<i64, SqlError>;
 }
 
-pub trait ToDbOption where Self: Sized {
-    async fn to_db_option(&self, tx: &mut SqliteTransaction<'_>) -> Result<Option<i64>, SqlError>;
+#[allow(async_fn_in_trait)]
+pub trait ToDbOption<DB: Database = Sqlite> where Self: Sized {
+    async fn to_db_option(&self, tx: &mut Transaction<'_, DB>) -> Result<Option<i64>, SqlError>;
 }
 
-impl<T: ToDb> ToDbOption for Option<T> {
-    async fn to_db_option(&self, tx: &mut SqliteTransaction<'_>) -> Result<Option<i64>, SqlError> {
+impl<DB: Database, T: ToDb<DB>> ToDbOption<DB> for Option<T> {
+    async fn to_db_option(&self, tx: &mut Transaction<'_, DB>) -> Result<Option<i64>, SqlError> {
         if let Some(t) = self {
             Ok(Some(t.to_db(tx).await?))
         } else {
@@ -130,10 +164,21 @@ impl<T: ToDb> ToDbOption for Option<T> {
     }
 }
 
+/// [`Term`], [`FloatingRate`] and [`Index`] are value-like: many instruments share the same
+/// reference rate, so rather than inserting a new row for each instrument, their `to_db` impls
+/// return the ID of an identical existing row if there is one.
 impl ToDb for Term {
 
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let unit_str = self.unit.to_string();
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM Term WHERE number = ? AND unit = ?"#,
+            self.number,
+            unit_str
+        ).fetch_optional(&mut **tx).await?;
+        if let Some(id) = existing {
+            return Ok(id)
+        }
         let query = sqlx::query!(
             "INSERT INTO Term (number, unit) VALUES (?, ?)",
             self.number,
@@ -150,12 +195,13 @@ impl ToDb for Term {
 impl ToDb for StrikePrice {
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let price_type_str = self.price_type.to_string();
+        let currency = self.currency.as_ref().map(|c| c.as_str());
         let query = sqlx::query!(
             "INSERT INTO StrikePrice (price_type, price, pending, currency) VALUES (?, ?, ?, ?)",
             price_type_str,
             self.price,
             self.pending,
-            self.currency
+            currency
         );
         Ok(query
             .execute(&mut **tx)
@@ -172,6 +218,14 @@ impl ToDb for FloatingRate {
         } else {
             None
         };
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM FloatingRate WHERE name IS ? AND term_id IS ?"#,
+            name,
+            term_id
+        ).fetch_optional(&mut **tx).await?;
+        if let Some(id) = existing {
+            return Ok(id)
+        }
         let query = sqlx::query!(
             "INSERT INTO FloatingRate (name, term_id) VALUES (?, ?)",
             name,
@@ -187,9 +241,18 @@ impl ToDb for FloatingRate {
 impl ToDb for Index {
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let name = self.name.to_db(tx).await?;
+        let isin = self.isin.as_ref().map(Isin::as_str);
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM FirdsIndex WHERE isin IS ? AND name_id = ?"#,
+            isin,
+            name
+        ).fetch_optional(&mut **tx).await?;
+        if let Some(id) = existing {
+            return Ok(id)
+        }
         let query = sqlx::query!(
             "INSERT INTO FirdsIndex (isin, name_id) VALUES (?, ?)",
-            self.isin,
+            isin,
             name
         );
         Ok(query
@@ -199,12 +262,51 @@ impl ToDb for Index {
     }
 }
 
+impl ToDb for SourceFile {
+    async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM SourceFile WHERE source = ? AND file_name = ?"#,
+            self.source,
+            self.file_name
+        ).fetch_optional(&mut **tx).await?;
+        if let Some(id) = existing {
+            return Ok(id)
+        }
+        let timestamp_str = self.timestamp.map(|t| t.to_string());
+        let query = sqlx::query!(
+            r#"
+                INSERT INTO SourceFile (
+                    source,
+                    file_type,
+                    file_name,
+                    file_id,
+                    download_link,
+                    publication_timestamp,
+                    checksum
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            self.source,
+            self.file_type,
+            self.file_name,
+            self.file_id,
+            self.download_link,
+            timestamp_str,
+            self.checksum
+        );
+        Ok(query
+            .execute(&mut **tx)
+            .await?
+            .last_insert_rowid())
+    }
+}
+
 impl ToDb for TradingVenueAttributes {
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let appr_date_str = self.approval_date.map(|d| d.to_string());
         let req_date_str = self.request_date.map(|d| d.to_string());
         let aoft_date_str = self.admission_or_first_trade_date.map(|d| d.to_string());
         let term_date_str = self.termination_date.map(|d| d.to_string());
+        let trading_venue = self.trading_venue.as_str();
         let query = sqlx::query!(
             r#"
                 INSERT INTO TradingVenueAttributes (
@@ -216,7 +318,7 @@ impl ToDb for TradingVenueAttributes {
                     termination_date
                 ) VALUES (?, ?, ?, ?, ?, ?)
             "#,
-            self.trading_venue,
+            trading_venue,
             self.requested_admission,
             appr_date_str,
             req_date_str,
@@ -273,6 +375,7 @@ impl ToDb for TechnicalAttributes {
         } else {
             None
         };
+        let relevant_trading_venue = self.relevant_trading_venue.as_ref().map(|m| m.as_str());
         let query = sqlx::query!(
             r#"
                 INSERT INTO TechnicalAttributes (
@@ -283,7 +386,7 @@ impl ToDb for TechnicalAttributes {
             "#,
             self.relevant_competent_authority,
             publication_period_id,
-            self.relevant_trading_venue
+            relevant_trading_venue
         );
         Ok(query
             .execute(&mut **tx)
@@ -297,6 +400,7 @@ impl ToDb for DebtAttributes {
         let mat_date_str = self.maturity_date.map(|d| d.to_string());
         let ir = self.interest_rate.to_db(tx).await?;
         let seniority_str = self.seniority.map(|d| d.to_string());
+        let nominal_currency = self.nominal_currency.as_str();
         let query = sqlx::query!(
             r#"
                 INSERT INTO DebtAttributes (
@@ -310,7 +414,7 @@ impl ToDb for DebtAttributes {
             "#,
             self.total_issued_amount,
             mat_date_str,
-            self.nominal_currency,
+            nominal_currency,
             self.nominal_value_per_unit,
             ir,
             seniority_str
@@ -363,6 +467,7 @@ impl ToDb for InterestRateDerivativeAttributes {
         } else {
             None
         };
+        let notional_currency_2 = self.notional_currency_2.as_ref().map(|c| c.as_str());
         let query = sqlx::query!(
             r#"
                 INSERT INTO InterestRateDerivativeAttributes (
@@ -374,7 +479,7 @@ impl ToDb for InterestRateDerivativeAttributes {
             "#,
             ref_rate,
             interest_rate_1_id,
-            self.notional_currency_2,
+            notional_currency_2,
             interest_rate_2_id
         );
         Ok(query
@@ -387,9 +492,10 @@ impl ToDb for InterestRateDerivativeAttributes {
 impl ToDb for FxDerivativeAttributes {
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let fx_type = self.fx_type.map(|t| t.to_string());
+        let notional_currency_2 = self.notional_currency_2.as_ref().map(|c| c.as_str());
         let query = sqlx::query!(
             "INSERT INTO FxDerivativeAttributes (notional_currency_2, fx_type) VALUES (?, ?)",
-            self.notional_currency_2,
+            notional_currency_2,
             fx_type
         );
         Ok(query
@@ -402,9 +508,9 @@ impl ToDb for FxDerivativeAttributes {
 impl ToDb for UnderlyingSingle {
     async fn to_db(&self, tx: &mut SqliteTransaction<'_>) -> Result<i64, SqlError> {
         let (isin, index_id, lei) = match &self {
-            UnderlyingSingle::Isin(isin) => (Some(isin), None, None),
+            UnderlyingSingle::Isin(isin) => (Some(isin.as_str()), None, None),
             UnderlyingSingle::Index(index) => (None, Some(index.to_db(tx).await?), None),
-            UnderlyingSingle::Lei(lei) => (None, None, Some(lei)),
+            UnderlyingSingle::Lei(lei) => (None, None, Some(lei.as_str())),
         };
         let query = sqlx::query!(
             "INSERT INTO UnderlyingSingle (isin, index_id, lei) VALUES (?, ?, ?)",
@@ -427,6 +533,7 @@ impl ToDb for UnderlyingBasket {
             .await?
             .last_insert_rowid();
         for isin in &self.isin {
+            let isin = isin.as_str();
             sqlx::query!(
                 "INSERT INTO UnderlyingBasketIsin VALUES (?, ?)",
                 basket_id,
@@ -434,6 +541,7 @@ impl ToDb for UnderlyingBasket {
             ).execute(&mut **tx).await?;
         }
         for lei in &self.issuer_lei {
+            let lei = lei.as_str();
             sqlx::query!(
                 "INSERT INTO UnderlyingBasketIssuerLei VALUES (?, ?)",
                 basket_id,
@@ -532,6 +640,14 @@ impl ToDb for RefDataDbEntry {
         let deriv_attrs = self.ref_data.derivative_attributes.to_db_option(tx).await?;
         let valid_from_str = self.valid_from.to_string();
         let valid_to_str = self.valid_to.map(|d| d.to_string());
+        let isin = self.ref_data.isin.as_str();
+        let issuer_lei = self.ref_data.issuer_lei.as_str();
+        let cfi = self.ref_data.cfi.as_str();
+        let fisn = self.ref_data.fisn.as_str();
+        let notional_currency = self.ref_data.notional_currency.as_str();
+        let instrument_class = self.ref_data.classify().to_string();
+        let source_file_id = self.source.map(|s| s.source_file_id);
+        let record_type = self.source.map(|s| s.record_type.to_string());
         let query = sqlx::query!(
             r#"
                 INSERT INTO ReferenceData (
@@ -546,25 +662,31 @@ impl ToDb for RefDataDbEntry {
                    technical_attributes_id,
                    debt_attributes_id,
                    derivative_attributes_id,
+                   instrument_class,
                    latest_record,
                    valid_from,
-                   valid_to
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                   valid_to,
+                   source_file_id,
+                   record_type
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
-            self.ref_data.isin,
+            isin,
             self.ref_data.full_name,
-            self.ref_data.cfi,
+            cfi,
             self.ref_data.is_commodities_derivative,
-            self.ref_data.issuer_lei,
-            self.ref_data.fisn,
+            issuer_lei,
+            fisn,
             tv_attrs,
-            self.ref_data.notional_currency,
+            notional_currency,
             tech_attrs,
             debt_attrs,
             deriv_attrs,
+            instrument_class,
             self.latest_record,
             valid_from_str,
             valid_to_str,
+            source_file_id,
+            record_type,
         );
         Ok(query
             .execute(&mut **tx)
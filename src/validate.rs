@@ -0,0 +1,373 @@
+//! Data quality checks for [`ReferenceData`], based on the requirements of the RTS 23 reporting
+//! standards.
+//!
+//! The parsers in this crate accept whatever data is present in the source files. A [`Validator`]
+//! runs a configurable set of [`Rule`]s over parsed records and returns a [`Finding`] for each
+//! problem found, which can be used to produce exception reports.
+//!
+//! ```no_run
+//! use firds::validate::{Severity, Validator};
+//! # let records: Vec<firds::ReferenceData> = vec![];
+//!
+//! let validator = Validator::default()
+//!     .without_rule("fisn-debt-attributes")
+//!     .with_severity("valid-identifiers", Severity::Error);
+//! for finding in validator.validate_all(&records) {
+//!     println!("{finding}");
+//! }
+//! ```
+
+use crate::identifiers::Isin;
+use crate::model::ReferenceData;
+use std::fmt::{Display, Formatter};
+use strum_macros::{Display, EnumString};
+
+/// The severity of a [`Finding`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, Display)]
+pub enum Severity {
+    /// Something that is unusual but not necessarily wrong.
+    #[strum(serialize = "INFO")]
+    Info,
+    /// Something that is probably wrong, but does not prevent the record being used.
+    #[strum(serialize = "WARNING")]
+    Warning,
+    /// Something that is definitely wrong, such as a missing mandatory field.
+    #[strum(serialize = "ERROR")]
+    Error,
+}
+
+/// A single problem found in a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// The ISIN of the record in which the problem was found.
+    pub isin: Isin,
+    /// The ID of the rule which found the problem.
+    pub rule: &'static str,
+    /// The severity of the problem.
+    pub severity: Severity,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}] {}: {}", self.isin, self.severity, self.rule, self.message)
+    }
+}
+
+/// A check which can be run over a [`ReferenceData`] record.
+pub trait Rule: Send + Sync {
+    /// A short, unique identifier for the rule, used in findings and to configure a [`Validator`].
+    fn id(&self) -> &'static str;
+
+    /// The severity of findings produced by this rule, unless overridden in the [`Validator`].
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// Check the record, returning a description of each problem found.
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String>;
+}
+
+/// Debt instruments (CFI category `D`) must have debt attributes.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DebtAttributesPresent;
+
+impl Rule for DebtAttributesPresent {
+    fn id(&self) -> &'static str {
+        "debt-attributes-present"
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        if ref_data.cfi.is_debt() && ref_data.debt_attributes.is_none() {
+            vec![format!("debt instrument (CFI {}) has no debt attributes", ref_data.cfi)]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Derivatives (CFI categories `F`, `H`, `J`, `K`, `O` and `S`) must have derivative attributes.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DerivativeAttributesPresent;
+
+impl Rule for DerivativeAttributesPresent {
+    fn id(&self) -> &'static str {
+        "derivative-attributes-present"
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        if ref_data.cfi.is_derivative() && ref_data.derivative_attributes.is_none() {
+            vec![format!("derivative (CFI {}) has no derivative attributes", ref_data.cfi)]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Options (CFI categories `O` and `H`) must have an option type and a strike price.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct OptionAttributesPresent;
+
+impl Rule for OptionAttributesPresent {
+    fn id(&self) -> &'static str {
+        "option-attributes-present"
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        if !ref_data.cfi.is_option() {
+            return vec![]
+        }
+        let mut problems = vec![];
+        let deriv_attrs = ref_data.derivative_attributes.as_ref();
+        if deriv_attrs.and_then(|d| d.option_type).is_none() {
+            problems.push(format!("option (CFI {}) has no option type", ref_data.cfi));
+        }
+        if deriv_attrs.and_then(|d| d.strike_price.as_ref()).is_none() {
+            problems.push(format!("option (CFI {}) has no strike price", ref_data.cfi));
+        }
+        problems
+    }
+}
+
+/// The maturity date of a debt instrument, or the expiry date of a derivative, must not be before
+/// the date of admission to trading or first trade.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MaturityNotBeforeAdmission;
+
+impl Rule for MaturityNotBeforeAdmission {
+    fn id(&self) -> &'static str {
+        "maturity-not-before-admission"
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        let Some(admission) = ref_data.trading_venue_attrs.admission_or_first_trade_date else {
+            return vec![]
+        };
+        let admission_date = admission.date_naive();
+        let mut problems = vec![];
+        let maturity = ref_data.debt_attributes.as_ref().and_then(|d| d.maturity_date);
+        if let Some(maturity) = maturity.filter(|&m| m < admission_date) {
+            problems.push(format!("maturity date {maturity} is before admission date {admission_date}"));
+        }
+        let expiry = ref_data.derivative_attributes.as_ref().and_then(|d| d.expiry_date);
+        if let Some(expiry) = expiry.filter(|&e| e < admission_date) {
+            problems.push(format!("expiry date {expiry} is before admission date {admission_date}"));
+        }
+        problems
+    }
+}
+
+/// An instrument must have commodity derivative attributes if (and only if) it is flagged as a
+/// commodities derivative.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CommodityAttributesConsistent;
+
+impl Rule for CommodityAttributesConsistent {
+    fn id(&self) -> &'static str {
+        "commodity-attributes-consistent"
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        let has_attrs = ref_data.derivative_attributes.as_ref()
+            .and_then(|d| d.asset_class_specific_attributes.as_ref())
+            .is_some_and(|a| a.commodity_attributes.is_some());
+        match (ref_data.is_commodities_derivative, has_attrs) {
+            (true, false) => vec!["commodities derivative has no commodity attributes".to_owned()],
+            (false, true) => vec!["commodity attributes present but not flagged as a commodities derivative".to_owned()],
+            _ => vec![]
+        }
+    }
+}
+
+/// The ISIN and issuer LEI must have valid check digits.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ValidIdentifiers;
+
+impl Rule for ValidIdentifiers {
+    fn id(&self) -> &'static str {
+        "valid-identifiers"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        let mut problems = vec![];
+        if let Err(e) = ref_data.isin.validate() {
+            problems.push(format!("invalid ISIN {}: {e}", ref_data.isin));
+        }
+        if let Err(e) = ref_data.issuer_lei.validate() {
+            problems.push(format!("invalid issuer LEI {}: {e}", ref_data.issuer_lei));
+        }
+        problems
+    }
+}
+
+/// The coupon and maturity stated in the FISN of a debt instrument should match its debt
+/// attributes.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FisnDebtAttributes;
+
+impl Rule for FisnDebtAttributes {
+    fn id(&self) -> &'static str {
+        "fisn-debt-attributes"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, ref_data: &ReferenceData) -> Vec<String> {
+        match &ref_data.debt_attributes {
+            Some(attrs) => ref_data.fisn.check_debt_attributes(attrs).into_iter()
+                .map(|i| format!("FISN {} is inconsistent with debt attributes: {i:?}", ref_data.fisn))
+                .collect(),
+            None => vec![]
+        }
+    }
+}
+
+/// Runs a set of [`Rule`]s over [`ReferenceData`] records.
+///
+/// [`Validator::default`] returns a validator with all of the rules defined in this module; rules
+/// can be added or removed, and their severities changed, using the builder-style methods.
+pub struct Validator {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Validator {
+    /// Create a validator with no rules.
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Add a rule, with its default severity. Any existing rule with the same ID is replaced.
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self = self.without_rule(rule.id());
+        let severity = rule.default_severity();
+        self.rules.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Remove the rule with the given ID, if present.
+    pub fn without_rule(mut self, id: &str) -> Self {
+        self.rules.retain(|(r, _)| r.id() != id);
+        self
+    }
+
+    /// Set the severity of findings produced by the rule with the given ID, if present.
+    pub fn with_severity(mut self, id: &str, severity: Severity) -> Self {
+        for (rule, s) in &mut self.rules {
+            if rule.id() == id {
+                *s = severity;
+            }
+        }
+        self
+    }
+
+    /// Return the IDs of the rules that will be run, in order.
+    pub fn rule_ids(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|(r, _)| r.id())
+    }
+
+    /// Run all rules over a single record.
+    pub fn validate(&self, ref_data: &ReferenceData) -> Vec<Finding> {
+        self.rules.iter()
+            .flat_map(|(rule, severity)| rule.check(ref_data).into_iter().map(|message| Finding {
+                isin: ref_data.isin.clone(),
+                rule: rule.id(),
+                severity: *severity,
+                message,
+            }))
+            .collect()
+    }
+
+    /// Run all rules over each of the given records.
+    pub fn validate_all<'a>(&self, records: impl IntoIterator<Item = &'a ReferenceData>) -> Vec<Finding> {
+        records.into_iter().flat_map(|r| self.validate(r)).collect()
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+            .with_rule(DebtAttributesPresent)
+            .with_rule(DerivativeAttributesPresent)
+            .with_rule(OptionAttributesPresent)
+            .with_rule(MaturityNotBeforeAdmission)
+            .with_rule(CommodityAttributesConsistent)
+            .with_rule(ValidIdentifiers)
+            .with_rule(FisnDebtAttributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::tests::builder;
+    use crate::validate::{Severity, Validator};
+    use crate::{DebtAttributes, DerivativeAttributes, Fisn, InterestRate, OptionType, ReferenceData};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn ref_data(cfi: &str) -> ReferenceData {
+        builder(cfi)
+            .fisn(Fisn::new("BUNDESREPUB.DEU/.5 BD 20260215"))
+            .admission_date(Utc.with_ymd_and_hms(2016, 2, 15, 0, 0, 0).unwrap())
+            .build_unchecked()
+            .unwrap()
+    }
+
+    fn rules(validator: &Validator, data: &ReferenceData) -> Vec<&'static str> {
+        validator.validate(data).into_iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_validator() {
+        let validator = Validator::default();
+        let mut bond = ref_data("DBFTFB");
+        assert_eq!(rules(&validator, &bond), vec!["debt-attributes-present"]);
+
+        bond.debt_attributes = Some(DebtAttributes {
+            total_issued_amount: 1_000_000.0,
+            maturity_date: NaiveDate::from_ymd_opt(2016, 2, 14),
+            nominal_currency: "EUR".parse().unwrap(),
+            nominal_value_per_unit: 0.01,
+            interest_rate: InterestRate::Fixed(0.5),
+            seniority: None,
+        });
+        let findings = validator.validate(&bond);
+        assert_eq!(
+            findings.iter().map(|f| (f.rule, f.severity)).collect::<Vec<_>>(),
+            vec![
+                ("maturity-not-before-admission", Severity::Error),
+                ("fisn-debt-attributes", Severity::Warning),
+            ]
+        );
+        assert_eq!(
+            findings[0].to_string(),
+            "DE0001102390 [ERROR] maturity-not-before-admission: \
+                maturity date 2016-02-14 is before admission date 2016-02-15"
+        );
+
+        let mut option = ref_data("OCAFPS");
+        option.is_commodities_derivative = true;
+        option.derivative_attributes = Some(DerivativeAttributes {
+            option_type: Some(OptionType::Call),
+            ..Default::default()
+        });
+        assert_eq!(
+            rules(&validator, &option),
+            vec!["option-attributes-present", "commodity-attributes-consistent"]
+        );
+
+        let validator = validator
+            .without_rule("commodity-attributes-consistent")
+            .with_severity("option-attributes-present", Severity::Info);
+        let findings = validator.validate_all([&bond, &option]);
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[2].severity, Severity::Info);
+        assert!(!validator.rule_ids().any(|id| id == "commodity-attributes-consistent"));
+        assert!(Validator::new().validate(&bond).is_empty());
+    }
+}
@@ -43,7 +43,10 @@ pub enum XmlError {
     /// IO error.
     Io(std::io::Error),
     /// Error constructing a `firds` struct.
-    Firds(crate::ParseError)
+    Firds(crate::ParseError),
+    /// An identifier (such as an ISIN or LEI) was invalid and strict parsing was requested. The
+    /// contained string is the invalid identifier.
+    InvalidIdentifier(String, crate::IdentifierError),
 }
 
 impl From<AttrError> for XmlError {
@@ -94,6 +97,18 @@ impl From<crate::ProductError> for XmlError {
     }
 }
 
+impl From<crate::CfiError> for XmlError {
+    fn from(e: crate::CfiError) -> Self {
+        Self::Firds(crate::ParseError::from(e))
+    }
+}
+
+impl From<crate::CurrencyError> for XmlError {
+    fn from(e: crate::CurrencyError) -> Self {
+        Self::Firds(crate::ParseError::from(e))
+    }
+}
+
 impl From<strum::ParseError> for XmlError {
     fn from(e: strum::ParseError) -> Self {
         Self::Firds(crate::ParseError::from(e))
@@ -52,8 +52,7 @@ impl FromXml for StrikePrice {
                 return Err(XmlError::ElementNotFound)
             };
             let price = val_elem.text.parse::<f64>()?;
-            let currency = text_or_none(child_or_none(monetary_val_elem, "Ccy"))
-                .map(ToOwned::to_owned);
+            let currency = parse_or_none(child_or_none(monetary_val_elem, "Ccy"))?;
             Ok(Self {
                 price_type,
                 price: Some(price),
@@ -63,7 +62,7 @@ impl FromXml for StrikePrice {
         } else {
             let no_price_elem = elem.get_child("NoPric")?;
             let pending = no_price_elem.get_child("Pdg")?.text == "PNDG";
-            let currency = text_or_none(no_price_elem.find_child("Ccy")).map(ToOwned::to_owned);
+            let currency = parse_or_none(no_price_elem.find_child("Ccy"))?;
             Ok(Self {
                 price_type: StrikePriceType::NoPrice,
                 price: None,
@@ -96,7 +95,7 @@ impl FromXml for Index {
     /// Parse an XML element of type `FinancialInstrument58` into an [`Index`] struct.
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         Ok(Self {
-            isin: text_or_none(elem.find_child("ISIN")).map(String::from),
+            isin: text_or_none(elem.find_child("ISIN")).map(Isin::new_unchecked),
             name: FloatingRate::from_xml(elem.get_child("Nm")?)?
         })
     }
@@ -106,7 +105,7 @@ impl FromXml for TradingVenueAttributes {
     /// Parse a `TradgVnRltdAttrbts` XML element from FIRDS into a `TradingVenueAttributes` struct.
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         Ok(Self {
-            trading_venue: elem.get_child("Id")?.text.to_owned(),
+            trading_venue: Mic::new_unchecked(&elem.get_child("Id")?.text),
             requested_admission: elem.get_child("IssrReq")?.text.parse::<bool>()?,
             approval_date: datetime_or_none(elem.find_child("AdmssnApprvlDtByIssr"))?,
             request_date: datetime_or_none(elem.find_child("ReqForAdmssnDt"))?,
@@ -162,7 +161,7 @@ impl FromXml for TechnicalAttributes {
             relevant_competent_authority: text_or_none(elem.find_child("RlvntCmptntAuthrty"))
                 .map(String::from),
             publication_period: PublicationPeriod::from_xml_option(elem.find_child("PblctnPrd"))?,
-            relevant_trading_venue: text_or_none(elem.find_child("RlvntTradgVn")).map(String::from)
+            relevant_trading_venue: text_or_none(elem.find_child("RlvntTradgVn")).map(Mic::new_unchecked)
         })
     }
 }
@@ -174,7 +173,7 @@ impl FromXml for DebtAttributes {
         Ok(Self {
             total_issued_amount: issued_amount_elem.text.parse()?,
             maturity_date: date_or_none(elem.find_child("MtrtyDt"))?,
-            nominal_currency: issued_amount_elem.get_attr("Ccy")?.to_owned(),
+            nominal_currency: issued_amount_elem.get_attr("Ccy")?.parse()?,
             nominal_value_per_unit: elem.get_child("NmnlValPerUnit")?.text.parse()?,
             interest_rate: InterestRate::from_xml(elem.get_child("IntrstRate")?)?,
             seniority: DebtSeniority::from_xml_option(elem.find_child("DebtSnrty"))?
@@ -215,7 +214,7 @@ impl FromXml for InterestRateDerivativeAttributes {
         Ok(Self {
             reference_rate: FloatingRate::from_xml(elem.get_child("IntrstRate")?)?,
             interest_rate_1: InterestRate::from_xml_option(elem.find_child("FirstLegIntrstRate"))?,
-            notional_currency_2: text_or_none(elem.find_child("OtherNtnlCcy")).map(String::from),
+            notional_currency_2: parse_or_none(elem.find_child("OtherNtnlCcy"))?,
             interest_rate_2: InterestRate::from_xml_option(elem.find_child("OthrLegIntrstRate"))?
         })
     }
@@ -226,7 +225,7 @@ impl FromXml for FxDerivativeAttributes {
     /// [`FxDerivativeAttributes`] struct.
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         Ok(Self {
-            notional_currency_2: text_or_none(elem.find_child("OthrNtnlCcy")).map(String::from),
+            notional_currency_2: parse_or_none(elem.find_child("OthrNtnlCcy"))?,
             fx_type: FxType::from_xml_option(elem.find_child("FxTp"))?
         })
     }
@@ -236,8 +235,8 @@ impl FromXml for UnderlyingSingle {
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         if let Some(child) = elem.find_first_child() {
             match child.local_name.as_str() {
-                "ISIN" => Ok(Self::Isin(child.text.to_owned())),
-                "LEI" => Ok(Self::Lei(child.text.to_owned())),
+                "ISIN" => Ok(Self::Isin(Isin::new_unchecked(&child.text))),
+                "LEI" => Ok(Self::Lei(Lei::new_unchecked(&child.text))),
                 "Indx" => Ok(Self::Index(Index::from_xml(child)?)),
                 _ => Err(XmlError::Firds(crate::ParseError::Enum))
             }
@@ -254,8 +253,8 @@ impl FromXml for UnderlyingBasket {
         let mut issuer_lei = vec![];
         for c in elem.iter_children() {
             match c.local_name.as_str() {
-                "ISIN" => isin.push(c.text.to_owned()),
-                "LEI" => issuer_lei.push(c.text.to_owned()),
+                "ISIN" => isin.push(Isin::new_unchecked(&c.text)),
+                "LEI" => issuer_lei.push(Lei::new_unchecked(&c.text)),
                 _ => return Err(XmlError::UnexpectedElement)
             }
         }
@@ -334,16 +333,16 @@ impl FromXml for ReferenceData {
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         let gen_attrs = elem.get_child("FinInstrmGnlAttrbts")?;
         Ok(Self {
-            isin: gen_attrs.get_child("Id")?.text.to_owned(),
+            isin: Isin::new_unchecked(&gen_attrs.get_child("Id")?.text),
             full_name: gen_attrs.get_child("FullNm")?.text.to_owned(),
-            cfi: gen_attrs.get_child("ClssfctnTp")?.text.to_owned(),
+            cfi: gen_attrs.get_child("ClssfctnTp")?.text.parse()?,
             is_commodities_derivative: gen_attrs.get_child("CmmdtyDerivInd")?.text.parse()?,
-            issuer_lei: elem.get_child("Issr")?.text.to_owned(),
-            fisn: gen_attrs.get_child("ShrtNm")?.text.to_owned(),
+            issuer_lei: Lei::new_unchecked(&elem.get_child("Issr")?.text),
+            fisn: Fisn::new(&gen_attrs.get_child("ShrtNm")?.text),
             trading_venue_attrs: TradingVenueAttributes::from_xml(
                 elem.get_child("TradgVnRltdAttrbts")?
             )?,
-            notional_currency: gen_attrs.get_child("NtnlCcy")?.text.to_owned(),
+            notional_currency: gen_attrs.get_child("NtnlCcy")?.text.parse()?,
             technical_attributes: TechnicalAttributes::from_xml_option(
                 elem.find_child("TechAttrbts")
             )?,
@@ -372,12 +371,12 @@ impl FromXml for ModifiedRecord {
 impl FromXml for CancelledRecord {
     fn from_xml(elem: &Element) -> Result<Self, XmlError> {
         Ok(Self {
-            isin: elem.get_child("FinInstrmGnlAttrbts")?
-                .get_child("Id")?
-                .text.to_owned(),
-            trading_venue: elem.get_child("TradgVnRltdAttrbts")?
-                .get_child("Id")?
-                .text.to_owned(),
+            isin: Isin::new_unchecked(
+                &elem.get_child("FinInstrmGnlAttrbts")?.get_child("Id")?.text
+            ),
+            trading_venue: Mic::new_unchecked(
+                &elem.get_child("TradgVnRltdAttrbts")?.get_child("Id")?.text
+            ),
         })
     }
 }
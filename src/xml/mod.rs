@@ -1,9 +1,10 @@
 //! Code for parsing structs from the XML files published by ESMA or the FCA.
 
-use crate::xml::error::XmlError;
+pub use crate::xml::error::XmlError;
 pub(crate) use crate::xml::from_xml::FromXml;
 pub(crate) use crate::xml::iter_xml::XmlIterator;
-use crate::ReferenceData;
+use crate::{Currency, CurrencyError, DerivativeUnderlying, IdentifierError, ReferenceData, UnderlyingSingle};
+use std::fmt::Display;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
@@ -13,16 +14,117 @@ mod iter_xml;
 mod error;
 mod parse_utils;
 
+/// How to handle invalid identifiers (such as ISINs or LEIs with bad check digits, or malformed
+/// MICs), unknown currency codes and CFI codes with an unknown category or group in the data.
+/// Badly formatted currency and CFI codes are always rejected.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum Strictness {
+    /// Return an [`XmlError::InvalidIdentifier`] error for any record containing an invalid
+    /// identifier, and an [`XmlError::Firds`] error for any record containing a currency code
+    /// that is not an active ISO 4217 code or a CFI code whose category or group is not defined by
+    /// ISO 10962.
+    Strict,
+    /// Accept invalid identifiers, unknown currency codes and unknown CFI categories and groups as
+    /// they are. They can be checked later using the relevant `validate`, `is_valid` or `is_iso`
+    /// methods.
+    #[default]
+    Lenient,
+}
+
+/// Convert the result of validating an identifier into an [`XmlError::InvalidIdentifier`] if it is
+/// invalid.
+fn check_id(id: &impl Display, res: Result<(), IdentifierError>) -> Result<(), XmlError> {
+    res.map_err(|e| XmlError::InvalidIdentifier(id.to_string(), e))
+}
+
+/// Check all of the identifiers in the given [`ReferenceData`], returning an error for the first
+/// invalid identifier found.
+fn check_identifiers(ref_data: &ReferenceData) -> Result<(), XmlError> {
+    check_id(&ref_data.isin, ref_data.isin.validate())?;
+    check_id(&ref_data.issuer_lei, ref_data.issuer_lei.validate())?;
+    let tv = &ref_data.trading_venue_attrs.trading_venue;
+    check_id(tv, tv.validate())?;
+    if let Some(tv) = ref_data.technical_attributes.as_ref().and_then(|t| t.relevant_trading_venue.as_ref()) {
+        check_id(tv, tv.validate())?;
+    }
+    let underlying = ref_data.derivative_attributes.as_ref()
+        .and_then(|d| d.underlying.as_ref());
+    match underlying {
+        Some(DerivativeUnderlying::Single(UnderlyingSingle::Isin(isin))) => {
+            check_id(isin, isin.validate())?
+        },
+        Some(DerivativeUnderlying::Single(UnderlyingSingle::Lei(lei))) => {
+            check_id(lei, lei.validate())?
+        },
+        Some(DerivativeUnderlying::Single(UnderlyingSingle::Index(index))) => {
+            if let Some(isin) = &index.isin {
+                check_id(isin, isin.validate())?;
+            }
+        },
+        Some(DerivativeUnderlying::Basket(basket)) => {
+            for isin in &basket.isin {
+                check_id(isin, isin.validate())?;
+            }
+            for lei in &basket.issuer_lei {
+                check_id(lei, lei.validate())?;
+            }
+        },
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Check that a currency code is an active ISO 4217 code.
+fn check_ccy(ccy: &Currency) -> Result<(), XmlError> {
+    if ccy.is_iso() {
+        Ok(())
+    } else {
+        Err(CurrencyError::UnknownCode(ccy.to_string()).into())
+    }
+}
+
+/// Check all of the currency codes in the given [`ReferenceData`], returning an error for the
+/// first unknown currency code found.
+fn check_currencies(ref_data: &ReferenceData) -> Result<(), XmlError> {
+    check_ccy(&ref_data.notional_currency)?;
+    if let Some(debt_attrs) = &ref_data.debt_attributes {
+        check_ccy(&debt_attrs.nominal_currency)?;
+    }
+    if let Some(deriv_attrs) = &ref_data.derivative_attributes {
+        if let Some(ccy) = deriv_attrs.strike_price.as_ref().and_then(|p| p.currency.as_ref()) {
+            check_ccy(ccy)?;
+        }
+        if let Some(ac_attrs) = &deriv_attrs.asset_class_specific_attributes {
+            let ir_ccy = ac_attrs.ir_attributes.as_ref().and_then(|a| a.notional_currency_2.as_ref());
+            let fx_ccy = ac_attrs.fx_attributes.as_ref().and_then(|a| a.notional_currency_2.as_ref());
+            for ccy in ir_ccy.into_iter().chain(fx_ccy) {
+                check_ccy(ccy)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub struct IterRefData<'a> {
     xml_iterator: XmlIterator<'a, BufReader<File>>,
+    strictness: Strictness,
 }
 
 impl<'a> IterRefData<'a> {
-    pub(crate) fn new(path: &Path) -> Result<Self, XmlError> {
+    /// Iterate over the `RefData` records in the XML file at the given path. Invalid identifiers
+    /// are accepted unless strict parsing is requested using [`IterRefData::with_strictness`].
+    pub fn new(path: &Path) -> Result<Self, XmlError> {
         Ok(Self {
             xml_iterator: XmlIterator::from_file(vec!["RefData"], path)?,
+            strictness: Strictness::default(),
         })
     }
+
+    /// Set how invalid identifiers in the data are handled (see [`Strictness`]).
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
 }
 
 impl Iterator for IterRefData<'_> {
@@ -30,7 +132,14 @@ impl Iterator for IterRefData<'_> {
     
     fn next(&mut self) -> Option<Self::Item> {
         match self.xml_iterator.next()? {
-            Ok(elem) => Some(ReferenceData::from_xml(&elem)),
+            Ok(elem) => Some(ReferenceData::from_xml(&elem).and_then(|ref_data| {
+                if self.strictness == Strictness::Strict {
+                    check_identifiers(&ref_data)?;
+                    check_currencies(&ref_data)?;
+                    ref_data.cfi.validate()?;
+                }
+                Ok(ref_data)
+            })),
             Err(e) => Some(Err(e)),
         }
     }